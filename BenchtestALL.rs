@@ -882,6 +882,7 @@ fn rsa2048(mut rng: &mut RAND) {
 		let elapsed = start.elapsed();
 		dur = (elapsed.as_secs() * 1_000) + (elapsed.subsec_nanos() / 1_000_000) as u64;
 	}
+	rsa::private_key_countermeasures(&mut prv, false, false);
 	let duration = (dur as f64) / (iterations as f64);
 	print!("RSA gen - {:} iterations  ", iterations);
 	println!(" {:0.2} ms per iteration", duration);
@@ -907,7 +908,7 @@ fn rsa2048(mut rng: &mut RAND) {
 	let mut iterations = 0;
 	let mut dur = 0 as u64;
 	while dur < (MIN_TIME as u64) * 1000 || iterations < MIN_ITERS {
-		rsa::decrypt(&prv, &c, &mut p);
+		rsa::decrypt(&prv, &mut rng, &c, &mut p);
 		iterations += 1;
 		let elapsed = start.elapsed();
 		dur = (elapsed.as_secs() * 1_000) + (elapsed.subsec_nanos() / 1_000_000) as u64;
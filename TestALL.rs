@@ -1401,7 +1401,7 @@ fn rsa_2048(mut rng: &mut RAND) {
     printbinary(&c);
 
     println!("Decrypting test string");
-    rsa::decrypt(&prv, &c, &mut ml);
+    rsa::decrypt(&prv, &mut rng, &c, &mut ml);
     let mlen = rsa::oaep_decode(sha, None, &mut ml); /* OAEP decode message  */
 
     let mess = str::from_utf8(&ml[0..mlen]).unwrap();
@@ -1410,7 +1410,7 @@ fn rsa_2048(mut rng: &mut RAND) {
     println!("Signing message");
     rsa::pkcs15(sha, message, &mut c);
 
-    rsa::decrypt(&prv, &c, &mut s); /* create signature in S */
+    rsa::decrypt(&prv, &mut rng, &c, &mut s); /* create signature in S */
 
     print!("Signature= 0x");
     printbinary(&s);
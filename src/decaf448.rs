@@ -0,0 +1,461 @@
+/*
+Licensed to the Apache Software Foundation (ASF) under one
+or more contributor license agreements.  See the NOTICE file
+distributed with this work for additional information
+regarding copyright ownership.  The ASF licenses this file
+to you under the Apache License, Version 2.0 (the
+"License"); you may not use this file except in compliance
+with the License.  You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing,
+software distributed under the License is distributed on an
+"AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+KIND, either express or implied.  See the License for the
+specific language governing permissions and limitations
+under the License.
+*/
+
+/* RFC 9496 Decaf448 prime order group, on top of the goldilocks curve.
+Group elements are ECP points, operated on with the usual ECP methods, and must only
+be encoded, decoded and compared with the functions here */
+
+use super::big;
+use super::big::Big;
+use super::dbig::DBig;
+use super::ecp::ECP;
+use super::fp::FP;
+use super::rom;
+
+use rand::RAND;
+
+/// Size of an encoded element and of a scalar
+pub const EFS: usize = big::MODBYTES;
+pub const EGS: usize = big::MODBYTES;
+/// Input size of the one-way map
+pub const UNIFORM_BYTES: usize = 2 * EFS;
+
+const SQRT_MINUS_D: &str = "22d962fbeb24f7683bf68d722fa26aa0a1f1a7b8a5b8d54b64a2d780968c14ba839a66f4fd6eded260337bf6aa20ce529642ef0f45572736";
+const INVSQRT_MINUS_D: &str = "6ef40652e222c057902be35a0bcac8075a90950c3a5b27a7d6ba56f128a6521abe707ee2c21fba15efbb2479f19e94f353afbb5eb878682c";
+const ONE_MINUS_D: isize = 39082;
+const ONE_MINUS_TWO_D: isize = 78163;
+
+fn constant(hex: &str) -> FP {
+    FP::new_big(&Big::fromstring(hex.to_string()))
+}
+
+/// Low bit of the canonical encoding
+fn is_negative(x: &FP) -> isize {
+    FP::new_copy(x).redc().parity()
+}
+
+/// Absolute value, the non-negative one of x and -x
+fn ct_abs(x: &mut FP) {
+    let mut n = FP::new_copy(x);
+    n.neg();
+    n.norm();
+    let d = is_negative(x);
+    x.cmove(&n, d);
+    x.reduce();
+}
+
+/// (was_square, r) where r = +sqrt(u/v) when u/v is square
+fn sqrt_ratio_m1(u: &FP, v: &FP) -> (bool, FP) {
+    let mut e = Big::new_ints(&rom::MODULUS);
+    e.dec(3);
+    e.norm();
+    e.shr(2);
+    let mut uv = FP::new_copy(u);
+    uv.mul(v);
+    let mut r = uv.pow(&mut e);
+    r.mul(u);
+
+    let mut check = FP::new_copy(&r);
+    check.sqr();
+    check.mul(v);
+    let was_square = check.equals(u);
+    ct_abs(&mut r);
+    (was_square, r)
+}
+
+/// The Decaf448 generator
+pub fn generator() -> ECP {
+    ECP::generator()
+}
+
+/// Canonical encoding of an element
+#[allow(non_snake_case)]
+pub fn encode(P: &ECP, b: &mut [u8]) {
+    let x0 = FP::new_big(&P.getx());
+    let y0 = FP::new_big(&P.gety());
+    let one = FP::new_int(1);
+    let mut t0 = FP::new_copy(&x0);
+    t0.mul(&y0);
+
+    let mut u1 = FP::new_copy(&x0);
+    u1.add(&t0);
+    u1.norm();
+    let mut w = FP::new_copy(&x0);
+    w.sub(&t0);
+    w.norm();
+    u1.mul(&w);
+
+    let mut w = FP::new_copy(&x0);
+    w.sqr();
+    w.mul(&u1);
+    w.imul(ONE_MINUS_D);
+    let (_, invsqrt) = sqrt_ratio_m1(&one, &w);
+
+    let mut ratio = FP::new_copy(&invsqrt);
+    ratio.mul(&u1);
+    ratio.mul(&constant(SQRT_MINUS_D));
+    ct_abs(&mut ratio);
+    let mut u2 = constant(INVSQRT_MINUS_D);
+    u2.mul(&ratio);
+    u2.sub(&t0);
+    u2.norm();
+
+    let mut s = FP::new_copy(&invsqrt);
+    s.imul(ONE_MINUS_D);
+    s.mul(&x0);
+    s.mul(&u2);
+    ct_abs(&mut s);
+    scalar_to_bytes(&s.redc(), b);
+}
+
+/// Decode an element, rejecting non-canonical and invalid encodings
+pub fn decode(b: &[u8]) -> Option<ECP> {
+    if b.len() != EFS {
+        return None;
+    }
+    let mut t: [u8; EFS] = [0; EFS];
+    for (i, c) in t.iter_mut().rev().enumerate() {
+        *c = b[i];
+    }
+    let sb = Big::frombytes(&t);
+    if Big::comp(&sb, &Big::new_ints(&rom::MODULUS)) >= 0 || sb.parity() == 1 {
+        return None;
+    }
+    let s = FP::new_big(&sb);
+    let one = FP::new_int(1);
+    let mut ss = FP::new_copy(&s);
+    ss.sqr();
+    let mut u1 = FP::new_copy(&one);
+    u1.add(&ss);
+    u1.norm();
+    let mut u1_sqr = FP::new_copy(&u1);
+    u1_sqr.sqr();
+
+    // u2 = u1^2 - 4.d.s^2, with d = -39081
+    let mut u2 = FP::new_copy(&ss);
+    u2.imul(4 * 39081);
+    u2.add(&u1_sqr);
+    u2.norm();
+
+    let mut w = FP::new_copy(&u2);
+    w.mul(&u1_sqr);
+    let (was_square, invsqrt) = sqrt_ratio_m1(&one, &w);
+    if !was_square {
+        return None;
+    }
+
+    let mut u3 = FP::new_copy(&s);
+    u3.add(&s);
+    u3.norm();
+    u3.mul(&invsqrt);
+    u3.mul(&u1);
+    u3.mul(&constant(SQRT_MINUS_D));
+    ct_abs(&mut u3);
+
+    let mut x = FP::new_copy(&u3);
+    x.mul(&invsqrt);
+    x.mul(&u2);
+    x.mul(&constant(INVSQRT_MINUS_D));
+    let mut y = FP::new_copy(&one);
+    y.sub(&ss);
+    y.norm();
+    y.mul(&invsqrt);
+    y.mul(&u1);
+    Some(ECP::new_bigs(&x.redc(), &y.redc()))
+}
+
+/// Equality of elements, which may be represented by different curve points
+#[allow(non_snake_case)]
+pub fn equals(P: &ECP, Q: &ECP) -> bool {
+    let mut a = FP::new_big(&P.getx());
+    a.mul(&FP::new_big(&Q.gety()));
+    let mut b = FP::new_big(&P.gety());
+    b.mul(&FP::new_big(&Q.getx()));
+    a.equals(&b)
+}
+
+/// Map a field element to a curve point, the element derivation function of RFC 9496
+fn map(t: &FP) -> ECP {
+    let one = FP::new_int(1);
+    let d = FP::new_big(&Big::new_ints(&rom::CURVE_B));
+    let mut r = FP::new_copy(t);
+    r.sqr();
+    r.neg();
+    r.norm();
+
+    let mut rm1 = FP::new_copy(&r);
+    rm1.sub(&one);
+    rm1.norm();
+    let mut rp1 = FP::new_copy(&r);
+    rp1.add(&one);
+    rp1.norm();
+
+    // u1 = (u0 + 1).(u0 - r) where u0 = d.(r - 1)
+    let mut u0 = FP::new_copy(&d);
+    u0.mul(&rm1);
+    let mut u1 = FP::new_copy(&u0);
+    u1.add(&one);
+    u1.norm();
+    let mut w = FP::new_copy(&u0);
+    w.sub(&r);
+    w.norm();
+    u1.mul(&w);
+
+    let mut w = FP::new_copy(&rp1);
+    w.mul(&u1);
+    let (was_square, v) = sqrt_ratio_m1(&FP::new_int(ONE_MINUS_TWO_D), &w);
+    let mut v_prime = FP::new_copy(t);
+    v_prime.mul(&v);
+    v_prime.cmove(&v, was_square as isize);
+    let mut sgn = FP::new_copy(&one);
+    sgn.neg();
+    sgn.norm();
+    sgn.cmove(&one, was_square as isize);
+
+    let mut s = FP::new_copy(&v_prime);
+    s.mul(&rp1);
+    let mut ss = FP::new_copy(&s);
+    ss.sqr();
+
+    let mut w0 = FP::new_copy(&s);
+    ct_abs(&mut w0);
+    w0.dbl();
+    w0.norm();
+    let mut w1 = FP::new_copy(&ss);
+    w1.add(&one);
+    w1.norm();
+    let mut w2 = FP::new_copy(&ss);
+    w2.sub(&one);
+    w2.norm();
+    let mut w3 = FP::new_copy(&v_prime);
+    w3.mul(&s);
+    w3.mul(&rm1);
+    w3.imul(ONE_MINUS_TWO_D);
+    w3.add(&sgn);
+    w3.norm();
+
+    // affine x = w0/w1, y = w2/w3
+    w1.inverse();
+    w0.mul(&w1);
+    w3.inverse();
+    w2.mul(&w3);
+    ECP::new_bigs(&w0.redc(), &w2.redc())
+}
+
+/// One-way map from 112 uniformly random bytes to an element. Used as hash-to-group
+/// on the output of an extendable output function such as SHAKE256
+#[allow(non_snake_case)]
+pub fn from_uniform_bytes(b: &[u8]) -> ECP {
+    let p = Big::new_ints(&rom::MODULUS);
+    let mut t: [u8; EFS] = [0; EFS];
+    let mut P = ECP::new();
+    for half in b[0..UNIFORM_BYTES].chunks(EFS) {
+        for (i, c) in t.iter_mut().rev().enumerate() {
+            *c = half[i];
+        }
+        let mut x = Big::frombytes(&t);
+        x.rmod(&p);
+        P.add(&map(&FP::new_big(&x)));
+    }
+    P
+}
+
+/// Decode a canonical little-endian scalar, which must be less than the group order
+pub fn scalar_from_bytes(b: &[u8]) -> Option<Big> {
+    if b.len() != EGS {
+        return None;
+    }
+    let mut t: [u8; EGS] = [0; EGS];
+    for (i, c) in t.iter_mut().rev().enumerate() {
+        *c = b[i];
+    }
+    let s = Big::frombytes(&t);
+    if Big::comp(&s, &Big::new_ints(&rom::CURVE_ORDER)) >= 0 {
+        return None;
+    }
+    Some(s)
+}
+
+/// Encode a scalar as EGS little-endian bytes
+pub fn scalar_to_bytes(s: &Big, b: &mut [u8]) {
+    let mut t: [u8; EGS] = [0; EGS];
+    Big::new_copy(s).tobytes(&mut t);
+    for (i, c) in t.iter().rev().enumerate() {
+        b[i] = *c;
+    }
+}
+
+/// Reduce 112 uniformly random little-endian bytes to a scalar
+pub fn scalar_from_uniform_bytes(b: &[u8]) -> Big {
+    let mut t: [u8; UNIFORM_BYTES] = [0; UNIFORM_BYTES];
+    for (i, c) in t.iter_mut().rev().enumerate() {
+        *c = b[i];
+    }
+    DBig::frombytes(&t).dmod(&Big::new_ints(&rom::CURVE_ORDER))
+}
+
+/// Random non-zero scalar
+pub fn random_scalar(rng: &mut RAND) -> Big {
+    let r = Big::new_ints(&rom::CURVE_ORDER);
+    loop {
+        let s = Big::randomnum(&r, rng);
+        if !s.iszilch() {
+            return s;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // From https://www.rfc-editor.org/rfc/rfc9496#appendix-B.1, multiples of the generator
+    const MULTIPLES: [&str; 16] = [
+        "0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+        "6666666666666666666666666666666666666666666666666666666633333333333333333333333333333333333333333333333333333333",
+        "c898eb4f87f97c564c6fd61fc7e49689314a1f818ec85eeb3bd5514ac816d38778f69ef347a89fca817e66defdedce178c7cc709b2116e75",
+        "a0c09bf2ba7208fda0f4bfe3d0f5b29a543012306d43831b5adc6fe7f8596fa308763db15468323b11cf6e4aeb8c18fe44678f44545a69bc",
+        "b46f1836aa287c0a5a5653f0ec5ef9e903f436e21c1570c29ad9e5f596da97eeaf17150ae30bcb3174d04bc2d712c8c7789d7cb4fda138f4",
+        "1c5bbecf4741dfaae79db72dface00eaaac502c2060934b6eaaeca6a20bd3da9e0be8777f7d02033d1b15884232281a41fc7f80eed04af5e",
+        "86ff0182d40f7f9edb7862515821bd67bfd6165a3c44de95d7df79b8779ccf6460e3c68b70c16aaa280f2d7b3f22d745b97a89906cfc476c",
+        "502bcb6842eb06f0e49032bae87c554c031d6d4d2d7694efbf9c468d48220c50f8ca28843364d70cee92d6fe246e61448f9db9808b3b2408",
+        "0c9810f1e2ebd389caa789374d78007974ef4d17227316f40e578b336827da3f6b482a4794eb6a3975b971b5e1388f52e91ea2f1bcb0f912",
+        "20d41d85a18d5657a29640321563bbd04c2ffbd0a37a7ba43a4f7d263ce26faf4e1f74f9f4b590c69229ae571fe37fa639b5b8eb48bd9a55",
+        "e6b4b8f408c7010d0601e7eda0c309a1a42720d6d06b5759fdc4e1efe22d076d6c44d42f508d67be462914d28b8edce32e7094305164af17",
+        "be88bbb86c59c13d8e9d09ab98105f69c2d1dd134dbcd3b0863658f53159db64c0e139d180f3c89b8296d0ae324419c06fa87fc7daaf34c1",
+        "a456f9369769e8f08902124a0314c7a06537a06e32411f4f93415950a17badfa7442b6217434a3a05ef45be5f10bd7b2ef8ea00c431edec5",
+        "186e452c4466aa4383b4c00210d52e7922dbf9771e8b47e229a9b7b73c8d10fd7ef0b6e41530f91f24a3ed9ab71fa38b98b2fe4746d51d68",
+        "4ae7fdcae9453f195a8ead5cbe1a7b9699673b52c40ab27927464887be53237f7f3a21b938d40d0ec9e15b1d5130b13ffed81373a53e2b43",
+        "841981c3bfeec3f60cfeca75d9d8dc17f46cf0106f2422b59aec580a58f342272e3a5e575a055ddb051390c54c24c6ecb1e0aceb075f6056",
+    ];
+
+    // Invalid encodings
+    const BAD: [&str; 11] = [
+        // From https://www.rfc-editor.org/rfc/rfc9496#appendix-B.2, non-canonical field encodings
+        "8e24f838059ee9fef1e209126defe53dcd74ef9b6304601c6966099effffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        "86fcc7212bd4a0b980928666dc28c444a605ef38e09fb569e28d4443ffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        "866d54bd4c4ff41a55d4eefdbeca73cbd653c7bd3135b383708ec0bdffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        "4a380ccdab9c86364a89e77a464d64f9157538cfdfa686adc0d5ded0ffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        "f22d9d4c945dd44d11e0b1d3d3d358d959b4844d83b08c44e659d79fffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        "8cdffc681aa99e9c818c8ef4c3808b58e86acdef1ab68c8443f5c0f4ffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        // p, a non-canonical zero
+        "fffffffffffffffffffffffffffffffffffffffffffffffffffffffffeffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        // Negative field elements, s = 1 and s = p - 2
+        "0100000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+        "fdfffffffffffffffffffffffffffffffffffffffffffffffffffffffeffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        // Non-square x^2, for s = -1 and another even s
+        "fefffffffffffffffffffffffffffffffffffffffffffffffffffffffeffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        "f4b165224a58b791df6af1d8303e61cdc4bb86c3d1c427103c344c4189eb2f1e7bd5d47e446fcec2a3d811736110e5781bcccea696762e61",
+    ];
+
+    // Appendix B.3, one-way map
+    const UNIFORM: [(&str, &str); 7] = [
+        (
+            "cbb8c991fd2f0b7e1913462d6463e4fd2ce4ccdd28274dc2ca1f4165d5ee6cdccea57be3416e166fd06718a31af45a2f8e987e301be59ae6673e963001dbbda80df47014a21a26d6c7eb4ebe0312aa6fffb8d1b26bc62ca40ed51f8057a635a02c2b8c83f48fa6a2d70f58a1185902c0",
+            "0c709c9607dbb01c94513358745b7c23953d03b33e39c7234e268d1d6e24f34014ccbc2216b965dd231d5327e591dc3c0e8844ccfd568848",
+        ),
+        (
+            "b6d8da654b13c3101d6634a231569e6b85961c3f4b460a08ac4a5857069576b64428676584baa45b97701be6d0b0ba18ac28d443403b45699ea0fbd1164f5893d39ad8f29e48e399aec5902508ea95e33bc1e9e4620489d684eb5c26bc1ad1e09aba61fabc2cdfee0b6b6862ffc8e55a",
+            "76ab794e28ff1224c727fa1016bf7f1d329260b7218a39aea2fdb17d8bd9119017b093d641cedf74328c327184dc6f2a64bd90eddccfcdab",
+        ),
+        (
+            "36a69976c3e5d74e4904776993cbac27d10f25f5626dd45c51d15dcf7b3e6a5446a6649ec912a56895d6baa9dc395ce9e34b868d9fb2c1fc72eb6495702ea4f446c9b7a188a4e0826b1506b0747a6709f37988ff1aeb5e3788d5076ccbb01a4bc6623c92ff147a1e21b29cc3fdd0e0f4",
+            "c8d7ac384143500e50890a1c25d643343accce584caf2544f9249b2bf4a6921082be0e7f3669bb5ec24535e6c45621e1f6dec676edd8b664",
+        ),
+        (
+            "d5938acbba432ecd5617c555a6a777734494f176259bff9dab844c81aadcf8f7abd1a9001d89c7008c1957272c1786a4293bb0ee7cb37cf3988e2513b14e1b75249a5343643d3c5e5545a0c1a2a4d3c685927c38bc5e5879d68745464e2589e000b31301f1dfb7471a4f1300d6fd0f99",
+            "62beffc6b8ee11ccd79dbaac8f0252c750eb052b192f41eeecb12f2979713b563caf7d22588eca5e80995241ef963e7ad7cb7962f343a973",
+        ),
+        (
+            "4dec58199a35f531a5f0a9f71a53376d7b4bdd6bbd2904234a8ea65bbacbce2a542291378157a8f4be7b6a092672a34d85e473b26ccfbd4cdc6739783dc3f4f6ee3537b7aed81df898c7ea0ae89a15b5559596c2a5eeacf8b2b362f3db2940e3798b63203cae77c4683ebaed71533e51",
+            "f4ccb31d263731ab88bed634304956d2603174c66da38742053fa37dd902346c3862155d68db63be87439e3d68758ad7268e239d39c4fd3b",
+        ),
+        (
+            "df2aa1536abb4acab26efa538ce07fd7bca921b13e17bc5ebcba7d1b6b733deda1d04c220f6b5ab35c61b6bcb15808251cab909a01465b8ae3fc770850c66246d5a9eae9e2877e0826e2b8dc1bc08009590bc6778a84e919fbd28e02a0f9c49b48dc689eb5d5d922dc01469968ee81b5",
+            "7e79b00e8e0a76a67c0040f62713b8b8c6d6f05e9c6d02592e8a22ea896f5deacc7c7df5ed42beae6fedb9000285b482aa504e279fd49c32",
+        ),
+        (
+            "e9fb440282e07145f1f7f5ecf3c273212cd3d26b836b41b02f108431488e5e84bd15f2418b3d92a3380dd66a374645c2a995976a015632d36a6c2189f202fc766e1c82f50ad9189be190a1f0e8f9b9e69c9c18cc98fdd885608f68bf0fdedd7b894081a63f70016a8abf04953affbefa",
+            "20b171cb16be977f15e013b9752cf86c54c631c4fc8cbf7c03c4d3ac9b8e8640e7b0e9300b987fe0ab5044669314f6ed1650ae037db853f1",
+        ),
+    ];
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_multiples() {
+        let G = generator();
+        let mut P = ECP::new();
+        let mut b: [u8; EFS] = [0; EFS];
+        for v in MULTIPLES.iter() {
+            encode(&P, &mut b);
+            assert_eq!(hex::encode(&b[..]), *v);
+            let Q = decode(&b).unwrap();
+            assert!(equals(&P, &Q));
+            P.add(&G);
+        }
+    }
+
+    #[test]
+    fn test_bad_encodings() {
+        for v in BAD.iter() {
+            assert!(decode(&hex::decode(v).unwrap()).is_none());
+        }
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_from_uniform_bytes() {
+        let mut b: [u8; EFS] = [0; EFS];
+        for v in UNIFORM.iter() {
+            let P = from_uniform_bytes(&hex::decode(v.0).unwrap());
+            encode(&P, &mut b);
+            assert_eq!(hex::encode(&b[..]), v.1);
+        }
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_torsion_equality() {
+        // adding the 2-torsion point (0,-1) gives the same element
+        let G = generator();
+        let mut m1 = Big::new_ints(&rom::MODULUS);
+        m1.dec(1);
+        m1.norm();
+        let T = ECP::new_bigs(&Big::new(), &m1);
+        assert!(!T.is_infinity());
+        let mut P = G.mul(&Big::new_int(7));
+        let mut Q = ECP::new();
+        Q.copy(&P);
+        Q.add(&T);
+        assert!(!P.equals(&Q));
+        assert!(equals(&P, &Q));
+        let mut b1: [u8; EFS] = [0; EFS];
+        let mut b2: [u8; EFS] = [0; EFS];
+        encode(&P, &mut b1);
+        encode(&Q, &mut b2);
+        assert_eq!(b1, b2);
+        P.add(&G);
+        assert!(!equals(&P, &Q));
+
+        let mut s: [u8; EGS] = [0; EGS];
+        let l = Big::new_ints(&rom::CURVE_ORDER);
+        scalar_to_bytes(&l, &mut s);
+        assert!(scalar_from_bytes(&s).is_none());
+    }
+}
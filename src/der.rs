@@ -0,0 +1,300 @@
+/*
+Licensed to the Apache Software Foundation (ASF) under one
+or more contributor license agreements.  See the NOTICE file
+distributed with this work for additional information
+regarding copyright ownership.  The ASF licenses this file
+to you under the Apache License, Version 2.0 (the
+"License"); you may not use this file except in compliance
+with the License.  You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing,
+software distributed under the License is distributed on an
+"AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+KIND, either express or implied.  See the License for the
+specific language governing permissions and limitations
+under the License.
+*/
+
+/* Minimal ASN.1 DER encoder and strict decoder */
+
+pub const INTEGER: u8 = 0x02;
+pub const BIT_STRING: u8 = 0x03;
+pub const OCTET_STRING: u8 = 0x04;
+pub const NULL: u8 = 0x05;
+pub const OID: u8 = 0x06;
+pub const SEQUENCE: u8 = 0x30;
+/// Constructed context-specific tags [0] and [1]
+pub const CONTEXT_0: u8 = 0xa0;
+pub const CONTEXT_1: u8 = 0xa1;
+
+/// Encode a definite length in the shortest form
+pub fn encode_length(len: usize, out: &mut Vec<u8>) {
+    if len < 0x80 {
+        out.push(len as u8);
+        return;
+    }
+    let mut n = 0;
+    let mut l = len;
+    while l > 0 {
+        n += 1;
+        l >>= 8;
+    }
+    out.push(0x80 | n as u8);
+    for i in (0..n).rev() {
+        out.push((len >> (8 * i)) as u8);
+    }
+}
+
+/// Append tag, length and value
+pub fn encode_tlv(tag: u8, value: &[u8], out: &mut Vec<u8>) {
+    out.push(tag);
+    encode_length(value.len(), out);
+    out.extend_from_slice(value);
+}
+
+/// Append an INTEGER from an unsigned big-endian magnitude, in minimal two's complement form
+pub fn encode_integer(mag: &[u8], out: &mut Vec<u8>) {
+    let mut i = 0;
+    while i < mag.len() && mag[i] == 0 {
+        i += 1;
+    }
+    let mut v: Vec<u8> = Vec::new();
+    if i == mag.len() || mag[i] & 0x80 != 0 {
+        v.push(0);
+    }
+    v.extend_from_slice(&mag[i..]);
+    encode_tlv(INTEGER, &v, out);
+}
+
+/// Append a SEQUENCE wrapping already encoded content
+pub fn encode_sequence(content: &[u8], out: &mut Vec<u8>) {
+    encode_tlv(SEQUENCE, content, out);
+}
+
+/// Append a BIT STRING holding whole octets
+pub fn encode_bit_string(bits: &[u8], out: &mut Vec<u8>) {
+    out.push(BIT_STRING);
+    encode_length(bits.len() + 1, out);
+    out.push(0);
+    out.extend_from_slice(bits);
+}
+
+/// Strict DER reader. Every read fails on indefinite or non-minimal lengths
+pub struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Reader<'a> {
+        Reader { data, pos: 0 }
+    }
+
+    /// true when all input has been consumed
+    pub fn is_empty(&self) -> bool {
+        self.pos == self.data.len()
+    }
+
+    /// tag of the next element, if any
+    pub fn peek(&self) -> Option<u8> {
+        if self.is_empty() {
+            return None;
+        }
+        Some(self.data[self.pos])
+    }
+
+    fn read_length(&mut self) -> Option<usize> {
+        if self.pos >= self.data.len() {
+            return None;
+        }
+        let b = self.data[self.pos];
+        self.pos += 1;
+        if b < 0x80 {
+            return Some(b as usize);
+        }
+        let n = (b & 0x7f) as usize;
+        if n == 0 || n > 4 || self.pos + n > self.data.len() {
+            return None;
+        }
+        if self.data[self.pos] == 0 {
+            return None;
+        }
+        let mut len = 0;
+        for i in 0..n {
+            len = (len << 8) | self.data[self.pos + i] as usize;
+        }
+        self.pos += n;
+        if len < 0x80 {
+            return None;
+        }
+        Some(len)
+    }
+
+    /// Read any element, returning its tag and value
+    pub fn read_any(&mut self) -> Option<(u8, &'a [u8])> {
+        if self.is_empty() {
+            return None;
+        }
+        let start = self.pos;
+        let tag = self.data[self.pos];
+        self.pos += 1;
+        let len = match self.read_length() {
+            Some(l) => l,
+            None => {
+                self.pos = start;
+                return None;
+            }
+        };
+        if len > self.data.len() - self.pos {
+            self.pos = start;
+            return None;
+        }
+        let v = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Some((tag, v))
+    }
+
+    /// Read an element with the expected tag, returning its value
+    pub fn read(&mut self, tag: u8) -> Option<&'a [u8]> {
+        let start = self.pos;
+        match self.read_any() {
+            Some((t, v)) if t == tag => Some(v),
+            _ => {
+                self.pos = start;
+                None
+            }
+        }
+    }
+
+    /// Read a non-negative, minimally encoded INTEGER, returning its magnitude without sign byte
+    pub fn read_integer(&mut self) -> Option<&'a [u8]> {
+        let start = self.pos;
+        let v = self.read(INTEGER)?;
+        if v.is_empty() || v[0] & 0x80 != 0 || (v.len() > 1 && v[0] == 0 && v[1] & 0x80 == 0) {
+            self.pos = start;
+            return None;
+        }
+        if v.len() > 1 && v[0] == 0 {
+            return Some(&v[1..]);
+        }
+        Some(v)
+    }
+
+    /// Read a small non-negative INTEGER
+    pub fn read_small_integer(&mut self) -> Option<usize> {
+        let start = self.pos;
+        let v = self.read_integer()?;
+        if v.len() > 4 {
+            self.pos = start;
+            return None;
+        }
+        let mut n = 0;
+        for b in v {
+            n = (n << 8) | *b as usize;
+        }
+        Some(n)
+    }
+
+    /// Read a BIT STRING of whole octets, returning them without the unused bits count
+    pub fn read_bit_string(&mut self) -> Option<&'a [u8]> {
+        let start = self.pos;
+        let v = self.read(BIT_STRING)?;
+        if v.is_empty() || v[0] != 0 {
+            self.pos = start;
+            return None;
+        }
+        Some(&v[1..])
+    }
+
+    /// Read a SEQUENCE, returning a reader over its content
+    pub fn read_sequence(&mut self) -> Option<Reader<'a>> {
+        let v = self.read(SEQUENCE)?;
+        Some(Reader::new(v))
+    }
+}
+
+/// Copy an unsigned magnitude into a fixed-width big-endian buffer. Fails if it does not fit
+pub fn to_fixed(mag: &[u8], out: &mut [u8]) -> bool {
+    if mag.len() > out.len() {
+        return false;
+    }
+    let d = out.len() - mag.len();
+    for b in out[0..d].iter_mut() {
+        *b = 0;
+    }
+    out[d..].copy_from_slice(mag);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integer_encoding() {
+        let mut out = Vec::new();
+        encode_integer(&[0x00, 0x00, 0x7f], &mut out);
+        assert_eq!(out, [0x02, 0x01, 0x7f]);
+
+        out.clear();
+        encode_integer(&[0x80], &mut out);
+        assert_eq!(out, [0x02, 0x02, 0x00, 0x80]);
+
+        out.clear();
+        encode_integer(&[0x00, 0x00], &mut out);
+        assert_eq!(out, [0x02, 0x01, 0x00]);
+
+        let mut r = Reader::new(&out);
+        assert_eq!(r.read_integer(), Some(&[0x00][..]));
+        assert!(r.is_empty());
+    }
+
+    #[test]
+    fn test_long_length() {
+        let mut out = Vec::new();
+        let v = vec![0xab; 300];
+        encode_tlv(OCTET_STRING, &v, &mut out);
+        assert_eq!(out[0..4], [0x04, 0x82, 0x01, 0x2c]);
+
+        let mut r = Reader::new(&out);
+        assert_eq!(r.read(OCTET_STRING), Some(&v[..]));
+        assert!(r.is_empty());
+    }
+
+    #[test]
+    fn test_strict_rejections() {
+        // non-minimal long form length
+        let mut r = Reader::new(&[0x02, 0x81, 0x01, 0x01]);
+        assert_eq!(r.read_integer(), None);
+        // indefinite length
+        let mut r = Reader::new(&[0x30, 0x80, 0x00, 0x00]);
+        assert!(r.read_sequence().is_none());
+        // redundant leading zero
+        let mut r = Reader::new(&[0x02, 0x02, 0x00, 0x01]);
+        assert_eq!(r.read_integer(), None);
+        // negative
+        let mut r = Reader::new(&[0x02, 0x01, 0x80]);
+        assert_eq!(r.read_integer(), None);
+        // empty integer
+        let mut r = Reader::new(&[0x02, 0x00]);
+        assert_eq!(r.read_integer(), None);
+        // truncated
+        let mut r = Reader::new(&[0x04, 0x03, 0x01]);
+        assert_eq!(r.read(OCTET_STRING), None);
+        // bit string with unused bits
+        let mut r = Reader::new(&[0x03, 0x02, 0x04, 0xf0]);
+        assert_eq!(r.read_bit_string(), None);
+    }
+
+    #[test]
+    fn test_bit_string() {
+        let mut out = Vec::new();
+        encode_bit_string(&[0x04, 0x01], &mut out);
+        assert_eq!(out, [0x03, 0x03, 0x00, 0x04, 0x01]);
+        let mut r = Reader::new(&out);
+        assert_eq!(r.read_bit_string(), Some(&[0x04, 0x01][..]));
+        assert!(r.is_empty());
+    }
+}
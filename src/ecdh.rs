@@ -21,10 +21,15 @@ use super::big;
 use super::big::Big;
 use super::ecp;
 use super::ecp::ECP;
+use super::ecp::{CurveType, CURVETYPE};
 use super::rom;
+use super::sec1;
+use super::sec1::PointError;
 
 use aes;
 use aes::AES;
+use der;
+use gcm::GCM;
 use hash256::HASH256;
 use hash384::HASH384;
 use hash512::HASH512;
@@ -38,6 +43,8 @@ pub const EGS: usize = big::MODBYTES as usize;
 pub const SHA256: usize = 32;
 pub const SHA384: usize = 48;
 pub const SHA512: usize = 64;
+pub const ECIES_GCM_NONCE: usize = 12;
+pub const ECIES_GCM_TAG: usize = 16;
 
 #[allow(non_snake_case)]
 
@@ -393,8 +400,19 @@ pub fn cbc_iv0_decrypt(k: &[u8], c: &[u8]) -> Option<Vec<u8>> {
 /// and G is fixed generator.
 /// If RNG is NULL then the private key is provided externally in s
 /// otherwise it is generated randomly internally
-#[allow(non_snake_case)]
 pub fn key_pair_generate(rng: Option<&mut RAND>, s: &mut [u8], w: &mut [u8]) -> isize {
+    key_pair_generate_compressed(rng, s, w, false)
+}
+
+/// As key_pair_generate, with W written in SEC1 compressed form, EFS+1 bytes, if compress
+/// is set, and uncompressed, 2*EFS+1 bytes, otherwise
+#[allow(non_snake_case)]
+pub fn key_pair_generate_compressed(
+    rng: Option<&mut RAND>,
+    s: &mut [u8],
+    w: &mut [u8],
+    compress: bool,
+) -> isize {
     let res = 0;
     let mut sc: Big;
     let G = ECP::generator();
@@ -412,44 +430,62 @@ pub fn key_pair_generate(rng: Option<&mut RAND>, s: &mut [u8], w: &mut [u8]) ->
 
     let WP = G.mul(&sc);
 
-    WP.tobytes(w, false); // To use point compression on public keys, change to true
+    WP.tobytes(w, compress);
 
     res
 }
 
-/// Validate public key
+/// Decode a public key. Except on Montgomery curves, where keys are x coordinates only,
+/// this is sec1::decode
 #[allow(non_snake_case)]
-pub fn public_key_validate(w: &[u8]) -> isize {
-    let mut WP = ECP::frombytes(w);
-    let mut res = 0;
+fn public_key_decode(w: &[u8]) -> Result<ECP, PointError> {
+    if CURVETYPE != CurveType::Montgomery {
+        return sec1::decode(w);
+    }
+    if w.len() < EFS + 1 {
+        return Err(PointError::InvalidLength);
+    }
+    let W = ECP::frombytes(w);
+    if W.is_infinity() {
+        return Err(PointError::NotOnCurve);
+    }
+    Ok(W)
+}
+
+/// Validate public key, with the reason it is rejected
+#[allow(non_snake_case)]
+pub fn public_key_check(w: &[u8]) -> Result<(), PointError> {
+    let mut WP = public_key_decode(w)?;
 
     let r = Big::new_ints(&rom::CURVE_ORDER);
+    let q = Big::new_ints(&rom::MODULUS);
+    let nb = q.nbits();
+    let mut k = Big::new();
+    k.one();
+    k.shl((nb + 4) / 2);
+    k.add(&q);
+    k.div(&r);
+
+    while k.parity() == 0 {
+        k.shr(1);
+        WP.dbl();
+    }
 
+    if !k.isunity() {
+        WP = WP.mul(&k)
+    }
     if WP.is_infinity() {
-        res = INVALID_PUBLIC_KEY
+        return Err(PointError::SmallOrder);
     }
-    if res == 0 {
-        let q = Big::new_ints(&rom::MODULUS);
-        let nb = q.nbits();
-        let mut k = Big::new();
-        k.one();
-        k.shl((nb + 4) / 2);
-        k.add(&q);
-        k.div(&r);
-
-        while k.parity() == 0 {
-            k.shr(1);
-            WP.dbl();
-        }
+    Ok(())
+}
 
-        if !k.isunity() {
-            WP = WP.mul(&k)
-        }
-        if WP.is_infinity() {
-            res = INVALID_PUBLIC_KEY
-        }
+/// Validate public key. See public_key_check for the reason it is rejected
+pub fn public_key_validate(w: &[u8]) -> isize {
+    match public_key_check(w) {
+        Ok(()) => 0,
+        Err(_) => INVALID_PUBLIC_KEY,
     }
-    res
 }
 
 /// IEEE-1363 Diffie-Hellman online calculation Z=S.WD
@@ -460,10 +496,10 @@ pub fn ecpsvdp_dh(s: &[u8], wd: &[u8], z: &mut [u8]) -> isize {
 
     let mut sc = Big::frombytes(&s);
 
-    let mut W = ECP::frombytes(&wd);
-    if W.is_infinity() {
-        res = ERROR
-    }
+    let mut W = match public_key_decode(wd) {
+        Ok(W) => W,
+        Err(_) => return ERROR,
+    };
 
     if res == 0 {
         let r = Big::new_ints(&rom::CURVE_ORDER);
@@ -481,32 +517,19 @@ pub fn ecpsvdp_dh(s: &[u8], wd: &[u8], z: &mut [u8]) -> isize {
     res
 }
 
-/// IEEE ECDSA Signature, C and D are signature on F using private key S
+/// ECDSA signing core. Returns (c, d, recid) for hashed message fb using private key sc,
+/// where recid encodes the parity of the y coordinate of k.G and whether its x overflowed the order
 #[allow(non_snake_case)]
-pub fn ecpsp_dsa(
-    sha: usize,
-    rng: &mut RAND,
-    s: &[u8],
-    f: &[u8],
-    c: &mut [u8],
-    d: &mut [u8],
-) -> isize {
-    let mut t: [u8; EFS] = [0; EFS];
-    let mut b: [u8; big::MODBYTES as usize] = [0; big::MODBYTES as usize];
-
-    hashit(sha, f, 0, None, big::MODBYTES as usize, &mut b);
-
+fn dsa_sign(rng: &mut RAND, sc: &Big, fb: &Big) -> (Big, Big, isize) {
     let G = ECP::generator();
 
     let r = Big::new_ints(&rom::CURVE_ORDER);
 
-    let sc = Big::frombytes(s); /* s or &s? */
-    let fb = Big::frombytes(&b);
-
     let mut cb = Big::new();
     let mut db = Big::new();
     let mut tb = Big::new();
     let mut V = ECP::new();
+    let mut recid = 0;
 
     while db.iszilch() {
         let mut u = Big::randomnum(&r, rng);
@@ -520,13 +543,17 @@ pub fn ecpsp_dsa(
         if cb.iszilch() {
             continue;
         }
+        recid = V.gets();
+        if Big::comp(&vx, &r) >= 0 {
+            recid |= 2;
+        }
 
         tb.copy(&Big::modmul(&u, &w, &r));
         u.copy(&tb);
 
         u.invmodp(&r);
-        db.copy(&Big::modmul(&sc, &cb, &r));
-        db.add(&fb);
+        db.copy(&Big::modmul(sc, &cb, &r));
+        db.add(fb);
 
         tb.copy(&Big::modmul(&db, &w, &r));
         db.copy(&tb);
@@ -535,6 +562,59 @@ pub fn ecpsp_dsa(
         db.copy(&tb);
     }
 
+    /* Low-S normalisation (BIP-62). Negating d also negates the nonce point */
+    if is_high_s(&db) {
+        db = Big::modneg(&db, &r);
+        recid ^= 1;
+    }
+    (cb, db, recid)
+}
+
+/// True if d is greater than half the group order
+fn is_high_s(db: &Big) -> bool {
+    let mut hr = Big::new_ints(&rom::CURVE_ORDER);
+    hr.shr(1);
+    Big::comp(db, &hr) > 0
+}
+
+/// Check that signature component D is in the lower half of the group order
+pub fn ecdsa_sig_is_low_s(d: &[u8]) -> bool {
+    let db = Big::frombytes(d);
+    !is_high_s(&db)
+}
+
+/// Replace signature component D by n-D if it is in the upper half of the group order.
+/// Returns true if D was changed. Both forms verify, so this removes ECDSA malleability
+pub fn ecdsa_sig_normalize(d: &mut [u8]) -> bool {
+    let mut db = Big::frombytes(d);
+    if db.iszilch() || !is_high_s(&db) {
+        return false;
+    }
+    let r = Big::new_ints(&rom::CURVE_ORDER);
+    db = Big::modneg(&db, &r);
+    db.tobytes(d);
+    true
+}
+
+/// IEEE ECDSA Signature, C and D are signature on F using private key S. D is always low-S
+pub fn ecpsp_dsa(
+    sha: usize,
+    rng: &mut RAND,
+    s: &[u8],
+    f: &[u8],
+    c: &mut [u8],
+    d: &mut [u8],
+) -> isize {
+    let mut t: [u8; EFS] = [0; EFS];
+    let mut b: [u8; big::MODBYTES as usize] = [0; big::MODBYTES as usize];
+
+    hashit(sha, f, 0, None, big::MODBYTES as usize, &mut b);
+
+    let sc = Big::frombytes(s); /* s or &s? */
+    let fb = Big::frombytes(&b);
+
+    let (mut cb, mut db, _) = dsa_sign(rng, &sc, &fb);
+
     cb.tobytes(&mut t);
     for i in 0..EFS {
         c[i] = t[i]
@@ -546,14 +626,147 @@ pub fn ecpsp_dsa(
     0
 }
 
-/// IEEE1363 ECDSA Signature Verification. Signature C and D on F is verified using public key W
+/// Reduce a message digest to a Big, keeping its leftmost bytes if it is longer than the group order
+fn digest_to_big(h: &[u8]) -> Big {
+    let mut b: [u8; EFS] = [0; EFS];
+    if h.len() >= EFS {
+        b.copy_from_slice(&h[0..EFS]);
+    } else {
+        b[EFS - h.len()..].copy_from_slice(h);
+    }
+    Big::frombytes(&b)
+}
+
+/// ECDSA Signature with recovery id on message digest H using private key S.
+/// The signature is written to SIG as r||s||v (2*EFS+1 bytes, 65 for 256-bit curves),
+/// where v is the recovery id 0-3
+pub fn ecpsp_dsa_recoverable_hash(rng: &mut RAND, s: &[u8], h: &[u8], sig: &mut [u8]) -> isize {
+    if CURVETYPE != CurveType::Weierstrass {
+        return ERROR;
+    }
+    if sig.len() < 2 * EFS + 1 {
+        return ERROR;
+    }
+    let mut t: [u8; EFS] = [0; EFS];
+
+    let sc = Big::frombytes(s);
+    let fb = digest_to_big(h);
+
+    let (mut cb, mut db, recid) = dsa_sign(rng, &sc, &fb);
+
+    cb.tobytes(&mut t);
+    sig[0..EFS].copy_from_slice(&t);
+    db.tobytes(&mut t);
+    sig[EFS..2 * EFS].copy_from_slice(&t);
+    sig[2 * EFS] = recid as u8;
+    0
+}
+
+/// ECDSA Signature with recovery id on F using private key S. F is hashed with SHA256, 384 or 512.
+/// The signature is written to SIG as r||s||v, see ecpsp_dsa_recoverable_hash
+pub fn ecpsp_dsa_recoverable(
+    sha: usize,
+    rng: &mut RAND,
+    s: &[u8],
+    f: &[u8],
+    sig: &mut [u8],
+) -> isize {
+    let mut b: [u8; EFS] = [0; EFS];
+    hashit(sha, f, 0, None, EFS, &mut b);
+    ecpsp_dsa_recoverable_hash(rng, s, &b, sig)
+}
+
+/// Nonce point R of an r||s||v signature, with the recovery id selecting among the candidates
 #[allow(non_snake_case)]
+fn nonce_point(sig: &[u8]) -> Option<ECP> {
+    if sig.len() != 2 * EFS + 1 {
+        return None;
+    }
+    let mut v = sig[2 * EFS] as isize;
+    if v >= 27 {
+        v -= 27;
+    }
+    if v > 3 {
+        return None;
+    }
+    let r = Big::new_ints(&rom::CURVE_ORDER);
+    let q = Big::new_ints(&rom::MODULUS);
+    let mut x = Big::frombytes(&sig[0..EFS]);
+    if v & 2 != 0 {
+        x.add(&r);
+        x.norm();
+        if Big::comp(&x, &q) >= 0 {
+            return None;
+        }
+    }
+    let R = ECP::new_bigint(&x, v & 1);
+    if R.is_infinity() {
+        return None;
+    }
+    Some(R)
+}
+
+/// Recover the public key W from an r||s||v signature SIG on message digest H (ecrecover).
+/// v may be the raw recovery id 0-3 or carry the Ethereum offset of 27.
+/// W is written uncompressed, 2*EFS+1 bytes
+#[allow(non_snake_case)]
+pub fn ecp_recover_hash(h: &[u8], sig: &[u8], w: &mut [u8]) -> isize {
+    if CURVETYPE != CurveType::Weierstrass {
+        return ERROR;
+    }
+    let R = match nonce_point(sig) {
+        Some(R) => R,
+        None => return INVALID,
+    };
+
+    let r = Big::new_ints(&rom::CURVE_ORDER);
+
+    let cb = Big::frombytes(&sig[0..EFS]);
+    let mut db = Big::frombytes(&sig[EFS..2 * EFS]);
+
+    if cb.iszilch() || Big::comp(&cb, &r) >= 0 || db.iszilch() || Big::comp(&db, &r) >= 0 {
+        return INVALID;
+    }
+
+    let G = ECP::generator();
+    let mut fb = digest_to_big(h);
+    fb.rmod(&r);
+
+    // W = r^-1.(s.R - e.G)
+    let mut ri = Big::new_copy(&cb);
+    ri.invmodp(&r);
+    db = Big::modmul(&db, &ri, &r);
+    fb = Big::modmul(&fb, &ri, &r);
+    fb = Big::modneg(&fb, &r);
+
+    let WP = R.mul2(&db, &G, &fb);
+    if WP.is_infinity() {
+        return INVALID;
+    }
+    WP.tobytes(w, false);
+    0
+}
+
+/// Recover the public key W from an r||s||v signature SIG on F. F is hashed with SHA256, 384 or 512.
+/// See ecp_recover_hash
+pub fn ecp_recover(sha: usize, f: &[u8], sig: &[u8], w: &mut [u8]) -> isize {
+    let mut b: [u8; EFS] = [0; EFS];
+    hashit(sha, f, 0, None, EFS, &mut b);
+    ecp_recover_hash(&b, sig, w)
+}
+
+/// IEEE1363 ECDSA Signature Verification. Signature C and D on F is verified using public key W
 pub fn ecpvp_dsa(sha: usize, w: &[u8], f: &[u8], c: &[u8], d: &[u8]) -> isize {
-    let mut res = 0;
+    let mut b: [u8; EFS] = [0; EFS];
 
-    let mut b: [u8; big::MODBYTES as usize] = [0; big::MODBYTES as usize];
+    hashit(sha, f, 0, None, EFS, &mut b);
+    ecpvp_dsa_hash(w, &b, c, d)
+}
 
-    hashit(sha, f, 0, None, big::MODBYTES as usize, &mut b);
+/// ECDSA Signature Verification of C and D on message digest H using public key W
+#[allow(non_snake_case)]
+pub fn ecpvp_dsa_hash(w: &[u8], h: &[u8], c: &[u8], d: &[u8]) -> isize {
+    let mut res = 0;
 
     let G = ECP::generator();
 
@@ -561,7 +774,7 @@ pub fn ecpvp_dsa(sha: usize, w: &[u8], f: &[u8], c: &[u8], d: &[u8]) -> isize {
 
     let cb = Big::frombytes(c); /* c or &c ? */
     let mut db = Big::frombytes(d); /* d or &d ? */
-    let mut fb = Big::frombytes(&b);
+    let mut fb = digest_to_big(h);
     let mut tb = Big::new();
 
     if cb.iszilch() || Big::comp(&cb, &r) >= 0 || db.iszilch() || Big::comp(&db, &r) >= 0 {
@@ -574,10 +787,7 @@ pub fn ecpvp_dsa(sha: usize, w: &[u8], f: &[u8], c: &[u8], d: &[u8]) -> isize {
         fb.copy(&tb);
         let h2 = Big::modmul(&cb, &db, &r);
 
-        let WP = ECP::frombytes(&w);
-        if WP.is_infinity() {
-            res = ERROR;
-        } else {
+        if let Ok(WP) = public_key_decode(w) {
             let mut P = ECP::new();
             P.copy(&WP);
 
@@ -593,12 +803,206 @@ pub fn ecpvp_dsa(sha: usize, w: &[u8], f: &[u8], c: &[u8], d: &[u8]) -> isize {
                     res = INVALID
                 }
             }
+        } else {
+            res = ERROR;
         }
     }
 
     res
 }
 
+/// Strict ECDSA Signature Verification following BIP-62/BIP-146.
+/// As ecpvp_dsa, but a signature whose D is in the upper half of the group order is INVALID
+pub fn ecpvp_dsa_strict(sha: usize, w: &[u8], f: &[u8], c: &[u8], d: &[u8]) -> isize {
+    if !ecdsa_sig_is_low_s(d) {
+        return INVALID;
+    }
+    ecpvp_dsa(sha, w, f, c, d)
+}
+
+/// Batch ECDSA Signature Verification of r||s||v signatures SIG[i] on message digests H[i]
+/// using public keys W[i]. R is recovered from each signature and a single random linear
+/// combination of the verification equations is checked with one multi-scalar multiplication.
+/// If that fails, or a signature cannot be batched, the signatures are verified one by one.
+/// RES[i] gets the result for each signature, and 0 is returned only if all are valid
+#[allow(non_snake_case)]
+pub fn ecpvp_dsa_batch_hash(
+    rng: &mut RAND,
+    w: &[&[u8]],
+    h: &[&[u8]],
+    sig: &[&[u8]],
+    res: &mut [isize],
+) -> isize {
+    if CURVETYPE != CurveType::Weierstrass {
+        return ERROR;
+    }
+    let n = sig.len();
+    if w.len() != n || h.len() != n || res.len() < n {
+        return ERROR;
+    }
+
+    let r = Big::new_ints(&rom::CURVE_ORDER);
+    let mut pts: Vec<ECP> = Vec::with_capacity(2 * n + 1);
+    let mut sc: Vec<Big> = Vec::with_capacity(2 * n + 1);
+    let mut batched: Vec<usize> = Vec::with_capacity(n);
+    let mut gs = Big::new();
+
+    for i in 0..n {
+        res[i] = INVALID;
+        let R = match nonce_point(sig[i]) {
+            Some(R) => R,
+            None => {
+                if sig[i].len() >= 2 * EFS {
+                    res[i] = ecpvp_dsa_hash(w[i], h[i], &sig[i][0..EFS], &sig[i][EFS..2 * EFS]);
+                }
+                continue;
+            }
+        };
+        let cb = Big::frombytes(&sig[i][0..EFS]);
+        let mut db = Big::frombytes(&sig[i][EFS..2 * EFS]);
+        if cb.iszilch() || Big::comp(&cb, &r) >= 0 || db.iszilch() || Big::comp(&db, &r) >= 0 {
+            continue;
+        }
+        let W = match public_key_decode(w[i]) {
+            Ok(W) => W,
+            Err(_) => {
+                res[i] = ERROR;
+                continue;
+            }
+        };
+
+        // z.R = z.u1.G + z.u2.W, for a random 128-bit z
+        let mut zb: [u8; EFS] = [0; EFS];
+        for b in zb.iter_mut().skip(EFS - 16) {
+            *b = rng.getbyte();
+        }
+        let mut z = Big::frombytes(&zb);
+        if z.iszilch() {
+            z.one();
+        }
+        let mut fb = digest_to_big(h[i]);
+        fb.rmod(&r);
+        db.invmodp(&r);
+        let u1 = Big::modmul(&fb, &db, &r);
+        let u2 = Big::modmul(&cb, &db, &r);
+
+        gs.add(&Big::modmul(&z, &u1, &r));
+        gs.rmod(&r);
+        pts.push(R);
+        sc.push(z);
+        pts.push(W);
+        sc.push(Big::modneg(&Big::modmul(&z, &u2, &r), &r));
+        batched.push(i);
+    }
+
+    if !batched.is_empty() {
+        pts.push(ECP::generator());
+        sc.push(Big::modneg(&gs, &r));
+        if ECP::muln(&pts, &sc).is_infinity() {
+            for &i in batched.iter() {
+                res[i] = 0;
+            }
+        } else {
+            for &i in batched.iter() {
+                res[i] = ecpvp_dsa_hash(w[i], h[i], &sig[i][0..EFS], &sig[i][EFS..2 * EFS]);
+            }
+        }
+    }
+
+    if res[0..n].iter().all(|&x| x == 0) {
+        0
+    } else {
+        INVALID
+    }
+}
+
+/// Batch ECDSA Signature Verification of r||s||v signatures SIG[i] on messages F[i].
+/// The messages are hashed with SHA256, 384 or 512. See ecpvp_dsa_batch_hash
+pub fn ecpvp_dsa_batch(
+    sha: usize,
+    rng: &mut RAND,
+    w: &[&[u8]],
+    f: &[&[u8]],
+    sig: &[&[u8]],
+    res: &mut [isize],
+) -> isize {
+    let mut hs: Vec<[u8; EFS]> = Vec::with_capacity(f.len());
+    for m in f.iter() {
+        let mut b: [u8; EFS] = [0; EFS];
+        hashit(sha, m, 0, None, EFS, &mut b);
+        hs.push(b);
+    }
+    let h: Vec<&[u8]> = hs.iter().map(|b| &b[..]).collect();
+    ecpvp_dsa_batch_hash(rng, w, &h, sig, res)
+}
+
+/// Encode ECDSA signature C,D as a DER ECDSA-Sig-Value (RFC 3279), as used by X.509 and TLS
+pub fn ecdsa_sig_to_der(c: &[u8], d: &[u8]) -> Vec<u8> {
+    let mut content: Vec<u8> = Vec::new();
+    der::encode_integer(c, &mut content);
+    der::encode_integer(d, &mut content);
+    let mut sig: Vec<u8> = Vec::new();
+    der::encode_sequence(&content, &mut sig);
+    sig
+}
+
+/// Decode a DER ECDSA-Sig-Value into C and D of EGS bytes each.
+/// Non-minimal encodings, negative values and trailing data are rejected
+pub fn ecdsa_sig_from_der(sig: &[u8], c: &mut [u8], d: &mut [u8]) -> isize {
+    let mut r = der::Reader::new(sig);
+    let mut seq = match r.read_sequence() {
+        Some(s) => s,
+        None => return INVALID,
+    };
+    if !r.is_empty() {
+        return INVALID;
+    }
+    let cm = match seq.read_integer() {
+        Some(x) => x,
+        None => return INVALID,
+    };
+    let dm = match seq.read_integer() {
+        Some(x) => x,
+        None => return INVALID,
+    };
+    if !seq.is_empty() {
+        return INVALID;
+    }
+    if !der::to_fixed(cm, &mut c[0..EGS]) || !der::to_fixed(dm, &mut d[0..EGS]) {
+        return INVALID;
+    }
+    0
+}
+
+/// Encode ECDSA signature C,D in the IEEE P1363 fixed-width form C||D of 2*EGS bytes, as used by JOSE
+pub fn ecdsa_sig_to_p1363(c: &[u8], d: &[u8], sig: &mut [u8]) -> isize {
+    if sig.len() < 2 * EGS
+        || !der::to_fixed(strip_zeros(c), &mut sig[0..EGS])
+        || !der::to_fixed(strip_zeros(d), &mut sig[EGS..2 * EGS])
+    {
+        return ERROR;
+    }
+    0
+}
+
+/// Split an IEEE P1363 C||D signature of exactly 2*EGS bytes into C and D
+pub fn ecdsa_sig_from_p1363(sig: &[u8], c: &mut [u8], d: &mut [u8]) -> isize {
+    if sig.len() != 2 * EGS {
+        return INVALID;
+    }
+    c[0..EGS].copy_from_slice(&sig[0..EGS]);
+    d[0..EGS].copy_from_slice(&sig[EGS..2 * EGS]);
+    0
+}
+
+fn strip_zeros(a: &[u8]) -> &[u8] {
+    let mut i = 0;
+    while i < a.len() && a[i] == 0 {
+        i += 1;
+    }
+    &a[i..]
+}
+
 /// IEEE1363 ECIES encryption. Encryption of plaintext M uses public key W and produces ciphertext V,C,T
 #[allow(non_snake_case)]
 pub fn ecies_encrypt(
@@ -745,3 +1149,622 @@ pub fn ecies_decrypt(
 
     m
 }
+
+/// Length of an encoded point, from its leading byte
+fn point_length(b0: u8) -> usize {
+    if b0 == 0x04 {
+        2 * EFS + 1
+    } else {
+        EFS + 1
+    }
+}
+
+/// HKDF-Extract then Expand with SHA256, 384 or 512
+fn hkdf(sha: usize, salt: &[u8], ikm: &[u8], info: &[u8], olen: usize) -> Vec<u8> {
+    match sha {
+        SHA384 => HASH384::hkdf_extend(&HASH384::hkdf_extract(salt, ikm), info, olen as u8),
+        SHA512 => HASH512::hkdf_extend(&HASH512::hkdf_extract(salt, ikm), info, olen as u8),
+        _ => HASH256::hkdf_extend(&HASH256::hkdf_extract(salt, ikm), info, olen as u8),
+    }
+}
+
+/// AES in counter mode with a zero initial counter block, incremented big-endian
+fn ctr_crypt(k: &[u8], m: &[u8]) -> Vec<u8> {
+    let mut a = AES::new();
+    let mut ctr: [u8; 16] = [0; 16];
+    let mut c: Vec<u8> = Vec::with_capacity(m.len());
+    a.init(aes::ECB, k.len(), k, None);
+    for block in m.chunks(16) {
+        let mut ks = ctr;
+        a.ecb_encrypt(&mut ks);
+        for (x, y) in block.iter().zip(ks.iter()) {
+            c.push(x ^ y);
+        }
+        for b in ctr.iter_mut().rev() {
+            *b = b.wrapping_add(1);
+            if *b != 0 {
+                break;
+            }
+        }
+    }
+    a.end();
+    c
+}
+
+/// ECIES key derivation for the AEAD variant. Returns AES key and nonce
+fn ecies_gcm_keys(sha: usize, p1: &[u8], v: &[u8], z: &[u8]) -> Vec<u8> {
+    hkdf(sha, v, z, p1, ecp::AESKEY + ECIES_GCM_NONCE)
+}
+
+/// ECIES encryption with AES-GCM. An ephemeral key pair is generated, and the AES key and
+/// nonce derived by HKDF from the shared secret, with the ephemeral public key V as salt and
+/// P1 as info. P2 is authenticated but not encrypted. Outputs V || C || T, with a 16-byte tag T
+pub fn ecies_gcm_encrypt(
+    sha: usize,
+    p1: &[u8],
+    p2: &[u8],
+    rng: &mut RAND,
+    w: &[u8],
+    m: &[u8],
+) -> Option<Vec<u8>> {
+    let mut z: [u8; EFS] = [0; EFS];
+    let mut u: [u8; EGS] = [0; EGS];
+    let mut v: [u8; 2 * EFS + 1] = [0; 2 * EFS + 1];
+
+    if key_pair_generate(Some(rng), &mut u, &mut v) != 0 {
+        return None;
+    }
+    if ecpsvdp_dh(&u, w, &mut z) != 0 {
+        return None;
+    }
+    let vlen = point_length(v[0]);
+    let k = ecies_gcm_keys(sha, p1, &v[0..vlen], &z);
+
+    let mut g = GCM::new();
+    g.init(ecp::AESKEY, &k, ECIES_GCM_NONCE, &k[ecp::AESKEY..]);
+    g.add_header(p2, p2.len());
+    let mut c = vec![0; m.len()];
+    g.add_plain(&mut c, m, m.len());
+    let t = g.finish(true);
+
+    let mut ct = v[0..vlen].to_vec();
+    ct.extend_from_slice(&c);
+    ct.extend_from_slice(&t);
+    Some(ct)
+}
+
+/// ECIES decryption with AES-GCM of ciphertext CT = V || C || T using private key U.
+/// Returns None if the tag does not verify
+pub fn ecies_gcm_decrypt(sha: usize, p1: &[u8], p2: &[u8], ct: &[u8], u: &[u8]) -> Option<Vec<u8>> {
+    let mut z: [u8; EFS] = [0; EFS];
+    if ct.is_empty() {
+        return None;
+    }
+    let vlen = point_length(ct[0]);
+    if ct.len() < vlen + ECIES_GCM_TAG {
+        return None;
+    }
+    let (v, rest) = ct.split_at(vlen);
+    let (c, t) = rest.split_at(rest.len() - ECIES_GCM_TAG);
+    if ecpsvdp_dh(u, v, &mut z) != 0 {
+        return None;
+    }
+    let k = ecies_gcm_keys(sha, p1, v, &z);
+
+    let mut g = GCM::new();
+    g.init(ecp::AESKEY, &k, ECIES_GCM_NONCE, &k[ecp::AESKEY..]);
+    g.add_header(p2, p2.len());
+    let mut m = vec![0; c.len()];
+    g.add_cipher(&mut m, c, c.len());
+    let tag = g.finish(true);
+    if !ncomp(t, &tag, ECIES_GCM_TAG) {
+        return None;
+    }
+    Some(m)
+}
+
+/// SEC1 v2 ECIES encryption, with the ANSI X9.63 KDF, AES-CTR and HMAC. The KDF input is the
+/// shared secret with S1 as SharedInfo1, and the HMAC covers C || S2. Outputs R || C || D,
+/// where R is the ephemeral public key and the tag D is of length sha
+pub fn ecies_sec1_encrypt(
+    sha: usize,
+    s1: &[u8],
+    s2: &[u8],
+    rng: &mut RAND,
+    w: &[u8],
+    m: &[u8],
+) -> Option<Vec<u8>> {
+    let mut z: [u8; EFS] = [0; EFS];
+    let mut u: [u8; EGS] = [0; EGS];
+    let mut r: [u8; 2 * EFS + 1] = [0; 2 * EFS + 1];
+    let mut k: [u8; ecp::AESKEY + SHA512] = [0; ecp::AESKEY + SHA512];
+    let mut d: [u8; SHA512] = [0; SHA512];
+
+    if key_pair_generate(Some(rng), &mut u, &mut r) != 0 {
+        return None;
+    }
+    if ecpsvdp_dh(&u, w, &mut z) != 0 {
+        return None;
+    }
+    kdf2(sha, &z, Some(s1), ecp::AESKEY + sha, &mut k);
+    let (ke, km) = k.split_at(ecp::AESKEY);
+
+    let mut c = ctr_crypt(ke, m);
+    let clen = c.len();
+    c.extend_from_slice(s2);
+    hmac(sha, &c, &km[0..sha], sha, &mut d);
+    c.truncate(clen);
+
+    let rlen = point_length(r[0]);
+    let mut ct = r[0..rlen].to_vec();
+    ct.extend_from_slice(&c);
+    ct.extend_from_slice(&d[0..sha]);
+    Some(ct)
+}
+
+/// SEC1 v2 ECIES decryption of ciphertext CT = R || C || D using private key U.
+/// Returns None if the tag does not verify
+pub fn ecies_sec1_decrypt(
+    sha: usize,
+    s1: &[u8],
+    s2: &[u8],
+    ct: &[u8],
+    u: &[u8],
+) -> Option<Vec<u8>> {
+    let mut z: [u8; EFS] = [0; EFS];
+    let mut k: [u8; ecp::AESKEY + SHA512] = [0; ecp::AESKEY + SHA512];
+    let mut tag: [u8; SHA512] = [0; SHA512];
+    if ct.is_empty() {
+        return None;
+    }
+    let rlen = point_length(ct[0]);
+    if ct.len() < rlen + sha {
+        return None;
+    }
+    let (r, rest) = ct.split_at(rlen);
+    let (c, d) = rest.split_at(rest.len() - sha);
+    if ecpsvdp_dh(u, r, &mut z) != 0 {
+        return None;
+    }
+    kdf2(sha, &z, Some(s1), ecp::AESKEY + sha, &mut k);
+    let (ke, km) = k.split_at(ecp::AESKEY);
+
+    let mut mc = c.to_vec();
+    mc.extend_from_slice(s2);
+    hmac(sha, &mc, &km[0..sha], sha, &mut tag);
+    if !ncomp(d, &tag, sha) {
+        return None;
+    }
+    Some(ctr_crypt(ke, c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::create_rng;
+
+    #[test]
+    fn test_ecdsa_recover() {
+        if CURVETYPE != CurveType::Weierstrass {
+            return;
+        }
+        let mut rng = create_rng();
+        let mut s: [u8; EGS] = [0; EGS];
+        let mut w: [u8; 2 * EFS + 1] = [0; 2 * EFS + 1];
+        let mut rw: [u8; 2 * EFS + 1] = [0; 2 * EFS + 1];
+        let mut sig: [u8; 2 * EFS + 1] = [0; 2 * EFS + 1];
+        let m = b"Hello World";
+
+        key_pair_generate(Some(&mut rng), &mut s, &mut w);
+        for _ in 0..4 {
+            assert_eq!(ecpsp_dsa_recoverable(SHA256, &mut rng, &s, m, &mut sig), 0);
+            assert_eq!(ecp_recover(SHA256, m, &sig, &mut rw), 0);
+            assert_eq!(w[..], rw[..]);
+
+            // Plain ECDSA verification accepts the r and s halves
+            assert_eq!(
+                ecpvp_dsa(SHA256, &w, m, &sig[0..EFS], &sig[EFS..2 * EFS]),
+                0
+            );
+
+            // Ethereum style v = 27 + recid
+            sig[2 * EFS] += 27;
+            assert_eq!(ecp_recover(SHA256, m, &sig, &mut rw), 0);
+            assert_eq!(w[..], rw[..]);
+
+            // A different message recovers a different key
+            if ecp_recover(SHA256, b"Hello Worle", &sig, &mut rw) == 0 {
+                assert_ne!(w[..], rw[..]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ecdsa_der() {
+        if CURVETYPE != CurveType::Weierstrass {
+            return;
+        }
+        let mut rng = create_rng();
+        let mut s: [u8; EGS] = [0; EGS];
+        let mut w: [u8; 2 * EFS + 1] = [0; 2 * EFS + 1];
+        let mut c: [u8; EGS] = [0; EGS];
+        let mut d: [u8; EGS] = [0; EGS];
+        let mut c2: [u8; EGS] = [0; EGS];
+        let mut d2: [u8; EGS] = [0; EGS];
+        let mut p: [u8; 2 * EGS] = [0; 2 * EGS];
+        let m = b"Hello World";
+
+        key_pair_generate(Some(&mut rng), &mut s, &mut w);
+        for _ in 0..4 {
+            ecpsp_dsa(SHA256, &mut rng, &s, m, &mut c, &mut d);
+
+            let sig = ecdsa_sig_to_der(&c, &d);
+            assert_eq!(ecdsa_sig_from_der(&sig, &mut c2, &mut d2), 0);
+            assert_eq!(c, c2);
+            assert_eq!(d, d2);
+            assert_eq!(ecpvp_dsa(SHA256, &w, m, &c2, &d2), 0);
+
+            // trailing data
+            let mut bad = sig.clone();
+            bad.push(0);
+            assert_eq!(ecdsa_sig_from_der(&bad, &mut c2, &mut d2), INVALID);
+
+            // truncated
+            assert_eq!(
+                ecdsa_sig_from_der(&sig[0..sig.len() - 1], &mut c2, &mut d2),
+                INVALID
+            );
+
+            assert_eq!(ecdsa_sig_to_p1363(&c, &d, &mut p), 0);
+            assert_eq!(ecdsa_sig_from_p1363(&p, &mut c2, &mut d2), 0);
+            assert_eq!(c, c2);
+            assert_eq!(d, d2);
+        }
+
+        // r = 1 with a redundant leading zero byte
+        let bad = [0x30, 0x07, 0x02, 0x02, 0x00, 0x01, 0x02, 0x01, 0x01];
+        assert_eq!(ecdsa_sig_from_der(&bad, &mut c2, &mut d2), INVALID);
+
+        // the same signature minimally encoded
+        let good = [0x30, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x01];
+        assert_eq!(ecdsa_sig_from_der(&good, &mut c2, &mut d2), 0);
+        assert_eq!(c2[EGS - 1], 1);
+        assert_eq!(d2[EGS - 1], 1);
+
+        // non-minimal sequence length
+        let bad = [0x30, 0x81, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x01];
+        assert_eq!(ecdsa_sig_from_der(&bad, &mut c2, &mut d2), INVALID);
+    }
+
+    #[test]
+    fn test_ecdsa_low_s() {
+        if CURVETYPE != CurveType::Weierstrass {
+            return;
+        }
+        let mut rng = create_rng();
+        let mut s: [u8; EGS] = [0; EGS];
+        let mut w: [u8; 2 * EFS + 1] = [0; 2 * EFS + 1];
+        let mut c: [u8; EGS] = [0; EGS];
+        let mut d: [u8; EGS] = [0; EGS];
+        let mut sig: [u8; 2 * EFS + 1] = [0; 2 * EFS + 1];
+        let mut rw: [u8; 2 * EFS + 1] = [0; 2 * EFS + 1];
+        let m = b"Hello World";
+        let r = Big::new_ints(&rom::CURVE_ORDER);
+
+        key_pair_generate(Some(&mut rng), &mut s, &mut w);
+        for _ in 0..8 {
+            ecpsp_dsa(SHA256, &mut rng, &s, m, &mut c, &mut d);
+            assert!(ecdsa_sig_is_low_s(&d));
+            assert!(!ecdsa_sig_normalize(&mut d));
+            assert_eq!(ecpvp_dsa_strict(SHA256, &w, m, &c, &d), 0);
+
+            // The malleated signature n-d is valid but not strictly valid
+            let mut hd = Big::modneg(&Big::frombytes(&d), &r);
+            let mut h: [u8; EGS] = [0; EGS];
+            hd.tobytes(&mut h);
+            assert!(!ecdsa_sig_is_low_s(&h));
+            assert_eq!(ecpvp_dsa(SHA256, &w, m, &c, &h), 0);
+            assert_eq!(ecpvp_dsa_strict(SHA256, &w, m, &c, &h), INVALID);
+            assert!(ecdsa_sig_normalize(&mut h));
+            assert_eq!(h, d);
+
+            // Recoverable signatures are low-S and keep a matching recovery id
+            ecpsp_dsa_recoverable(SHA256, &mut rng, &s, m, &mut sig);
+            assert!(ecdsa_sig_is_low_s(&sig[EFS..2 * EFS]));
+            assert_eq!(ecp_recover(SHA256, m, &sig, &mut rw), 0);
+            assert_eq!(w[..], rw[..]);
+        }
+    }
+
+    #[test]
+    fn test_ecdsa_recover_invalid() {
+        if CURVETYPE != CurveType::Weierstrass {
+            return;
+        }
+        let mut rng = create_rng();
+        let mut s: [u8; EGS] = [0; EGS];
+        let mut w: [u8; 2 * EFS + 1] = [0; 2 * EFS + 1];
+        let mut sig: [u8; 2 * EFS + 1] = [0; 2 * EFS + 1];
+        let m = b"Hello World";
+
+        key_pair_generate(Some(&mut rng), &mut s, &mut w);
+        ecpsp_dsa_recoverable(SHA256, &mut rng, &s, m, &mut sig);
+
+        let mut bad = sig;
+        bad[2 * EFS] = 4;
+        assert_eq!(ecp_recover(SHA256, m, &bad, &mut w), INVALID);
+
+        let mut bad = sig;
+        for b in bad.iter_mut().take(EFS) {
+            *b = 0;
+        }
+        assert_eq!(ecp_recover(SHA256, m, &bad, &mut w), INVALID);
+
+        assert_eq!(ecp_recover(SHA256, m, &sig[0..2 * EFS], &mut w), INVALID);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_ecdsa_batch() {
+        if CURVETYPE != CurveType::Weierstrass {
+            return;
+        }
+        let mut rng = create_rng();
+        let n = 12;
+        let mut keys: Vec<[u8; 2 * EFS + 1]> = Vec::new();
+        let mut sigs: Vec<[u8; 2 * EFS + 1]> = Vec::new();
+        let mut msgs: Vec<Vec<u8>> = Vec::new();
+        let mut s: [u8; EGS] = [0; EGS];
+        let mut w: [u8; 2 * EFS + 1] = [0; 2 * EFS + 1];
+        let mut sig: [u8; 2 * EFS + 1] = [0; 2 * EFS + 1];
+        for i in 0..n {
+            if i % 4 == 0 {
+                key_pair_generate(Some(&mut rng), &mut s, &mut w);
+            }
+            let m = format!("transaction {}", i).into_bytes();
+            assert_eq!(ecpsp_dsa_recoverable(SHA256, &mut rng, &s, &m, &mut sig), 0);
+            keys.push(w);
+            sigs.push(sig);
+            msgs.push(m);
+        }
+        let ws: Vec<&[u8]> = keys.iter().map(|k| &k[..]).collect();
+        let fs: Vec<&[u8]> = msgs.iter().map(|m| &m[..]).collect();
+        let mut res = vec![-1; n];
+
+        let ss: Vec<&[u8]> = sigs.iter().map(|s| &s[..]).collect();
+        assert_eq!(
+            ecpvp_dsa_batch(SHA256, &mut rng, &ws, &fs, &ss, &mut res),
+            0
+        );
+        assert!(res.iter().all(|&x| x == 0));
+
+        // A wrong recovery id fails the batch, but the signature is still valid on its own.
+        // A signature without one, or with the Ethereum offset, is handled too
+        let mut fixed = sigs.clone();
+        fixed[3][2 * EFS] ^= 1;
+        fixed[5][2 * EFS] += 27;
+        let mut ss: Vec<&[u8]> = fixed.iter().map(|s| &s[..]).collect();
+        ss[7] = &sigs[7][0..2 * EFS];
+        assert_eq!(
+            ecpvp_dsa_batch(SHA256, &mut rng, &ws, &fs, &ss, &mut res),
+            0
+        );
+        assert!(res.iter().all(|&x| x == 0));
+
+        // Bad signatures are identified by the fallback
+        let mut bad = sigs.clone();
+        bad[2][EFS + 1] ^= 1;
+        bad[9][0] = 0xff;
+        let ss: Vec<&[u8]> = bad.iter().map(|s| &s[..]).collect();
+        assert_eq!(
+            ecpvp_dsa_batch(SHA256, &mut rng, &ws, &fs, &ss, &mut res),
+            INVALID
+        );
+        for (i, &x) in res.iter().enumerate() {
+            assert_eq!(x == 0, i != 2 && i != 9);
+        }
+
+        // A signature on another message under the wrong key
+        let ss: Vec<&[u8]> = sigs.iter().map(|s| &s[..]).collect();
+        let mut ws2 = ws.clone();
+        ws2[0] = ws[n - 1];
+        assert_eq!(
+            ecpvp_dsa_batch(SHA256, &mut rng, &ws2, &fs, &ss, &mut res),
+            INVALID
+        );
+        assert_ne!(res[0], 0);
+        assert!(res[1..].iter().all(|&x| x == 0));
+
+        assert_eq!(
+            ecpvp_dsa_batch(SHA256, &mut rng, &ws[1..], &fs, &ss, &mut res),
+            ERROR
+        );
+        assert_eq!(
+            ecpvp_dsa_batch(SHA256, &mut rng, &[], &[], &[], &mut res),
+            0
+        );
+
+        // Multi-scalar multiplication agrees with the sum of single multiplications
+        let r = Big::new_ints(&rom::CURVE_ORDER);
+        let G = ECP::generator();
+        let mut X: Vec<ECP> = Vec::new();
+        let mut e: Vec<Big> = Vec::new();
+        let mut T = ECP::new();
+        for i in 0..20 {
+            let P = G.mul(&Big::randomnum(&r, &mut rng));
+            let k = if i % 5 == 0 {
+                Big::new_int(i as isize)
+            } else {
+                Big::randomnum(&r, &mut rng)
+            };
+            T.add(&P.mul(&k));
+            X.push(P);
+            e.push(k);
+        }
+        assert!(ECP::muln(&X, &e).equals(&T));
+    }
+
+    #[test]
+    fn test_aes_ctr() {
+        let k: Vec<u8> = (0..16).collect();
+        let m: Vec<u8> = (0..40).collect();
+        let c = ctr_crypt(&k, &m);
+        assert_eq!(
+            hex::encode(&c),
+            "c6a03934838a5d8567468b69adc5d6766357018681d5a2095162a7f879e9331569f7a570bdbe80ab"
+        );
+        assert_eq!(ctr_crypt(&k, &c), m);
+    }
+
+    #[test]
+    fn test_ecies_gcm() {
+        let mut rng = create_rng();
+        let mut s: [u8; EGS] = [0; EGS];
+        let mut w: [u8; 2 * EFS + 1] = [0; 2 * EFS + 1];
+        let m = b"Hello World";
+        let p1 = b"key derivation";
+        let p2 = b"header";
+
+        key_pair_generate(Some(&mut rng), &mut s, &mut w);
+        let wlen = point_length(w[0]);
+        for sha in [SHA256, SHA384, SHA512].iter() {
+            let ct = ecies_gcm_encrypt(*sha, p1, p2, &mut rng, &w[0..wlen], m).unwrap();
+            assert_eq!(ct.len(), wlen + m.len() + ECIES_GCM_TAG);
+            assert_eq!(ecies_gcm_decrypt(*sha, p1, p2, &ct, &s).unwrap(), m);
+
+            assert!(ecies_gcm_decrypt(*sha, p1, b"other", &ct, &s).is_none());
+            assert!(ecies_gcm_decrypt(*sha, b"other", p2, &ct, &s).is_none());
+            let mut bad = ct.clone();
+            bad[wlen] ^= 1;
+            assert!(ecies_gcm_decrypt(*sha, p1, p2, &bad, &s).is_none());
+            assert!(ecies_gcm_decrypt(*sha, p1, p2, &ct[0..wlen + 4], &s).is_none());
+        }
+    }
+
+    #[test]
+    fn test_ecies_sec1() {
+        let mut rng = create_rng();
+        let mut s: [u8; EGS] = [0; EGS];
+        let mut w: [u8; 2 * EFS + 1] = [0; 2 * EFS + 1];
+        let m = b"A message longer than a single AES block";
+        let s1 = b"shared info 1";
+        let s2 = b"shared info 2";
+
+        key_pair_generate(Some(&mut rng), &mut s, &mut w);
+        let wlen = point_length(w[0]);
+        for sha in [SHA256, SHA384, SHA512].iter() {
+            let ct = ecies_sec1_encrypt(*sha, s1, s2, &mut rng, &w[0..wlen], m).unwrap();
+            assert_eq!(ct.len(), wlen + m.len() + *sha);
+            assert_eq!(ecies_sec1_decrypt(*sha, s1, s2, &ct, &s).unwrap(), &m[..]);
+
+            assert!(ecies_sec1_decrypt(*sha, s1, b"other", &ct, &s).is_none());
+            assert!(ecies_sec1_decrypt(*sha, b"other", s2, &ct, &s).is_none());
+            let mut bad = ct.clone();
+            bad[ct.len() - 1] ^= 1;
+            assert!(ecies_sec1_decrypt(*sha, s1, s2, &bad, &s).is_none());
+        }
+    }
+
+    #[test]
+    fn test_ecies_sec1_vectors() {
+        // P-256, generated with the X9.63 KDF, AES-128-CTR and HMAC of Python's cryptography
+        // package and a fixed ephemeral key. Format: (sha, R || C || D)
+        const U: &str = "c51e4753afdec1e6b6c6a5b992f43f8dd0c7a8933072708b6522468b2ffb06fd";
+        const W: &str = "04942c9f408ead9d82d34a1b9a6a827ebe3e2ddf782b448d23be1b6143988ccef48c9eaf6c0d14d992fc63bad3e2496be2eee61cb5b97f65f428ca94a5d0ee19a1";
+        const VECTORS: [(usize, &str); 2] = [
+            (SHA256, "0475d5ff21632fc7f616bafc872f15af1d9e7cb6b4354c8d2d68e11efa67a6395cf4152590a085796e50a7196a4a5834bb0447360b338ba4a605369bfa4b82aa946ed450e6cf813b70f5961892d8fbd72b194037e98359e3fc0ece740db5778af12ee3df39899ac1f8b9f6674c9ed1a1eb3aaf0a1a1ae862c93dd56c8bfdafb73f01aec4c6fc144ac8"),
+            (SHA384, "0475d5ff21632fc7f616bafc872f15af1d9e7cb6b4354c8d2d68e11efa67a6395cf4152590a085796e50a7196a4a5834bb0447360b338ba4a605369bfa4b82aa9467b27085cc972b5fb2244a99a9eff954e964cb35b22f9d1aaee669849c7bccc7aed32110f8b439be9c13e90187fb6a907708522a7e10d57f4b6369cc9eecf00baf6ad45e583c56429dbebb2abdf45ec3db487112cd752450"),
+        ];
+        let mut p: [u8; EFS] = [0; EFS];
+        Big::new_ints(&rom::MODULUS).tobytes(&mut p);
+        if hex::encode(&p[..]) != "ffffffff00000001000000000000000000000000ffffffffffffffffffffffff"
+        {
+            return;
+        }
+        let mut u = hex::decode(U).unwrap();
+        let mut w: [u8; 2 * EFS + 1] = [0; 2 * EFS + 1];
+        key_pair_generate(None, &mut u, &mut w);
+        assert_eq!(hex::encode(&w[..]), W);
+        for v in VECTORS.iter() {
+            let ct = hex::decode(v.1).unwrap();
+            assert_eq!(
+                ecies_sec1_decrypt(v.0, b"shared info 1", b"shared info 2", &ct, &u).unwrap(),
+                &b"A message longer than a single AES block"[..]
+            );
+        }
+    }
+
+    #[test]
+    fn test_key_pair_compressed() {
+        if CURVETYPE == CurveType::Montgomery {
+            return;
+        }
+        let mut rng = create_rng();
+        let mut s1: [u8; EGS] = [0; EGS];
+        let mut s2: [u8; EGS] = [0; EGS];
+        let mut w1: [u8; 2 * EFS + 1] = [0; 2 * EFS + 1];
+        let mut w2: [u8; EFS + 1] = [0; EFS + 1];
+        let mut z1: [u8; EFS] = [0; EFS];
+        let mut z2: [u8; EFS] = [0; EFS];
+
+        key_pair_generate(Some(&mut rng), &mut s1, &mut w1);
+        s2.copy_from_slice(&s1);
+        assert_eq!(
+            key_pair_generate_compressed(None, &mut s2, &mut w2, true),
+            0
+        );
+        assert_eq!(w1[0], 0x04);
+        assert_eq!(w2[0] & 0xfe, 0x02);
+        assert_eq!(&w1[1..EFS + 1], &w2[1..]);
+        assert_eq!(public_key_validate(&w2), 0);
+
+        key_pair_generate(Some(&mut rng), &mut s2, &mut w1);
+        assert_eq!(ecpsvdp_dh(&s2, &w2, &mut z1), 0);
+        assert_eq!(ecpsvdp_dh(&s1, &w1, &mut z2), 0);
+        assert_eq!(z1, z2);
+    }
+
+    #[test]
+    fn test_public_key_check() {
+        if CURVETYPE == CurveType::Montgomery {
+            return;
+        }
+        let mut rng = create_rng();
+        let mut s: [u8; EGS] = [0; EGS];
+        let mut w: [u8; 2 * EFS + 1] = [0; 2 * EFS + 1];
+        let mut z: [u8; EFS] = [0; EFS];
+        key_pair_generate(Some(&mut rng), &mut s, &mut w);
+        assert_eq!(public_key_check(&w), Ok(()));
+
+        let mut t = w;
+        t[2 * EFS] ^= 1;
+        assert_eq!(public_key_check(&t), Err(PointError::NotOnCurve));
+        assert_eq!(public_key_validate(&t), INVALID_PUBLIC_KEY);
+        assert_eq!(ecpsvdp_dh(&s, &t, &mut z), ERROR);
+        assert_eq!(
+            public_key_check(&w[..2 * EFS]),
+            Err(PointError::InvalidLength)
+        );
+        assert_eq!(ecpsvdp_dh(&s, &w[..2 * EFS], &mut z), ERROR);
+        assert_eq!(public_key_check(&[0]), Err(PointError::Infinity));
+
+        // x = p instead of 0 is rejected, rather than read as infinity
+        let mut t = sec1::encode(&ECP::generator(), sec1::PointFormat::Uncompressed);
+        let mut p: [u8; EFS] = [0; EFS];
+        Big::new_ints(&rom::MODULUS).tobytes(&mut p);
+        t[1..EFS + 1].copy_from_slice(&p);
+        assert_eq!(public_key_check(&t), Err(PointError::NonCanonical));
+
+        if CURVETYPE == CurveType::Edwards {
+            // (0,-1) has order 2
+            let mut y = Big::new_ints(&rom::MODULUS);
+            y.dec(1);
+            let t = sec1::encode(
+                &ECP::new_bigs(&Big::new(), &y),
+                sec1::PointFormat::Uncompressed,
+            );
+            assert_eq!(public_key_check(&t), Err(PointError::SmallOrder));
+            assert_eq!(public_key_validate(&t), INVALID_PUBLIC_KEY);
+        }
+    }
+}
@@ -303,6 +303,9 @@ impl ECP {
         }
         let one = FP::new_int(1);
         if self.z.equals(&one) {
+            // coordinates may still be unreduced, e.g. after a square root
+            self.x.reduce();
+            self.y.reduce();
             return;
         }
         self.z.inverse();
@@ -1198,6 +1201,69 @@ impl ECP {
         return S;
     }
 
+    /// Return e[0].X[0]+e[1].X[1]+...+e[n-1].X[n-1] using Pippenger's bucket method.
+    /// Not constant time, so only for public scalars such as in signature verification
+    pub fn muln(X: &[ECP], e: &[Big]) -> ECP {
+        let n = X.len().min(e.len());
+        let mut P = ECP::new();
+        if n == 0 {
+            return P;
+        }
+
+        // window width grows with the number of points
+        let mut c = 2;
+        while c < 10 && (1 << (c + 2)) <= n {
+            c += 1;
+        }
+
+        let mut nb = 0;
+        for s in e.iter().take(n) {
+            let mut t = Big::new_copy(s);
+            t.norm();
+            nb = nb.max(t.nbits());
+        }
+        let nw = (nb + c - 1) / c;
+
+        // signed digits are not needed, the buckets absorb the additions
+        let mut digits: Vec<Vec<usize>> = Vec::with_capacity(n);
+        for s in e.iter().take(n) {
+            let mut t = Big::new_copy(s);
+            let mut d = Vec::with_capacity(nw);
+            for _ in 0..nw {
+                d.push(t.lastbits(c) as usize);
+                t.fshr(c);
+            }
+            digits.push(d);
+        }
+
+        let mut B: Vec<ECP> = (0..1 << c).map(|_| ECP::new()).collect();
+        let mut R = ECP::new();
+        let mut S = ECP::new();
+        for i in (0..nw).rev() {
+            for _ in 0..c {
+                P.dbl();
+            }
+            for b in B.iter_mut() {
+                b.inf();
+            }
+            for j in 0..n {
+                let k = digits[j][i];
+                if k != 0 {
+                    B[k].add(&X[j]);
+                }
+            }
+            R.inf();
+            S.inf();
+            for b in B.iter().skip(1).rev() {
+                R.add(b);
+                S.add(&R);
+            }
+            P.add(&S);
+        }
+        P.affine();
+        P
+    }
+
     // Multiply itself by cofactor of the curve
     pub fn cfp(&mut self) {
         let cf = rom::CURVE_COF_I;
@@ -171,6 +171,20 @@ impl FF {
         return self.v[0].lastbits(m);
     }
 
+    /* number of bits */
+    pub fn nbits(&self) -> usize {
+        let mut t = FF::new_int(self.length);
+        t.copy(self);
+        t.norm();
+        for i in (0..self.length).rev() {
+            let b = t.v[i].nbits();
+            if b > 0 {
+                return i * big::BIGBITS + b;
+            }
+        }
+        0
+    }
+
     /* compare x and y - must be normalised, and of same length */
     pub fn comp(a: &FF, b: &FF) -> isize {
         let mut i = a.length - 1;
@@ -337,6 +351,31 @@ impl FF {
         }
     }
 
+    /* N word FF from the big-endian bytes B of any length, right aligned. Leading bytes
+    beyond its size are dropped */
+    pub fn from_be_bytes(b: &[u8], n: usize) -> FF {
+        let mut t = vec![0; n * big::MODBYTES];
+        let m = b.len().min(t.len());
+        let d = t.len() - m;
+        t[d..].copy_from_slice(&b[b.len() - m..]);
+        let mut x = FF::new_int(n);
+        FF::frombytes(&mut x, &t);
+        x
+    }
+
+    /* big-endian bytes of this, right aligned in B. B is zero padded, or holds only the
+    low bytes if it is too short */
+    pub fn to_be_bytes(&self, b: &mut [u8]) {
+        let mut t = FF::new_int(self.length);
+        let mut w = vec![0; self.length * big::MODBYTES];
+        t.copy(self);
+        t.tobytes(&mut w);
+        let m = w.len().min(b.len());
+        let d = b.len() - m;
+        b[0..d].iter_mut().for_each(|x| *x = 0);
+        b[d..].copy_from_slice(&w[w.len() - m..]);
+    }
+
     /* in-place swapping using xor - side channel resistant - lengths must be the same */
     pub fn cswap(a: &mut FF, b: &mut FF, d: isize) {
         for i in 0..a.length {
@@ -461,9 +500,31 @@ impl FF {
         self.rnorm(nd2, sn);
     }
 
+    /* copy of x, truncated or zero extended to n words */
+    pub fn resize(x: &FF, n: usize) -> FF {
+        let mut z = FF::new_int(n);
+        for i in 0..n.min(x.length) {
+            z.v[i].copy(&x.v[i]);
+        }
+        z
+    }
+
+    /* zero extended product back to 2*n words. Overflow from excess in the operands is
+    held in the top word, as karmul does */
+    fn unpad(z: &FF, n: usize) -> FF {
+        let mut r = FF::resize(z, 2 * n);
+        r.v[2 * n - 1].xortop(z.v[2 * n].get(0) << P_TBITS);
+        r
+    }
+
     /* z=x*y. Assumes x and y are of same length. */
     pub fn mul(x: &FF, y: &FF) -> FF {
         let n = x.length;
+        if !n.is_power_of_two() {
+            /* karatsuba halves down to one word, so zero extend */
+            let m = n.next_power_of_two();
+            return FF::unpad(&FF::mul(&FF::resize(x, m), &FF::resize(y, m)), n);
+        }
         let mut z = FF::new_int(2 * n);
         let mut t = FF::new_int(2 * n);
         z.karmul(0, &x, 0, &y, 0, &mut t, 0, n);
@@ -473,6 +534,12 @@ impl FF {
     /* return low part of product this*y */
     pub fn lmul(&mut self, y: &FF) {
         let n = self.length;
+        if !n.is_power_of_two() {
+            let mut x = FF::resize(self, n.next_power_of_two());
+            x.lmul(&FF::resize(y, x.length));
+            self.copy(&x);
+            return;
+        }
         let mut t = FF::new_int(2 * n);
         let mut x = FF::new_int(n);
         x.copy(&self);
@@ -510,6 +577,10 @@ impl FF {
     /* z=x^2 */
     pub fn sqr(x: &FF) -> FF {
         let n = x.length;
+        if !n.is_power_of_two() {
+            let m = n.next_power_of_two();
+            return FF::unpad(&FF::sqr(&FF::resize(x, m)), n);
+        }
         let mut z = FF::new_int(2 * n);
         let mut t = FF::new_int(2 * n);
         z.karsqr(0, &x, 0, &mut t, 0, n);
@@ -524,6 +595,19 @@ impl FF {
         let mut r = FF::new_int(n);
         let mut m = FF::new_int(n);
 
+        if !n.is_power_of_two() {
+            /* full product in place of karmul_upper */
+            m.copy(self);
+            m.lmul(md);
+            t = FF::mul(ms, &m);
+            r.sducopy(self);
+            m.sducopy(&t);
+            r.add(ms);
+            r.sub(&m);
+            r.norm();
+            return r;
+        }
+
         r.sducopy(&self);
         m.karmul_lower(0, &self, 0, &md, 0, &mut t, 0, n);
         self.karmul_upper(&ms, &m, &mut t, n);
@@ -550,6 +634,12 @@ impl FF {
         m.dsucopy(&b);
         let mut k = big::BIGBITS * n;
 
+        /* line up the top bit of b with the top of m, for b short of n words */
+        for _ in b.nbits()..big::BIGBITS * n {
+            m.shl();
+            k += 1;
+        }
+
         while FF::comp(&x, &m) >= 0 {
             x.sub(&m);
             x.norm();
@@ -678,6 +768,10 @@ impl FF {
     /* U=1/a mod 2^m - Arazi & Qi */
     pub fn invmod2m(&self) -> FF {
         let n = self.length;
+        if !n.is_power_of_two() {
+            let x = FF::resize(self, n.next_power_of_two());
+            return FF::resize(&x.invmod2m(), n);
+        }
 
         let mut b = FF::new_int(n);
         let mut c = FF::new_int(n);
@@ -728,6 +822,31 @@ impl FF {
         }
     }
 
+    /* random number of exactly nb bits, with the top two bits set so that the product
+    of two such numbers has exactly 2*nb bits. Returns false, leaving this unchanged, unless
+    2 <= nb <= length*BIGBITS */
+    pub fn random_bits(&mut self, rng: &mut RAND, nb: usize) -> bool {
+        if nb < 2 || nb > self.length * big::BIGBITS {
+            return false;
+        }
+        for i in 0..self.length {
+            self.v[i].copy(&Big::random(rng))
+        }
+        let w = (nb - 1) / big::BIGBITS;
+        for i in w + 1..self.length {
+            self.v[i].zero();
+        }
+        if nb - w * big::BIGBITS < big::BIGBITS {
+            self.v[w].mod2m(nb - w * big::BIGBITS);
+        }
+        for b in [nb - 1, nb - 2].iter() {
+            let mut t = Big::new_int(1);
+            t.shl(b % big::BIGBITS);
+            self.v[b / big::BIGBITS].or(&t);
+        }
+        true
+    }
+
     /* generate random x less than p */
     pub fn randomnum(&mut self, p: &FF, rng: &mut RAND) {
         let n = self.length;
@@ -964,6 +1083,50 @@ impl FF {
         return yy;
     }
 
+    /* greatest common divisor of x and y - binary method, not side channel resistant */
+    pub fn gcd(x: &FF, y: &FF) -> FF {
+        let n = x.length;
+        let mut u = FF::new_int(n);
+        let mut v = FF::new_int(n);
+        u.copy(x);
+        v.copy(y);
+        u.norm();
+        v.norm();
+        if u.iszilch() {
+            return v;
+        }
+        if v.iszilch() {
+            return u;
+        }
+
+        let mut k = 0;
+        while u.parity() == 0 && v.parity() == 0 {
+            u.shr();
+            v.shr();
+            k += 1;
+        }
+        while u.parity() == 0 {
+            u.shr();
+        }
+        loop {
+            while v.parity() == 0 {
+                v.shr();
+            }
+            if FF::comp(&u, &v) > 0 {
+                std::mem::swap(&mut u, &mut v);
+            }
+            v.sub(&u);
+            v.norm();
+            if v.iszilch() {
+                break;
+            }
+        }
+        for _ in 0..k {
+            u.shl();
+        }
+        u
+    }
+
     /* quick and dirty check for common factor with n */
     pub fn cfactor(&self, s: isize) -> bool {
         let n = self.length;
@@ -0,0 +1,427 @@
+/*
+Licensed to the Apache Software Foundation (ASF) under one
+or more contributor license agreements.  See the NOTICE file
+distributed with this work for additional information
+regarding copyright ownership.  The ASF licenses this file
+to you under the Apache License, Version 2.0 (the
+"License"); you may not use this file except in compliance
+with the License.  You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing,
+software distributed under the License is distributed on an
+"AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+KIND, either express or implied.  See the License for the
+specific language governing permissions and limitations
+under the License.
+*/
+
+/* Finite field Diffie-Hellman over the named groups of RFC 7919 (ffdhe) and RFC 3526
+(MODP). Each prime p is safe, p=2q+1 with q prime, and the generator is 2, which has order
+q as p=7 mod 8. Keys and shared secrets are big-endian and the length of p */
+
+use super::big;
+use super::ff::FF;
+
+use crate::rand::RAND;
+
+pub const INVALID_PUBLIC_KEY: isize = -2;
+pub const ERROR: isize = -3;
+
+/* groups */
+pub const FFDHE2048: usize = 0;
+pub const FFDHE3072: usize = 1;
+pub const FFDHE4096: usize = 2;
+pub const MODP1536: usize = 3;
+pub const MODP2048: usize = 4;
+pub const MODP3072: usize = 5;
+pub const MODP4096: usize = 6;
+pub const MODP6144: usize = 7;
+pub const MODP8192: usize = 8;
+
+const FFDHE2048_P: &str =
+    "ffffffffffffffffadf85458a2bb4a9aafdc5620273d3cf1d8b9c583ce2d3695a9e13641146433fb\
+    cc939dce249b3ef97d2fe363630c75d8f681b202aec4617ad3df1ed5d5fd65612433f51f5f066ed0\
+    856365553ded1af3b557135e7f57c935984f0c70e0e68b77e2a689daf3efe8721df158a136ade735\
+    30acca4f483a797abc0ab182b324fb61d108a94bb2c8e3fbb96adab760d7f4681d4f42a3de394df4\
+    ae56ede76372bb190b07a7c8ee0a6d709e02fce1cdf7e2ecc03404cd28342f619172fe9ce98583ff\
+    8e4f1232eef28183c3fe3b1b4c6fad733bb5fcbc2ec22005c58ef1837d1683b2c6f34a26c1b2effa\
+    886b423861285c97ffffffffffffffff";
+
+const FFDHE3072_P: &str =
+    "ffffffffffffffffadf85458a2bb4a9aafdc5620273d3cf1d8b9c583ce2d3695a9e13641146433fb\
+    cc939dce249b3ef97d2fe363630c75d8f681b202aec4617ad3df1ed5d5fd65612433f51f5f066ed0\
+    856365553ded1af3b557135e7f57c935984f0c70e0e68b77e2a689daf3efe8721df158a136ade735\
+    30acca4f483a797abc0ab182b324fb61d108a94bb2c8e3fbb96adab760d7f4681d4f42a3de394df4\
+    ae56ede76372bb190b07a7c8ee0a6d709e02fce1cdf7e2ecc03404cd28342f619172fe9ce98583ff\
+    8e4f1232eef28183c3fe3b1b4c6fad733bb5fcbc2ec22005c58ef1837d1683b2c6f34a26c1b2effa\
+    886b4238611fcfdcde355b3b6519035bbc34f4def99c023861b46fc9d6e6c9077ad91d2691f7f7ee\
+    598cb0fac186d91caefe130985139270b4130c93bc437944f4fd4452e2d74dd364f2e21e71f54bff\
+    5cae82ab9c9df69ee86d2bc522363a0dabc521979b0deada1dbf9a42d5c4484e0abcd06bfa53ddef\
+    3c1b20ee3fd59d7c25e41d2b66c62e37ffffffffffffffff";
+
+const FFDHE4096_P: &str =
+    "ffffffffffffffffadf85458a2bb4a9aafdc5620273d3cf1d8b9c583ce2d3695a9e13641146433fb\
+    cc939dce249b3ef97d2fe363630c75d8f681b202aec4617ad3df1ed5d5fd65612433f51f5f066ed0\
+    856365553ded1af3b557135e7f57c935984f0c70e0e68b77e2a689daf3efe8721df158a136ade735\
+    30acca4f483a797abc0ab182b324fb61d108a94bb2c8e3fbb96adab760d7f4681d4f42a3de394df4\
+    ae56ede76372bb190b07a7c8ee0a6d709e02fce1cdf7e2ecc03404cd28342f619172fe9ce98583ff\
+    8e4f1232eef28183c3fe3b1b4c6fad733bb5fcbc2ec22005c58ef1837d1683b2c6f34a26c1b2effa\
+    886b4238611fcfdcde355b3b6519035bbc34f4def99c023861b46fc9d6e6c9077ad91d2691f7f7ee\
+    598cb0fac186d91caefe130985139270b4130c93bc437944f4fd4452e2d74dd364f2e21e71f54bff\
+    5cae82ab9c9df69ee86d2bc522363a0dabc521979b0deada1dbf9a42d5c4484e0abcd06bfa53ddef\
+    3c1b20ee3fd59d7c25e41d2b669e1ef16e6f52c3164df4fb7930e9e4e58857b6ac7d5f42d69f6d18\
+    7763cf1d5503400487f55ba57e31cc7a7135c886efb4318aed6a1e012d9e6832a907600a918130c4\
+    6dc778f971ad0038092999a333cb8b7a1a1db93d7140003c2a4ecea9f98d0acc0a8291cdcec97dcf\
+    8ec9b55a7f88a46b4db5a851f44182e1c68a007e5e655f6affffffffffffffff";
+
+const MODP1536_P: &str =
+    "ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74020bbea63b139b22\
+    514a08798e3404ddef9519b3cd3a431b302b0a6df25f14374fe1356d6d51c245e485b576625e7ec6\
+    f44c42e9a637ed6b0bff5cb6f406b7edee386bfb5a899fa5ae9f24117c4b1fe649286651ece45b3d\
+    c2007cb8a163bf0598da48361c55d39a69163fa8fd24cf5f83655d23dca3ad961c62f356208552bb\
+    9ed529077096966d670c354e4abc9804f1746c08ca237327ffffffffffffffff";
+
+const MODP2048_P: &str =
+    "ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74020bbea63b139b22\
+    514a08798e3404ddef9519b3cd3a431b302b0a6df25f14374fe1356d6d51c245e485b576625e7ec6\
+    f44c42e9a637ed6b0bff5cb6f406b7edee386bfb5a899fa5ae9f24117c4b1fe649286651ece45b3d\
+    c2007cb8a163bf0598da48361c55d39a69163fa8fd24cf5f83655d23dca3ad961c62f356208552bb\
+    9ed529077096966d670c354e4abc9804f1746c08ca18217c32905e462e36ce3be39e772c180e8603\
+    9b2783a2ec07a28fb5c55df06f4c52c9de2bcbf6955817183995497cea956ae515d2261898fa0510\
+    15728e5a8aacaa68ffffffffffffffff";
+
+const MODP3072_P: &str =
+    "ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74020bbea63b139b22\
+    514a08798e3404ddef9519b3cd3a431b302b0a6df25f14374fe1356d6d51c245e485b576625e7ec6\
+    f44c42e9a637ed6b0bff5cb6f406b7edee386bfb5a899fa5ae9f24117c4b1fe649286651ece45b3d\
+    c2007cb8a163bf0598da48361c55d39a69163fa8fd24cf5f83655d23dca3ad961c62f356208552bb\
+    9ed529077096966d670c354e4abc9804f1746c08ca18217c32905e462e36ce3be39e772c180e8603\
+    9b2783a2ec07a28fb5c55df06f4c52c9de2bcbf6955817183995497cea956ae515d2261898fa0510\
+    15728e5a8aaac42dad33170d04507a33a85521abdf1cba64ecfb850458dbef0a8aea71575d060c7d\
+    b3970f85a6e1e4c7abf5ae8cdb0933d71e8c94e04a25619dcee3d2261ad2ee6bf12ffa06d98a0864\
+    d87602733ec86a64521f2b18177b200cbbe117577a615d6c770988c0bad946e208e24fa074e5ab31\
+    43db5bfce0fd108e4b82d120a93ad2caffffffffffffffff";
+
+const MODP4096_P: &str =
+    "ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74020bbea63b139b22\
+    514a08798e3404ddef9519b3cd3a431b302b0a6df25f14374fe1356d6d51c245e485b576625e7ec6\
+    f44c42e9a637ed6b0bff5cb6f406b7edee386bfb5a899fa5ae9f24117c4b1fe649286651ece45b3d\
+    c2007cb8a163bf0598da48361c55d39a69163fa8fd24cf5f83655d23dca3ad961c62f356208552bb\
+    9ed529077096966d670c354e4abc9804f1746c08ca18217c32905e462e36ce3be39e772c180e8603\
+    9b2783a2ec07a28fb5c55df06f4c52c9de2bcbf6955817183995497cea956ae515d2261898fa0510\
+    15728e5a8aaac42dad33170d04507a33a85521abdf1cba64ecfb850458dbef0a8aea71575d060c7d\
+    b3970f85a6e1e4c7abf5ae8cdb0933d71e8c94e04a25619dcee3d2261ad2ee6bf12ffa06d98a0864\
+    d87602733ec86a64521f2b18177b200cbbe117577a615d6c770988c0bad946e208e24fa074e5ab31\
+    43db5bfce0fd108e4b82d120a92108011a723c12a787e6d788719a10bdba5b2699c327186af4e23c\
+    1a946834b6150bda2583e9ca2ad44ce8dbbbc2db04de8ef92e8efc141fbecaa6287c59474e6bc05d\
+    99b2964fa090c3a2233ba186515be7ed1f612970cee2d7afb81bdd762170481cd0069127d5b05aa9\
+    93b4ea988d8fddc186ffb7dc90a6c08f4df435c934063199ffffffffffffffff";
+
+const MODP6144_P: &str =
+    "ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74020bbea63b139b22\
+    514a08798e3404ddef9519b3cd3a431b302b0a6df25f14374fe1356d6d51c245e485b576625e7ec6\
+    f44c42e9a637ed6b0bff5cb6f406b7edee386bfb5a899fa5ae9f24117c4b1fe649286651ece45b3d\
+    c2007cb8a163bf0598da48361c55d39a69163fa8fd24cf5f83655d23dca3ad961c62f356208552bb\
+    9ed529077096966d670c354e4abc9804f1746c08ca18217c32905e462e36ce3be39e772c180e8603\
+    9b2783a2ec07a28fb5c55df06f4c52c9de2bcbf6955817183995497cea956ae515d2261898fa0510\
+    15728e5a8aaac42dad33170d04507a33a85521abdf1cba64ecfb850458dbef0a8aea71575d060c7d\
+    b3970f85a6e1e4c7abf5ae8cdb0933d71e8c94e04a25619dcee3d2261ad2ee6bf12ffa06d98a0864\
+    d87602733ec86a64521f2b18177b200cbbe117577a615d6c770988c0bad946e208e24fa074e5ab31\
+    43db5bfce0fd108e4b82d120a92108011a723c12a787e6d788719a10bdba5b2699c327186af4e23c\
+    1a946834b6150bda2583e9ca2ad44ce8dbbbc2db04de8ef92e8efc141fbecaa6287c59474e6bc05d\
+    99b2964fa090c3a2233ba186515be7ed1f612970cee2d7afb81bdd762170481cd0069127d5b05aa9\
+    93b4ea988d8fddc186ffb7dc90a6c08f4df435c93402849236c3fab4d27c7026c1d4dcb2602646de\
+    c9751e763dba37bdf8ff9406ad9e530ee5db382f413001aeb06a53ed9027d831179727b0865a8918\
+    da3edbebcf9b14ed44ce6cbaced4bb1bdb7f1447e6cc254b332051512bd7af426fb8f401378cd2bf\
+    5983ca01c64b92ecf032ea15d1721d03f482d7ce6e74fef6d55e702f46980c82b5a84031900b1c9e\
+    59e7c97fbec7e8f323a97a7e36cc88be0f1d45b7ff585ac54bd407b22b4154aacc8f6d7ebf48e1d8\
+    14cc5ed20f8037e0a79715eef29be32806a1d58bb7c5da76f550aa3d8a1fbff0eb19ccb1a313d55c\
+    da56c9ec2ef29632387fe8d76e3c0468043e8f663f4860ee12bf2d5b0b7474d6e694f91e6dcc4024\
+    ffffffffffffffff";
+
+const MODP8192_P: &str =
+    "ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74020bbea63b139b22\
+    514a08798e3404ddef9519b3cd3a431b302b0a6df25f14374fe1356d6d51c245e485b576625e7ec6\
+    f44c42e9a637ed6b0bff5cb6f406b7edee386bfb5a899fa5ae9f24117c4b1fe649286651ece45b3d\
+    c2007cb8a163bf0598da48361c55d39a69163fa8fd24cf5f83655d23dca3ad961c62f356208552bb\
+    9ed529077096966d670c354e4abc9804f1746c08ca18217c32905e462e36ce3be39e772c180e8603\
+    9b2783a2ec07a28fb5c55df06f4c52c9de2bcbf6955817183995497cea956ae515d2261898fa0510\
+    15728e5a8aaac42dad33170d04507a33a85521abdf1cba64ecfb850458dbef0a8aea71575d060c7d\
+    b3970f85a6e1e4c7abf5ae8cdb0933d71e8c94e04a25619dcee3d2261ad2ee6bf12ffa06d98a0864\
+    d87602733ec86a64521f2b18177b200cbbe117577a615d6c770988c0bad946e208e24fa074e5ab31\
+    43db5bfce0fd108e4b82d120a92108011a723c12a787e6d788719a10bdba5b2699c327186af4e23c\
+    1a946834b6150bda2583e9ca2ad44ce8dbbbc2db04de8ef92e8efc141fbecaa6287c59474e6bc05d\
+    99b2964fa090c3a2233ba186515be7ed1f612970cee2d7afb81bdd762170481cd0069127d5b05aa9\
+    93b4ea988d8fddc186ffb7dc90a6c08f4df435c93402849236c3fab4d27c7026c1d4dcb2602646de\
+    c9751e763dba37bdf8ff9406ad9e530ee5db382f413001aeb06a53ed9027d831179727b0865a8918\
+    da3edbebcf9b14ed44ce6cbaced4bb1bdb7f1447e6cc254b332051512bd7af426fb8f401378cd2bf\
+    5983ca01c64b92ecf032ea15d1721d03f482d7ce6e74fef6d55e702f46980c82b5a84031900b1c9e\
+    59e7c97fbec7e8f323a97a7e36cc88be0f1d45b7ff585ac54bd407b22b4154aacc8f6d7ebf48e1d8\
+    14cc5ed20f8037e0a79715eef29be32806a1d58bb7c5da76f550aa3d8a1fbff0eb19ccb1a313d55c\
+    da56c9ec2ef29632387fe8d76e3c0468043e8f663f4860ee12bf2d5b0b7474d6e694f91e6dbe1159\
+    74a3926f12fee5e438777cb6a932df8cd8bec4d073b931ba3bc832b68d9dd300741fa7bf8afc47ed\
+    2576f6936ba424663aab639c5ae4f5683423b4742bf1c978238f16cbe39d652de3fdb8befc848ad9\
+    22222e04a4037c0713eb57a81a23f0c73473fc646cea306b4bcbc8862f8385ddfa9d4b7fa2c087e8\
+    79683303ed5bdd3a062b3cf5b3a278a66d2a13f83f44f82ddf310ee074ab6a364597e899a0255dc1\
+    64f31cc50846851df9ab48195ded7ea1b1d510bd7ee74d73faf36bc31ecfa268359046f4eb879f92\
+    4009438b481c6cd7889a002ed5ee382bc9190da6fc026e479558e4475677e9aa9e3050e2765694df\
+    c81f56e880b96e7160c980dd98edd3dfffffffffffffffff";
+
+/* prime of GROUP, in hex */
+fn prime(group: usize) -> Option<&'static str> {
+    match group {
+        FFDHE2048 => Some(FFDHE2048_P),
+        FFDHE3072 => Some(FFDHE3072_P),
+        FFDHE4096 => Some(FFDHE4096_P),
+        MODP1536 => Some(MODP1536_P),
+        MODP2048 => Some(MODP2048_P),
+        MODP3072 => Some(MODP3072_P),
+        MODP4096 => Some(MODP4096_P),
+        MODP6144 => Some(MODP6144_P),
+        MODP8192 => Some(MODP8192_P),
+        _ => None,
+    }
+}
+
+/* length of the prime of GROUP in bytes, or 0 for an unknown group */
+pub fn group_bytes(group: usize) -> usize {
+    prime(group).map_or(0, |p| p.len() / 2)
+}
+
+/* p and q=(p-1)/2 of GROUP */
+fn group_ff(group: usize) -> Option<(FF, FF)> {
+    let h = prime(group)?.as_bytes();
+    let b: Vec<u8> = h
+        .chunks(2)
+        .map(|x| u8::from_str_radix(std::str::from_utf8(x).unwrap(), 16).unwrap())
+        .collect();
+    let p = FF::from_be_bytes(&b, (b.len() + big::MODBYTES - 1) / big::MODBYTES);
+    let mut q = FF::new_int(p.getlen());
+    q.copy(&p);
+    q.dec(1);
+    q.shr();
+    Some((p, q))
+}
+
+/* private key S, 0 < S < q, as an FF of the length of p */
+fn private_key(s: &[u8], p: &FF, q: &FF) -> Option<FF> {
+    let mut x = FF::from_be_bytes(s, p.getlen());
+    if x.iszilch() || FF::comp(&x, q) >= 0 {
+        return None;
+    }
+    Some(x)
+}
+
+/* Calculate a key pair S,W where W=2^S mod p. S is random if RNG is given, and is
+provided in S otherwise. S and W are group_bytes(GROUP) long */
+pub fn key_pair_generate(
+    group: usize,
+    rng: Option<&mut RAND>,
+    s: &mut [u8],
+    w: &mut [u8],
+) -> isize {
+    let (p, q) = match group_ff(group) {
+        Some(g) => g,
+        None => return ERROR,
+    };
+    let k = group_bytes(group);
+    if s.len() != k || w.len() != k {
+        return ERROR;
+    }
+    let mut x = FF::new_int(p.getlen());
+    if let Some(r) = rng {
+        while x.iszilch() {
+            x.randomnum(&q, r);
+        }
+        x.to_be_bytes(s);
+    } else {
+        x = match private_key(s, &p, &q) {
+            Some(x) => x,
+            None => return ERROR,
+        };
+    }
+    let mut y = FF::new_int(p.getlen());
+    y.set(2);
+    y.skpow(&x, &p);
+    y.to_be_bytes(w);
+    x.zero();
+    0
+}
+
+/* Validate the public key W, big-endian and at most group_bytes(GROUP) long. It must lie in
+1 < W < p-1, which rules out the small subgroup of order 2, and W^q=1 mod p, so that it is
+in the subgroup of order q (NIST SP 800-56A 5.6.2.3.1) */
+pub fn public_key_validate(group: usize, w: &[u8]) -> isize {
+    let (p, q) = match group_ff(group) {
+        Some(g) => g,
+        None => return ERROR,
+    };
+    if w.len() > group_bytes(group) {
+        return INVALID_PUBLIC_KEY;
+    }
+    let y = FF::from_be_bytes(w, p.getlen());
+    let mut t = FF::new_int(p.getlen());
+    t.one();
+    t.inc(1);
+    if FF::comp(&y, &t) < 0 {
+        return INVALID_PUBLIC_KEY;
+    }
+    t.copy(&p);
+    t.dec(1);
+    if FF::comp(&y, &t) >= 0 {
+        return INVALID_PUBLIC_KEY;
+    }
+    t.copy(&y);
+    t.pow(&q, &p);
+    let mut one = FF::new_int(p.getlen());
+    one.one();
+    if FF::comp(&t, &one) != 0 {
+        return INVALID_PUBLIC_KEY;
+    }
+    0
+}
+
+/* IEEE-1363 Diffie-Hellman secret value derivation Z=WD^S mod p, after validation of WD.
+Z is group_bytes(GROUP) long, left padded with zeros, as TLS 1.3 uses it (RFC 8446 7.4.1).
+See tls12_premaster_secret for TLS 1.2 */
+pub fn dlsvdp_dh(group: usize, s: &[u8], wd: &[u8], z: &mut [u8]) -> isize {
+    let (p, q) = match group_ff(group) {
+        Some(g) => g,
+        None => return ERROR,
+    };
+    let k = group_bytes(group);
+    if s.len() != k || z.len() != k {
+        return ERROR;
+    }
+    let res = public_key_validate(group, wd);
+    if res != 0 {
+        return res;
+    }
+    let mut x = match private_key(s, &p, &q) {
+        Some(x) => x,
+        None => return ERROR,
+    };
+    let mut y = FF::from_be_bytes(wd, p.getlen());
+    y.skpow(&x, &p);
+    y.to_be_bytes(z);
+    x.zero();
+    y.zero();
+    0
+}
+
+/* The TLS 1.2 pre_master_secret, which is Z with its leading zero bytes stripped
+(RFC 5246 8.1.2). This leaks the number of such bytes through its length, as TLS 1.2
+does */
+pub fn tls12_premaster_secret(z: &[u8]) -> &[u8] {
+    let i = z.iter().take_while(|&&x| x == 0).count();
+    &z[i..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::create_rng;
+
+    // Private keys S1 and S2, public keys W1 and W2, and the shared secret, computed with
+    // Python. Both secrets have a leading zero byte
+    const VECTORS: [(usize, &str, &str, &str, &str, &str); 2] = [
+        (
+            FFDHE2048,
+            "4534c43a4148fe28902fc85a10c8bc6ae8c2340ac535d8c4a65228ed34cd5c879d35bde0e8fd46e68b5591b9b01a48d069b0d174a94834c30d650b6e9ecb1d5f9d6d4278f234c256f63cbcd3660802aea7c5f31d44b41dc45bbdbddec5bfe2c65398952f604585c6ba9c6e0b6c59d51d1880de8223532e62b9f9f0943fadcd4e07cca836666b98e85f0635a092c780ae49346aab9a4130c7e437eaf0db15976c381b78fe811870038bcd1acba164c2670b9f15ecbbd6b49e746f25d42783770446a32f42bc66323ac2232d710b7880d7ae0b65170cb76f5acec8129282e394bd8dea3aa4c08a607352d095151c4a09caeeee318369ca47e7582600e9111f4efe",
+            "097900b550e4a458029106b7bd06f58e3ae713998358bd76dcb1daca55424be314bbbac6351f8984aa89210dc5cb8075fb522e903c7340ac4e867a860c3c74459640d5d3a9e7caff028365b950d22c02d951f18c4edc4648a42b7e5b19ed87087ee04345e45a3e119c18fba470d8ba73c7236d3b920942765e02db796d480b0b71a683e8c7d6bd1b3d4beb3523b506d7eb3dd737591cb76d57160a262bd91effc405137a5d73b88168903eaff099922a8d347891a30610113d62d90d3c74a4bc3b309597cfd2749e7753e1661d74cb9e3f53dfbf0b81a59fb9bf6dfed7068dd2b7b2e9d14a4c5b95b33b49b9774ed906779ee5af73a4483829dd3bb8434af02e",
+            "254104ccf5bfb44589999c5f92468c72d2d5544fb57851ae397f8701101b7d502847b6a3e52757a8900abf38ed54791970a4b01d7a91b011bb81dde2d17412435b7cb0baff1b5e74cc8cf90aa1edb2f4331b9696fdfd0f63a502755258071a21e64e03485155bba1abef69fc17a182da24ad6e28c402555087d99f6d79447a31ade4ae48ce48c2feca322b9780920e60b6f1560051d8d698c09ea39fbb3f978f2d41023996768260dd870e98f9007f1e38eb43f67f9192c4d79692daecf907eae025e9f4d5832e263ad98edc15e6a205d1fb3858ad2be069d94b517590930ffca17bce08578da1f2794a3c711980f37afaecb3276cfa52c3e1167390e92d031a",
+            "db96411354cfabc1b8e42b9b86674499fa97f369702e8f92a8fdfe574bc347ab8a62e8edff9be0863828aec3acce4ac803d7ce452966a6e1873f110b22ef810681393a0fa6d38bec8386e078b1179fad27606996ccb663909c5cee094276c3d2042eddfe4e913c62022b779cd93a366a643961597eda015ad55d6cb129ad43fbde1ce663958a66dca3b6b0da8b0df5b17494cec32c6c3fa626c7ce97e816b3c1334cc2f0a2ce7d262c526056d89aab84c3ed3e5bc9a3537fb033b7b7c7eb60042b5e8705c24e8a4b21f3ca1c09aafed0cd158f9c1c7e4529f06c18762f3572597f025401feea7e3a50099134365a3b996fa76834c141e2d2402fc8d1e24a4571",
+            "009f72c62d850957f2a43ef19071f2f84230f9d91e6ea109ae307d828f1a6a8a404e1bc8a8d327331dd866795098716cd62d872078ccdd6815be56b5eb27286170e219333d8bedc6f569a3d522cb679d63ccfafea2fc4bdc7cc55d8a0b527bd62b0b022226b1b2cb8f1ff196ee0064fc4691604f6724bb9429e4ce557b25bafdab2c3d3325fdc0549c41fd057c8cc6e1d89e51d181124ec61345a26c3e3e5b11ac98a2f75d72aac1215bf4545c91f41a465a0c014474ebd26741aea8a281bd4f5692ed4255b7d485ff7820f92fd870fcad129f7661ff11120fd1095c9c733e4d4bed410515a0b26820cb3464ae4bd8d3b3ad9245eabb0e9bcd1e5b36adc97c2d",
+        ),
+        (
+            MODP1536,
+            "5834a57178cd3f70725855349d22f378660ece4591e9d8ecb4d48e0135c73eee0b3b0f2ccdf8da1a0743bcae4338d1a551e5cf71a56ba77327cffe88ab51290feb45917059a7f48988b29ed744e68141584a4c610aa1a3942ef7398dc03e06a3523e1698063443750f51a6246111b42206dca89958b2668f99125436f9e462f54efef23fad1012b5b4aae38107292a07d8ccaa415feafc01756f0089374c68fe6a0db7d51b1f9f362b8516719e398f6eafb66d7c136364ac540cd5e54d42f3ca",
+            "49913e0e0ac0d149107dee23c8764b14aba7093691dea22135ab0172b2aed78e731f03345c2be1a1e915470203cf1dcadda757e8816df1c15d5607ac69c3a0257d23cac750912971b750dc7b86cba51b25bdedab01e5ce06a3792296b3f744b95a3e2736e4b8e2503f9578a06c08f8d285c3592dfc3e34e9bf81688b2726db31cfacf594d529f73f75c82b173c3f3145314223c341f9f1dfed4156e517a2f8c5bc8cf9aa4d6e3327f9bd31ce93240b8292cc9a11d3da186d2f878a2a880ad8d3",
+            "2f0b3f051a1b6c9e9b2183ab6e9a306f93ea644e1bd8a1ed98be3e252bf53306d36160d7335465e3dedc198ce233550a124511398a013b16c51237900201d58c48700563fddf3f7f34a79303009aa4003d3ffa9e0bfc2cd8b647dc403b2f53439cd49de0d75745d081fe51a857b1ebf1833f02ef19a455c0bd9d75df0201792f0b6e7e04d6a36e6c0d2b42581b2087aaf811760e568f8667795d7159dfdc563f864f285409b87af6027cdac82b0b3059b4b3f8cc62aac973119a896a4d15df7d",
+            "413978a0f78c9392c54b4cd181e70b320f028d5a81653acc3fe9e02564657a0d05c6b32ea2538fef48649b685f3371dba7ae726f0ac3e45ef38af8e56ab10f8eca07d33cceeb8ccc21ff08e19500185b3ba12043b3afb5935cb16925fc3c218c28fd789f84f66b1063888c36ac5f084fe08e319a7b44dd4063c51fb10c0a34f31635008f2ed7d4993e8acfb2ffa26a8b7adc6d41788baa78054a127e821dad41c3dd2ab602444d24192f8a9dbfbd0a4994cc28ad1b42ff5df8f820ef4510d0ae",
+            "001710845b4d0366068eda6dca22c78652d90581a54f64b41edfbffc98133288ac8052861c446a45eadd40e20912795498e025eafbbade8d54459fea8f83c99450be444f97189c04d9fe84a1aa3807e1d08ecd6b9d92ca571a3ba797d55b66ba8e6a6103768e4d86cdcdeea90600eb19a4f16d7bad04d4f1aaadf7e74ca0bdec22661a19ceaef5fb159153cf1917f808226e61bbf730654de84d113874e43944421f436a62164d7c4cf49c19e392ad2300acc0b67717ecff3cac2f5e72818614",
+        ),
+    ];
+
+    const GROUPS: [usize; 9] = [
+        FFDHE2048, FFDHE3072, FFDHE4096, MODP1536, MODP2048, MODP3072, MODP4096, MODP6144, MODP8192,
+    ];
+
+    #[test]
+    fn test_ffdh_vectors() {
+        for v in VECTORS.iter() {
+            let k = group_bytes(v.0);
+            let mut s = hex::decode(v.1).unwrap();
+            let mut w = vec![0; k];
+            let mut z = vec![0; k];
+            assert_eq!(key_pair_generate(v.0, None, &mut s, &mut w), 0);
+            assert_eq!(hex::encode(&w), v.2);
+            let mut s = hex::decode(v.3).unwrap();
+            assert_eq!(key_pair_generate(v.0, None, &mut s, &mut w), 0);
+            assert_eq!(hex::encode(&w), v.4);
+
+            assert_eq!(dlsvdp_dh(v.0, &s, &hex::decode(v.2).unwrap(), &mut z), 0);
+            assert_eq!(hex::encode(&z), v.5);
+            let s = hex::decode(v.1).unwrap();
+            assert_eq!(dlsvdp_dh(v.0, &s, &w, &mut z), 0);
+            assert_eq!(hex::encode(&z), v.5);
+            assert_eq!(z[0], 0);
+            assert_eq!(tls12_premaster_secret(&z), &z[1..]);
+
+            // TLS 1.2 peers send public keys without leading zeros
+            let i = w.iter().take_while(|&&x| x == 0).count();
+            assert_eq!(dlsvdp_dh(v.0, &s, &w[i..], &mut z), 0);
+            assert_eq!(hex::encode(&z), v.5);
+
+            assert_eq!(dlsvdp_dh(v.0, &s, &w, &mut z[1..]), ERROR);
+            assert_eq!(dlsvdp_dh(v.0, &[0; 1], &w, &mut z), ERROR);
+            assert_eq!(dlsvdp_dh(v.0, &vec![0; k], &w, &mut z), ERROR);
+        }
+
+        let mut rng = create_rng();
+        let mut s1 = vec![0; 256];
+        let mut s2 = vec![0; 256];
+        let mut w1 = vec![0; 256];
+        let mut w2 = vec![0; 256];
+        let mut z1 = vec![0; 256];
+        let mut z2 = vec![0; 256];
+        assert_eq!(
+            key_pair_generate(FFDHE2048, Some(&mut rng), &mut s1, &mut w1),
+            0
+        );
+        assert_eq!(
+            key_pair_generate(FFDHE2048, Some(&mut rng), &mut s2, &mut w2),
+            0
+        );
+        assert_eq!(dlsvdp_dh(FFDHE2048, &s1, &w2, &mut z1), 0);
+        assert_eq!(dlsvdp_dh(FFDHE2048, &s2, &w1, &mut z2), 0);
+        assert_eq!(z1, z2);
+        assert_eq!(
+            key_pair_generate(9, Some(&mut rng), &mut s1, &mut w1),
+            ERROR
+        );
+        assert_eq!(group_bytes(9), 0);
+    }
+
+    #[test]
+    fn test_ffdh_validate() {
+        for g in GROUPS.iter() {
+            let k = group_bytes(*g);
+            let mut p = hex::decode(prime(*g).unwrap()).unwrap();
+            assert_eq!(p.len(), k);
+            assert_eq!(public_key_validate(*g, &[2]), 0);
+            assert_eq!(public_key_validate(*g, &[0]), INVALID_PUBLIC_KEY);
+            assert_eq!(public_key_validate(*g, &[1]), INVALID_PUBLIC_KEY);
+            assert_eq!(public_key_validate(*g, &p), INVALID_PUBLIC_KEY);
+            // p-1 has order 2, and p-2 is a non-residue outside the subgroup of order q
+            p[k - 1] -= 1;
+            assert_eq!(public_key_validate(*g, &p), INVALID_PUBLIC_KEY);
+            p[k - 1] -= 1;
+            assert_eq!(public_key_validate(*g, &p), INVALID_PUBLIC_KEY);
+            p.insert(0, 0);
+            assert_eq!(public_key_validate(*g, &p), INVALID_PUBLIC_KEY);
+        }
+    }
+}
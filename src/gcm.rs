@@ -94,7 +94,7 @@ impl GCM {
         for i in 0..128 {
             j -= 1;
             let mut c = ((self.statex[m] >> j) & 1) as u32;
-            c = (!c) + 1;
+            c = (!c).wrapping_add(1);
             for k in 0..GCM_NB {
                 p[k] ^= self.table[i][k] & c
             }
@@ -479,3 +479,33 @@ fn main()
 
 }
 */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcm() {
+        // NIST GCM test case 4
+        let k = hex::decode("feffe9928665731c6d6a8f9467308308").unwrap();
+        let iv = hex::decode("cafebabefacedbaddecaf888").unwrap();
+        let m = hex::decode("d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a721c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39").unwrap();
+        let h = hex::decode("feedfacedeadbeeffeedfacedeadbeefabaddad2").unwrap();
+
+        let mut gcm = GCM::new();
+        let mut c = vec![0; m.len()];
+        gcm.init(k.len(), &k, iv.len(), &iv);
+        gcm.add_header(&h, h.len());
+        gcm.add_plain(&mut c, &m, m.len());
+        let t = gcm.finish(true);
+        assert_eq!(hex::encode(&c), "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091");
+        assert_eq!(hex::encode(t), "5bc94fbc3221a5db94fae95ae7121a47");
+
+        let mut p = vec![0; c.len()];
+        gcm.init(k.len(), &k, iv.len(), &iv);
+        gcm.add_header(&h, h.len());
+        gcm.add_cipher(&mut p, &c, c.len());
+        assert_eq!(gcm.finish(true), t);
+        assert_eq!(p, m);
+    }
+}
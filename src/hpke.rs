@@ -0,0 +1,919 @@
+/*
+Licensed to the Apache Software Foundation (ASF) under one
+or more contributor license agreements.  See the NOTICE file
+distributed with this work for additional information
+regarding copyright ownership.  The ASF licenses this file
+to you under the Apache License, Version 2.0 (the
+"License"); you may not use this file except in compliance
+with the License.  You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing,
+software distributed under the License is distributed on an
+"AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+KIND, either express or implied.  See the License for the
+specific language governing permissions and limitations
+under the License.
+*/
+
+/* Hybrid Public Key Encryption - RFC 9180. DHKEM over whichever of nist256, nist384,
+nist521 and c25519 are compiled in, with HKDF-SHA256/384/512 and AES-128/256-GCM.
+Cipher suites are selected at run time by their IANA identifiers */
+
+use gcm::GCM;
+use hash256::HASH256;
+use hash384::HASH384;
+use hash512::HASH512;
+use rand::RAND;
+
+#[cfg(feature = "c25519")]
+use c25519;
+#[cfg(feature = "nist256")]
+use nist256;
+#[cfg(feature = "nist384")]
+use nist384;
+#[cfg(feature = "nist521")]
+use nist521;
+
+pub const MODE_BASE: u8 = 0;
+pub const MODE_PSK: u8 = 1;
+pub const MODE_AUTH: u8 = 2;
+pub const MODE_AUTH_PSK: u8 = 3;
+
+pub const KEM_P256_SHA256: u16 = 0x0010;
+pub const KEM_P384_SHA384: u16 = 0x0011;
+pub const KEM_P521_SHA512: u16 = 0x0012;
+pub const KEM_X25519_SHA256: u16 = 0x0020;
+
+pub const KDF_HKDF_SHA256: u16 = 0x0001;
+pub const KDF_HKDF_SHA384: u16 = 0x0002;
+pub const KDF_HKDF_SHA512: u16 = 0x0003;
+
+pub const AEAD_AES_128_GCM: u16 = 0x0001;
+pub const AEAD_AES_256_GCM: u16 = 0x0002;
+pub const AEAD_EXPORT_ONLY: u16 = 0xffff;
+
+/// AEAD nonce and tag sizes
+pub const NN: usize = 12;
+pub const NT: usize = 16;
+
+/// KEM parameters (Nsecret, Npk, Nsk, KDF), or None if the curve is not compiled in
+fn kem_params(kem: u16) -> Option<(usize, usize, usize, u16)> {
+    match kem {
+        #[cfg(feature = "nist256")]
+        KEM_P256_SHA256 => Some((32, 65, 32, KDF_HKDF_SHA256)),
+        #[cfg(feature = "nist384")]
+        KEM_P384_SHA384 => Some((48, 97, 48, KDF_HKDF_SHA384)),
+        #[cfg(feature = "nist521")]
+        KEM_P521_SHA512 => Some((64, 133, 66, KDF_HKDF_SHA512)),
+        #[cfg(feature = "c25519")]
+        KEM_X25519_SHA256 => Some((32, 32, 32, KDF_HKDF_SHA256)),
+        _ => None,
+    }
+}
+
+/// Hash output length of a KDF
+fn kdf_length(kdf: u16) -> Option<usize> {
+    match kdf {
+        KDF_HKDF_SHA256 => Some(32),
+        KDF_HKDF_SHA384 => Some(48),
+        KDF_HKDF_SHA512 => Some(64),
+        _ => None,
+    }
+}
+
+/// AEAD key length
+fn aead_key_length(aead: u16) -> Option<usize> {
+    match aead {
+        AEAD_AES_128_GCM => Some(16),
+        AEAD_AES_256_GCM => Some(32),
+        AEAD_EXPORT_ONLY => Some(0),
+        _ => None,
+    }
+}
+
+fn extract(kdf: u16, salt: &[u8], ikm: &[u8]) -> Vec<u8> {
+    match kdf {
+        KDF_HKDF_SHA384 => HASH384::hkdf_extract(salt, ikm).to_vec(),
+        KDF_HKDF_SHA512 => HASH512::hkdf_extract(salt, ikm).to_vec(),
+        _ => HASH256::hkdf_extract(salt, ikm).to_vec(),
+    }
+}
+
+fn expand(kdf: u16, prk: &[u8], info: &[u8], l: usize) -> Vec<u8> {
+    // HKDF-Expand of RFC 5869, for L up to 255 blocks
+    let mut okm = Vec::with_capacity(l);
+    let mut t: Vec<u8> = vec![];
+    let mut i = 0;
+    while okm.len() < l {
+        i += 1;
+        t.extend_from_slice(info);
+        t.push(i as u8);
+        t = match kdf {
+            KDF_HKDF_SHA384 => HASH384::hmac(prk, &t).to_vec(),
+            KDF_HKDF_SHA512 => HASH512::hmac(prk, &t).to_vec(),
+            _ => HASH256::hmac(prk, &t).to_vec(),
+        };
+        okm.extend_from_slice(&t);
+    }
+    okm.truncate(l);
+    okm
+}
+
+fn labeled_extract(kdf: u16, suite_id: &[u8], salt: &[u8], label: &[u8], ikm: &[u8]) -> Vec<u8> {
+    let mut t = b"HPKE-v1".to_vec();
+    t.extend_from_slice(suite_id);
+    t.extend_from_slice(label);
+    t.extend_from_slice(ikm);
+    extract(kdf, salt, &t)
+}
+
+fn labeled_expand(
+    kdf: u16,
+    suite_id: &[u8],
+    prk: &[u8],
+    label: &[u8],
+    info: &[u8],
+    l: usize,
+) -> Vec<u8> {
+    let mut t = vec![(l >> 8) as u8, l as u8];
+    t.extend_from_slice(b"HPKE-v1");
+    t.extend_from_slice(suite_id);
+    t.extend_from_slice(label);
+    t.extend_from_slice(info);
+    expand(kdf, prk, &t, l)
+}
+
+/// Group order R as Nsk big-endian bytes, for rejection sampling of NIST curve keys
+fn kem_order(kem: u16, r: &mut [u8]) {
+    match kem {
+        #[cfg(feature = "nist256")]
+        KEM_P256_SHA256 => nist256::big::Big::new_ints(&nist256::rom::CURVE_ORDER).tobytes(r),
+        #[cfg(feature = "nist384")]
+        KEM_P384_SHA384 => nist384::big::Big::new_ints(&nist384::rom::CURVE_ORDER).tobytes(r),
+        #[cfg(feature = "nist521")]
+        KEM_P521_SHA512 => nist521::big::Big::new_ints(&nist521::rom::CURVE_ORDER).tobytes(r),
+        _ => r.iter_mut().for_each(|b| *b = 0xff),
+    }
+}
+
+/// Serialised public key for secret key SK
+fn kem_public_key(kem: u16, npk: usize, sk: &[u8]) -> Option<Vec<u8>> {
+    let mut s = sk.to_vec();
+    let mut pk = vec![0; npk];
+    let res = match kem {
+        #[cfg(feature = "nist256")]
+        KEM_P256_SHA256 => nist256::ecdh::key_pair_generate(None, &mut s, &mut pk),
+        #[cfg(feature = "nist384")]
+        KEM_P384_SHA384 => nist384::ecdh::key_pair_generate(None, &mut s, &mut pk),
+        #[cfg(feature = "nist521")]
+        KEM_P521_SHA512 => nist521::ecdh::key_pair_generate(None, &mut s, &mut pk),
+        #[cfg(feature = "c25519")]
+        KEM_X25519_SHA256 => c25519::x25519::key_pair_generate(None, &mut s, &mut pk),
+        _ => return None,
+    };
+    if res != 0 {
+        return None;
+    }
+    Some(pk)
+}
+
+/// Diffie-Hellman shared secret of secret key SK and serialised public key PK
+fn kem_dh(kem: u16, nsk: usize, sk: &[u8], pk: &[u8]) -> Option<Vec<u8>> {
+    let mut z = vec![0; nsk];
+    if kem != KEM_X25519_SHA256 && pk[0] != 0x04 {
+        return None;
+    }
+    let res = match kem {
+        #[cfg(feature = "nist256")]
+        KEM_P256_SHA256 => nist256::ecdh::ecpsvdp_dh(sk, pk, &mut z),
+        #[cfg(feature = "nist384")]
+        KEM_P384_SHA384 => nist384::ecdh::ecpsvdp_dh(sk, pk, &mut z),
+        #[cfg(feature = "nist521")]
+        KEM_P521_SHA512 => nist521::ecdh::ecpsvdp_dh(sk, pk, &mut z),
+        #[cfg(feature = "c25519")]
+        KEM_X25519_SHA256 => c25519::x25519::shared_secret(sk, pk, &mut z),
+        _ => return None,
+    };
+    if res != 0 {
+        return None;
+    }
+    Some(z)
+}
+
+/// An HPKE cipher suite
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Suite {
+    kem: u16,
+    kdf: u16,
+    aead: u16,
+    nsecret: usize,
+    npk: usize,
+    nsk: usize,
+    kem_kdf: u16,
+}
+
+/// An HPKE encryption context, for the sender or the receiver
+pub struct Context {
+    suite: Suite,
+    key: Vec<u8>,
+    base_nonce: Vec<u8>,
+    exporter_secret: Vec<u8>,
+    seq: u64,
+}
+
+impl Suite {
+    /// Suite from KEM, KDF and AEAD identifiers. None if any is unsupported
+    pub fn new(kem: u16, kdf: u16, aead: u16) -> Option<Suite> {
+        let (nsecret, npk, nsk, kem_kdf) = kem_params(kem)?;
+        kdf_length(kdf)?;
+        aead_key_length(aead)?;
+        Some(Suite {
+            kem,
+            kdf,
+            aead,
+            nsecret,
+            npk,
+            nsk,
+            kem_kdf,
+        })
+    }
+
+    /// Length of a serialised public key, and of the encapsulated key
+    pub fn public_key_size(&self) -> usize {
+        self.npk
+    }
+
+    /// Length of a serialised secret key
+    pub fn secret_key_size(&self) -> usize {
+        self.nsk
+    }
+
+    fn kem_suite_id(&self) -> Vec<u8> {
+        let mut s = b"KEM".to_vec();
+        s.extend_from_slice(&[(self.kem >> 8) as u8, self.kem as u8]);
+        s
+    }
+
+    fn suite_id(&self) -> Vec<u8> {
+        let mut s = b"HPKE".to_vec();
+        for id in [self.kem, self.kdf, self.aead].iter() {
+            s.extend_from_slice(&[(id >> 8) as u8, *id as u8]);
+        }
+        s
+    }
+
+    /// Deterministic key pair (SK, PK) from input keying material IKM
+    pub fn derive_key_pair(&self, ikm: &[u8]) -> Option<(Vec<u8>, Vec<u8>)> {
+        let sid = self.kem_suite_id();
+        let prk = labeled_extract(self.kem_kdf, &sid, &[], b"dkp_prk", ikm);
+        if self.kem == KEM_X25519_SHA256 {
+            let sk = labeled_expand(self.kem_kdf, &sid, &prk, b"sk", &[], self.nsk);
+            let pk = kem_public_key(self.kem, self.npk, &sk)?;
+            return Some((sk, pk));
+        }
+        let mut order = vec![0; self.nsk];
+        kem_order(self.kem, &mut order);
+        for counter in 0..=255u8 {
+            let mut sk =
+                labeled_expand(self.kem_kdf, &sid, &prk, b"candidate", &[counter], self.nsk);
+            if self.kem == KEM_P521_SHA512 {
+                sk[0] &= 0x01;
+            }
+            if sk.iter().any(|&b| b != 0) && sk < order {
+                let pk = kem_public_key(self.kem, self.npk, &sk)?;
+                return Some((sk, pk));
+            }
+        }
+        None
+    }
+
+    /// Random key pair (SK, PK)
+    pub fn generate_key_pair(&self, rng: &mut RAND) -> Option<(Vec<u8>, Vec<u8>)> {
+        let ikm = self.random_ikm(rng);
+        self.derive_key_pair(&ikm)
+    }
+
+    fn extract_and_expand(&self, dh: &[u8], kem_context: &[u8]) -> Vec<u8> {
+        let sid = self.kem_suite_id();
+        let prk = labeled_extract(self.kem_kdf, &sid, &[], b"eae_prk", dh);
+        labeled_expand(
+            self.kem_kdf,
+            &sid,
+            &prk,
+            b"shared_secret",
+            kem_context,
+            self.nsecret,
+        )
+    }
+
+    /// (Auth)Encap. Returns the shared secret and the encapsulated key
+    fn encap(&self, ske: &[u8], pkr: &[u8], sks: Option<&[u8]>) -> Option<(Vec<u8>, Vec<u8>)> {
+        if pkr.len() != self.npk {
+            return None;
+        }
+        let enc = kem_public_key(self.kem, self.npk, ske)?;
+        let mut dh = kem_dh(self.kem, self.nsk, ske, pkr)?;
+        let mut kem_context = enc.clone();
+        kem_context.extend_from_slice(pkr);
+        if let Some(s) = sks {
+            dh.extend_from_slice(&kem_dh(self.kem, self.nsk, s, pkr)?);
+            kem_context.extend_from_slice(&kem_public_key(self.kem, self.npk, s)?);
+        }
+        Some((self.extract_and_expand(&dh, &kem_context), enc))
+    }
+
+    /// (Auth)Decap. Returns the shared secret
+    fn decap(&self, enc: &[u8], skr: &[u8], pks: Option<&[u8]>) -> Option<Vec<u8>> {
+        if enc.len() != self.npk {
+            return None;
+        }
+        let mut dh = kem_dh(self.kem, self.nsk, skr, enc)?;
+        let mut kem_context = enc.to_vec();
+        kem_context.extend_from_slice(&kem_public_key(self.kem, self.npk, skr)?);
+        if let Some(s) = pks {
+            if s.len() != self.npk {
+                return None;
+            }
+            dh.extend_from_slice(&kem_dh(self.kem, self.nsk, skr, s)?);
+            kem_context.extend_from_slice(s);
+        }
+        Some(self.extract_and_expand(&dh, &kem_context))
+    }
+
+    fn key_schedule(
+        &self,
+        mode: u8,
+        shared_secret: &[u8],
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+    ) -> Option<Context> {
+        let got_psk = !psk.is_empty();
+        if got_psk == psk_id.is_empty() || got_psk != (mode & MODE_PSK != 0) || mode > 3 {
+            return None;
+        }
+        let sid = self.suite_id();
+        let mut ksc = vec![mode];
+        ksc.extend_from_slice(&labeled_extract(
+            self.kdf,
+            &sid,
+            &[],
+            b"psk_id_hash",
+            psk_id,
+        ));
+        ksc.extend_from_slice(&labeled_extract(self.kdf, &sid, &[], b"info_hash", info));
+        let secret = labeled_extract(self.kdf, &sid, shared_secret, b"secret", psk);
+
+        let nk = aead_key_length(self.aead)?;
+        let nh = kdf_length(self.kdf)?;
+        let mut key = Vec::new();
+        let mut base_nonce = Vec::new();
+        if nk > 0 {
+            key = labeled_expand(self.kdf, &sid, &secret, b"key", &ksc, nk);
+            base_nonce = labeled_expand(self.kdf, &sid, &secret, b"base_nonce", &ksc, NN);
+        }
+        let exporter_secret = labeled_expand(self.kdf, &sid, &secret, b"exp", &ksc, nh);
+        Some(Context {
+            suite: *self,
+            key,
+            base_nonce,
+            exporter_secret,
+            seq: 0,
+        })
+    }
+
+    /// Sender setup for any mode, with the ephemeral key pair derived from IKME.
+    /// PSK and PSK_ID are empty unless the mode uses a pre-shared key, and SKS is
+    /// the sender's secret key in the authenticated modes.
+    /// Returns the encapsulated key and the context
+    #[allow(clippy::too_many_arguments)]
+    pub fn setup_sender(
+        &self,
+        mode: u8,
+        ikme: &[u8],
+        pkr: &[u8],
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+        sks: Option<&[u8]>,
+    ) -> Option<(Vec<u8>, Context)> {
+        if sks.is_some() != (mode & MODE_AUTH != 0) {
+            return None;
+        }
+        let (ske, _) = self.derive_key_pair(ikme)?;
+        let (shared_secret, enc) = self.encap(&ske, pkr, sks)?;
+        let ctx = self.key_schedule(mode, &shared_secret, info, psk, psk_id)?;
+        Some((enc, ctx))
+    }
+
+    /// Receiver setup for any mode, from the encapsulated key ENC and the receiver's
+    /// secret key SKR. PKS is the sender's public key in the authenticated modes
+    #[allow(clippy::too_many_arguments)]
+    pub fn setup_receiver(
+        &self,
+        mode: u8,
+        enc: &[u8],
+        skr: &[u8],
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+        pks: Option<&[u8]>,
+    ) -> Option<Context> {
+        if pks.is_some() != (mode & MODE_AUTH != 0) {
+            return None;
+        }
+        let shared_secret = self.decap(enc, skr, pks)?;
+        self.key_schedule(mode, &shared_secret, info, psk, psk_id)
+    }
+
+    fn random_ikm(&self, rng: &mut RAND) -> Vec<u8> {
+        (0..self.nsk).map(|_| rng.getbyte()).collect()
+    }
+
+    /// Base mode sender setup
+    pub fn setup_base_s(
+        &self,
+        rng: &mut RAND,
+        pkr: &[u8],
+        info: &[u8],
+    ) -> Option<(Vec<u8>, Context)> {
+        let ikme = self.random_ikm(rng);
+        self.setup_sender(MODE_BASE, &ikme, pkr, info, &[], &[], None)
+    }
+
+    /// Base mode receiver setup
+    pub fn setup_base_r(&self, enc: &[u8], skr: &[u8], info: &[u8]) -> Option<Context> {
+        self.setup_receiver(MODE_BASE, enc, skr, info, &[], &[], None)
+    }
+
+    /// PSK mode sender setup
+    pub fn setup_psk_s(
+        &self,
+        rng: &mut RAND,
+        pkr: &[u8],
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+    ) -> Option<(Vec<u8>, Context)> {
+        let ikme = self.random_ikm(rng);
+        self.setup_sender(MODE_PSK, &ikme, pkr, info, psk, psk_id, None)
+    }
+
+    /// PSK mode receiver setup
+    pub fn setup_psk_r(
+        &self,
+        enc: &[u8],
+        skr: &[u8],
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+    ) -> Option<Context> {
+        self.setup_receiver(MODE_PSK, enc, skr, info, psk, psk_id, None)
+    }
+
+    /// Auth mode sender setup, authenticated by the sender's secret key SKS
+    pub fn setup_auth_s(
+        &self,
+        rng: &mut RAND,
+        pkr: &[u8],
+        info: &[u8],
+        sks: &[u8],
+    ) -> Option<(Vec<u8>, Context)> {
+        let ikme = self.random_ikm(rng);
+        self.setup_sender(MODE_AUTH, &ikme, pkr, info, &[], &[], Some(sks))
+    }
+
+    /// Auth mode receiver setup, checking the sender's public key PKS
+    pub fn setup_auth_r(&self, enc: &[u8], skr: &[u8], info: &[u8], pks: &[u8]) -> Option<Context> {
+        self.setup_receiver(MODE_AUTH, enc, skr, info, &[], &[], Some(pks))
+    }
+
+    /// AuthPSK mode sender setup
+    pub fn setup_auth_psk_s(
+        &self,
+        rng: &mut RAND,
+        pkr: &[u8],
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+        sks: &[u8],
+    ) -> Option<(Vec<u8>, Context)> {
+        let ikme = self.random_ikm(rng);
+        self.setup_sender(MODE_AUTH_PSK, &ikme, pkr, info, psk, psk_id, Some(sks))
+    }
+
+    /// AuthPSK mode receiver setup
+    pub fn setup_auth_psk_r(
+        &self,
+        enc: &[u8],
+        skr: &[u8],
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+        pks: &[u8],
+    ) -> Option<Context> {
+        self.setup_receiver(MODE_AUTH_PSK, enc, skr, info, psk, psk_id, Some(pks))
+    }
+}
+
+impl Context {
+    /// Nonce for the current sequence number
+    fn nonce(&self) -> Vec<u8> {
+        let mut n = self.base_nonce.clone();
+        for (i, b) in n.iter_mut().rev().take(8).enumerate() {
+            *b ^= (self.seq >> (8 * i)) as u8;
+        }
+        n
+    }
+
+    /// Encrypt PT with associated data AAD, outputting ciphertext and tag.
+    /// None for the export-only AEAD or once the sequence number is exhausted
+    pub fn seal(&mut self, aad: &[u8], pt: &[u8]) -> Option<Vec<u8>> {
+        if self.key.is_empty() || self.seq == u64::MAX {
+            return None;
+        }
+        let mut g = GCM::new();
+        g.init(self.key.len(), &self.key, NN, &self.nonce());
+        g.add_header(aad, aad.len());
+        let mut ct = vec![0; pt.len()];
+        g.add_plain(&mut ct, pt, pt.len());
+        ct.extend_from_slice(&g.finish(true));
+        self.seq += 1;
+        Some(ct)
+    }
+
+    /// Decrypt and authenticate CT with associated data AAD. None if the tag does not verify
+    pub fn open(&mut self, aad: &[u8], ct: &[u8]) -> Option<Vec<u8>> {
+        if self.key.is_empty() || self.seq == u64::MAX || ct.len() < NT {
+            return None;
+        }
+        let clen = ct.len() - NT;
+        let mut g = GCM::new();
+        g.init(self.key.len(), &self.key, NN, &self.nonce());
+        g.add_header(aad, aad.len());
+        let mut pt = vec![0; clen];
+        g.add_cipher(&mut pt, &ct[0..clen], clen);
+        let t = g.finish(true);
+        let mut d = 0;
+        for (x, y) in t.iter().zip(ct[clen..].iter()) {
+            d |= x ^ y;
+        }
+        if d != 0 {
+            return None;
+        }
+        self.seq += 1;
+        Some(pt)
+    }
+
+    /// Export L bytes of secret bound to EXPORTER_CONTEXT. L is at most 255 times the
+    /// hash length of the KDF
+    pub fn export(&self, exporter_context: &[u8], l: usize) -> Option<Vec<u8>> {
+        if l > 255 * kdf_length(self.suite.kdf)? {
+            return None;
+        }
+        let sid = self.suite.suite_id();
+        Some(labeled_expand(
+            self.suite.kdf,
+            &sid,
+            &self.exporter_secret,
+            b"sec",
+            exporter_context,
+            l,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::create_rng;
+
+    const INFO: &[u8] = b"Ode on a Grecian Urn";
+    const PSK: &str = "0247fd33b913760fa1fa51e1892d9f307fbe65eb171e8132c2af18555a738b82";
+    const PSK_ID: &[u8] = b"Ennyn Durin aran Moria";
+    const PT: &[u8] = b"Beauty is truth, truth beauty";
+
+    struct Vector {
+        kem: u16,
+        kdf: u16,
+        aead: u16,
+        mode: u8,
+        ikm_e: &'static str,
+        ikm_r: &'static str,
+        ikm_s: &'static str,
+        enc: &'static str,
+        key: &'static str,
+        base_nonce: &'static str,
+        exporter_secret: &'static str,
+        // first encryption, of PT with aad "Count-0"
+        ct: &'static str,
+        // export of "TestContext" with L = 32
+        export: &'static str,
+        // SHA-256 of the export of "TestContext" with L = 255 Nh, from the HKDF of Python's
+        // cryptography package
+        long_export: &'static str,
+    }
+
+    // RFC 9180 Appendix A, for the suites implemented here
+    const VECTORS: [Vector; 10] = [
+        // A.1.1
+        Vector {
+            kem: KEM_X25519_SHA256,
+            kdf: KDF_HKDF_SHA256,
+            aead: AEAD_AES_128_GCM,
+            mode: MODE_BASE,
+            ikm_e: "7268600d403fce431561aef583ee1613527cff655c1343f29812e66706df3234",
+            ikm_r: "6db9df30aa07dd42ee5e8181afdb977e538f5e1fec8a06223f33f7013e525037",
+            ikm_s: "",
+            enc: "37fda3567bdbd628e88668c3c8d7e97d1d1253b6d4ea6d44c150f741f1bf4431",
+            key: "4531685d41d65f03dc48f6b8302c05b0",
+            base_nonce: "56d890e5accaaf011cff4b7d",
+            exporter_secret: "45ff1c2e220db587171952c0592d5f5ebe103f1561a2614e38f2ffd47e99e3f8",
+            ct: "f938558b5d72f1a23810b4be2ab4f84331acc02fc97babc53a52ae8218a355a96d8770ac83d07bea87e13c512a",
+            export: "e9e43065102c3836401bed8c3c3c75ae46be1639869391d62c61f1ec7af54931",
+            long_export: "b45798f4972306cd65c338e6cea462564917fa616e665187cd64a13afe8b8a4f",
+        },
+        // A.1.2
+        Vector {
+            kem: KEM_X25519_SHA256,
+            kdf: KDF_HKDF_SHA256,
+            aead: AEAD_AES_128_GCM,
+            mode: MODE_PSK,
+            ikm_e: "78628c354e46f3e169bd231be7b2ff1c77aa302460a26dbfa15515684c00130b",
+            ikm_r: "d4a09d09f575fef425905d2ab396c1449141463f698f8efdb7accfaff8995098",
+            ikm_s: "",
+            enc: "0ad0950d9fb9588e59690b74f1237ecdf1d775cd60be2eca57af5a4b0471c91b",
+            key: "15026dba546e3ae05836fc7de5a7bb26",
+            base_nonce: "9518635eba129d5ce0914555",
+            exporter_secret: "3d76025dbbedc49448ec3f9080a1abab6b06e91c0b11ad23c912f043a0ee7655",
+            ct: "e52c6fed7f758d0cf7145689f21bc1be6ec9ea097fef4e959440012f4feb73fb611b946199e681f4cfc34db8ea",
+            export: "8aff52b45a1be3a734bc7a41e20b4e055ad4c4d22104b0c20285a7c4302401cd",
+            long_export: "9c6de6a0807c8fa76010794acde2abd740ac96c1bae3ed0c2a8e655d44c7b45b",
+        },
+        // A.1.3
+        Vector {
+            kem: KEM_X25519_SHA256,
+            kdf: KDF_HKDF_SHA256,
+            aead: AEAD_AES_128_GCM,
+            mode: MODE_AUTH,
+            ikm_e: "6e6d8f200ea2fb20c30b003a8b4f433d2f4ed4c2658d5bc8ce2fef718059c9f7",
+            ikm_r: "f1d4a30a4cef8d6d4e3b016e6fd3799ea057db4f345472ed302a67ce1c20cdec",
+            ikm_s: "94b020ce91d73fca4649006c7e7329a67b40c55e9e93cc907d282bbbff386f58",
+            enc: "23fb952571a14a25e3d678140cd0e5eb47a0961bb18afcf85896e5453c312e76",
+            key: "b062cb2c4dd4bca0ad7c7a12bbc341e6",
+            base_nonce: "a1bc314c1942ade7051ffed0",
+            exporter_secret: "ee1a093e6e1c393c162ea98fdf20560c75909653550540a2700511b65c88c6f1",
+            ct: "5fd92cc9d46dbf8943e72a07e42f363ed5f721212cd90bcfd072bfd9f44e06b80fd17824947496e21b680c141b",
+            export: "5a0131813abc9a522cad678eb6bafaabc43389934adb8097d23c5ff68059eb64",
+            long_export: "5c3294e4029a864709e7bbac13644a62e1b96fd9531af0de5425a47a917c69e4",
+        },
+        // A.1.4
+        Vector {
+            kem: KEM_X25519_SHA256,
+            kdf: KDF_HKDF_SHA256,
+            aead: AEAD_AES_128_GCM,
+            mode: MODE_AUTH_PSK,
+            ikm_e: "4303619085a20ebcf18edd22782952b8a7161e1dbae6e46e143a52a96127cf84",
+            ikm_r: "4b16221f3b269a88e207270b5e1de28cb01f847841b344b8314d6a622fe5ee90",
+            ikm_s: "62f77dcf5df0dd7eac54eac9f654f426d4161ec850cc65c54f8b65d2e0b4e345",
+            enc: "820818d3c23993492cc5623ab437a48a0a7ca3e9639c140fe1e33811eb844b7c",
+            key: "1364ead92c47aa7becfa95203037b19a",
+            base_nonce: "99d8b5c54669807e9fc70df1",
+            exporter_secret: "f048d55eacbf60f9c6154bd4021774d1075ebf963c6adc71fa846f183ab2dde6",
+            ct: "a84c64df1e11d8fd11450039d4fe64ff0c8a99fca0bd72c2d4c3e0400bc14a40f27e45e141a24001697737533e",
+            export: "a30c20370c026bbea4dca51cb63761695132d342bae33a6a11527d3e7679436d",
+            long_export: "435f151df6b273608c89e03427df8b81d0c9dacc5de80569271eec67d65eea25",
+        },
+        // A.3.1
+        Vector {
+            kem: KEM_P256_SHA256,
+            kdf: KDF_HKDF_SHA256,
+            aead: AEAD_AES_128_GCM,
+            mode: MODE_BASE,
+            ikm_e: "4270e54ffd08d79d5928020af4686d8f6b7d35dbe470265f1f5aa22816ce860e",
+            ikm_r: "668b37171f1072f3cf12ea8a236a45df23fc13b82af3609ad1e354f6ef817550",
+            ikm_s: "",
+            enc: "04a92719c6195d5085104f469a8b9814d5838ff72b60501e2c4466e5e67b325ac98536d7b61a1af4b78e5b7f951c0900be863c403ce65c9bfcb9382657222d18c4",
+            key: "868c066ef58aae6dc589b6cfdd18f97e",
+            base_nonce: "4e0bc5018beba4bf004cca59",
+            exporter_secret: "14ad94af484a7ad3ef40e9f3be99ecc6fa9036df9d4920548424df127ee0d99f",
+            ct: "5ad590bb8baa577f8619db35a36311226a896e7342a6d836d8b7bcd2f20b6c7f9076ac232e3ab2523f39513434",
+            export: "d8f1ea7942adbba7412c6d431c62d01371ea476b823eb697e1f6e6cae1dab85a",
+            long_export: "9ad2a2cfea123a16e85ec06247140dfc690d3478f16fc3ab89f42b8a47826440",
+        },
+        // A.3.2
+        Vector {
+            kem: KEM_P256_SHA256,
+            kdf: KDF_HKDF_SHA256,
+            aead: AEAD_AES_128_GCM,
+            mode: MODE_PSK,
+            ikm_e: "2afa611d8b1a7b321c761b483b6a053579afa4f767450d3ad0f84a39fda587a6",
+            ikm_r: "d42ef874c1913d9568c9405407c805baddaffd0898a00f1e84e154fa787b2429",
+            ikm_s: "",
+            enc: "04305d35563527bce037773d79a13deabed0e8e7cde61eecee403496959e89e4d0ca701726696d1485137ccb5341b3c1c7aaee90a4a02449725e744b1193b53b5f",
+            key: "55d9eb9d26911d4c514a990fa8d57048",
+            base_nonce: "b595dc6b2d7e2ed23af529b1",
+            exporter_secret: "895a723a1eab809804973a53c0ee18ece29b25a7555a4808277ad2651d66d705",
+            ct: "90c4deb5b75318530194e4bb62f890b019b1397bbf9d0d6eb918890e1fb2be1ac2603193b60a49c2126b75d0eb",
+            export: "164e02144d44b607a7722e58b0f4156e67c0c2874d74cf71da6ca48a4cbdc5e0",
+            long_export: "0357dd9e3d8e970dbee7cf3c6e65ffbfa1541d02d9b9f05b4675f5a298455d56",
+        },
+        // A.3.3
+        Vector {
+            kem: KEM_P256_SHA256,
+            kdf: KDF_HKDF_SHA256,
+            aead: AEAD_AES_128_GCM,
+            mode: MODE_AUTH,
+            ikm_e: "798d82a8d9ea19dbc7f2c6dfa54e8a6706f7cdc119db0813dacf8440ab37c857",
+            ikm_r: "7bc93bde8890d1fb55220e7f3b0c107ae7e6eda35ca4040bb6651284bf0747ee",
+            ikm_s: "874baa0dcf93595a24a45a7f042e0d22d368747daaa7e19f80a802af19204ba8",
+            enc: "042224f3ea800f7ec55c03f29fc9865f6ee27004f818fcbdc6dc68932c1e52e15b79e264a98f2c535ef06745f3d308624414153b22c7332bc1e691cb4af4d53454",
+            key: "19aa8472b3fdc530392b0e54ca17c0f5",
+            base_nonce: "b390052d26b67a5b8a8fcaa4",
+            exporter_secret: "f152759972660eb0e1db880835abd5de1c39c8e9cd269f6f082ed80e28acb164",
+            ct: "82ffc8c44760db691a07c5627e5fc2c08e7a86979ee79b494a17cc3405446ac2bdb8f265db4a099ed3289ffe19",
+            export: "14fe634f95ca0d86e15247cca7de7ba9b73c9b9deb6437e1c832daf7291b79d5",
+            long_export: "698c1c54aeba8478d8b43c4c594486b7089ae909318663d104dea2cc750b1c05",
+        },
+        // A.4.1
+        Vector {
+            kem: KEM_P256_SHA256,
+            kdf: KDF_HKDF_SHA512,
+            aead: AEAD_AES_128_GCM,
+            mode: MODE_BASE,
+            ikm_e: "4ab11a9dd78c39668f7038f921ffc0993b368171d3ddde8031501ee1e08c4c9a",
+            ikm_r: "ea9ff7cc5b2705b188841c7ace169290ff312a9cb31467784ca92d7a2e6e1be8",
+            ikm_s: "",
+            enc: "0493ed86735bdfb978cc055c98b45695ad7ce61ce748f4dd63c525a3b8d53a15565c6897888070070c1579db1f86aaa56deb8297e64db7e8924e72866f9a472580",
+            key: "090ca96e5f8aa02b69fac360da50ddf9",
+            base_nonce: "9c995e621bf9a20c5ca45546",
+            exporter_secret: "4a7abb2ac43e6553f129b2c5750a7e82d149a76ed56dc342d7bca61e26d494f4855dff0d0165f27ce57756f7f16baca006539bb8e4518987ba610480ac03efa8",
+            ct: "d3cf4984931484a080f74c1bb2a6782700dc1fef9abe8442e44a6f09044c88907200b332003543754eb51917ba",
+            export: "93fb9411430b2cfa2cf0bed448c46922a5be9beff20e2e621df7e4655852edbc",
+            long_export: "2e210e57ecc5fef4386cb8924012570135b6c51d9fa22e080773cb315d9b80d0",
+        },
+        // A.6.1
+        Vector {
+            kem: KEM_P521_SHA512,
+            kdf: KDF_HKDF_SHA512,
+            aead: AEAD_AES_256_GCM,
+            mode: MODE_BASE,
+            ikm_e: "7f06ab8215105fc46aceeb2e3dc5028b44364f960426eb0d8e4026c2f8b5d7e7a986688f1591abf5ab753c357a5d6f0440414b4ed4ede71317772ac98d9239f70904",
+            ikm_r: "2ad954bbe39b7122529f7dde780bff626cd97f850d0784a432784e69d86eccaade43b6c10a8ffdb94bf943c6da479db137914ec835a7e715e36e45e29b587bab3bf1",
+            ikm_s: "",
+            enc: "040138b385ca16bb0d5fa0c0665fbbd7e69e3ee29f63991d3e9b5fa740aab8900aaeed46ed73a49055758425a0ce36507c54b29cc5b85a5cee6bae0cf1c21f2731ece2013dc3fb7c8d21654bb161b463962ca19e8c654ff24c94dd2898de12051f1ed0692237fb02b2f8d1dc1c73e9b366b529eb436e98a996ee522aef863dd5739d2f29b0",
+            key: "751e346ce8f0ddb2305c8a2a85c70d5cf559c53093656be636b9406d4d7d1b70",
+            base_nonce: "55ff7a7d739c69f44b25447b",
+            exporter_secret: "e4ff9dfbc732a2b9c75823763c5ccc954a2c0648fc6de80a58581252d0ee3215388a4455e69086b50b87eb28c169a52f42e71de4ca61c920e7bd24c95cc3f992",
+            ct: "170f8beddfe949b75ef9c387e201baf4132fa7374593dfafa90768788b7b2b200aafcc6d80ea4c795a7c5b841a",
+            export: "f389beaac6fcf6c0d9376e20f97e364f0609a88f1bc76d7328e9104df8477013",
+            long_export: "e91a906660ec215bb4b695f941fa11f360bb562a183ed6c376ee995d0c67dd23",
+        },
+        // A.7.1
+        Vector {
+            kem: KEM_X25519_SHA256,
+            kdf: KDF_HKDF_SHA256,
+            aead: AEAD_EXPORT_ONLY,
+            mode: MODE_BASE,
+            ikm_e: "55bc245ee4efda25d38f2d54d5bb6665291b99f8108a8c4b686c2b14893ea5d9",
+            ikm_r: "683ae0da1d22181e74ed2e503ebf82840deb1d5e872cade20f4b458d99783e31",
+            ikm_s: "",
+            enc: "e5e8f9bfff6c2f29791fc351d2c25ce1299aa5eaca78a757c0b4fb4bcd830918",
+            key: "",
+            base_nonce: "",
+            exporter_secret: "79dc8e0509cf4a3364ca027e5a0138235281611ca910e435e8ed58167c72f79b",
+            ct: "",
+            export: "ffaabc85a776136ca0c378e5d084c9140ab552b78f039d2e8775f26efff4c70e",
+            long_export: "be826af7c6427f39ad3747cc81618e88d03d3ae1fbdc3289827ca86567cdb345",
+        },
+    ];
+
+    // KEMs of the curves in this build. Every vector of these must run
+    fn kem_enabled(kem: u16) -> bool {
+        (kem == KEM_P256_SHA256 && cfg!(feature = "nist256"))
+            || (kem == KEM_P521_SHA512 && cfg!(feature = "nist521"))
+            || (kem == KEM_X25519_SHA256 && cfg!(feature = "c25519"))
+    }
+
+    #[test]
+    fn test_vectors() {
+        let psk = hex::decode(PSK).unwrap();
+        for v in VECTORS.iter().filter(|v| kem_enabled(v.kem)) {
+            let suite = Suite::new(v.kem, v.kdf, v.aead).unwrap();
+            let (psk, psk_id) = if v.mode & MODE_PSK != 0 {
+                (&psk[..], PSK_ID)
+            } else {
+                (&[][..], &[][..])
+            };
+            let (skr, pkr) = suite
+                .derive_key_pair(&hex::decode(v.ikm_r).unwrap())
+                .unwrap();
+            let auth = if v.mode & MODE_AUTH != 0 {
+                suite.derive_key_pair(&hex::decode(v.ikm_s).unwrap())
+            } else {
+                None
+            };
+            let sks = auth.as_ref().map(|k| &k.0[..]);
+            let pks = auth.as_ref().map(|k| &k.1[..]);
+
+            let ikme = hex::decode(v.ikm_e).unwrap();
+            let (enc, mut ctx_s) = suite
+                .setup_sender(v.mode, &ikme, &pkr, INFO, psk, psk_id, sks)
+                .unwrap();
+            assert_eq!(hex::encode(&enc), v.enc);
+            let mut ctx_r = suite
+                .setup_receiver(v.mode, &enc, &skr, INFO, psk, psk_id, pks)
+                .unwrap();
+            for ctx in [&ctx_s, &ctx_r].iter() {
+                assert_eq!(hex::encode(&ctx.key), v.key);
+                assert_eq!(hex::encode(&ctx.base_nonce), v.base_nonce);
+                assert_eq!(hex::encode(&ctx.exporter_secret), v.exporter_secret);
+                assert_eq!(
+                    hex::encode(ctx.export(b"TestContext", 32).unwrap()),
+                    v.export
+                );
+                let nh = kdf_length(v.kdf).unwrap();
+                let mut h = HASH256::new();
+                h.process_array(&ctx.export(b"TestContext", 255 * nh).unwrap());
+                assert_eq!(hex::encode(h.hash()), v.long_export);
+                assert!(ctx.export(b"TestContext", 255 * nh + 1).is_none());
+            }
+
+            if v.aead == AEAD_EXPORT_ONLY {
+                assert!(ctx_s.seal(b"Count-0", PT).is_none());
+            } else {
+                let ct = ctx_s.seal(b"Count-0", PT).unwrap();
+                assert_eq!(hex::encode(&ct), v.ct);
+                assert_eq!(ctx_r.open(b"Count-0", &ct).unwrap(), PT);
+                let ct = ctx_s.seal(b"Count-1", PT).unwrap();
+                assert_eq!(ctx_r.open(b"Count-1", &ct).unwrap(), PT);
+            }
+        }
+    }
+
+    #[test]
+    fn test_modes() {
+        let mut rng = create_rng();
+        let psk = [7u8; 32];
+        for kem in [
+            KEM_P256_SHA256,
+            KEM_P384_SHA384,
+            KEM_P521_SHA512,
+            KEM_X25519_SHA256,
+        ]
+        .iter()
+        {
+            let suite = match Suite::new(*kem, KDF_HKDF_SHA256, AEAD_AES_256_GCM) {
+                Some(s) => s,
+                None => continue,
+            };
+            let (skr, pkr) = suite.generate_key_pair(&mut rng).unwrap();
+            let (sks, pks) = suite.generate_key_pair(&mut rng).unwrap();
+            let (_, pkx) = suite.generate_key_pair(&mut rng).unwrap();
+
+            let (enc, mut s) = suite.setup_base_s(&mut rng, &pkr, INFO).unwrap();
+            let mut r = suite.setup_base_r(&enc, &skr, INFO).unwrap();
+            let ct = s.seal(b"", PT).unwrap();
+            assert_eq!(r.open(b"", &ct).unwrap(), PT);
+
+            let (enc, mut s) = suite
+                .setup_psk_s(&mut rng, &pkr, INFO, &psk, PSK_ID)
+                .unwrap();
+            let mut r = suite.setup_psk_r(&enc, &skr, INFO, &psk, PSK_ID).unwrap();
+            let ct = s.seal(b"", PT).unwrap();
+            assert_eq!(r.open(b"", &ct).unwrap(), PT);
+
+            let (enc, mut s) = suite.setup_auth_s(&mut rng, &pkr, INFO, &sks).unwrap();
+            let mut r = suite.setup_auth_r(&enc, &skr, INFO, &pks).unwrap();
+            let ct = s.seal(b"aad", PT).unwrap();
+            assert!(r.open(b"bad", &ct).is_none());
+            assert_eq!(r.open(b"aad", &ct).unwrap(), PT);
+            // Wrong sender key
+            let mut r = suite.setup_auth_r(&enc, &skr, INFO, &pkx).unwrap();
+            assert!(r.open(b"aad", &ct).is_none());
+
+            let (enc, mut s) = suite
+                .setup_auth_psk_s(&mut rng, &pkr, INFO, &psk, PSK_ID, &sks)
+                .unwrap();
+            let mut r = suite
+                .setup_auth_psk_r(&enc, &skr, INFO, &psk, PSK_ID, &pks)
+                .unwrap();
+            let ct = s.seal(b"", PT).unwrap();
+            assert_eq!(r.open(b"", &ct).unwrap(), PT);
+            assert_eq!(s.export(b"x", 255), r.export(b"x", 255));
+
+            // Inconsistent PSK inputs and bad encapsulations
+            assert!(suite.setup_psk_s(&mut rng, &pkr, INFO, &psk, &[]).is_none());
+            assert!(suite.setup_psk_s(&mut rng, &pkr, INFO, &[], &[]).is_none());
+            assert!(suite
+                .setup_sender(MODE_BASE, &[1], &pkr, INFO, &psk, PSK_ID, None)
+                .is_none());
+            assert!(suite.setup_base_r(&enc[1..], &skr, INFO).is_none());
+        }
+        assert!(Suite::new(0x0021, KDF_HKDF_SHA256, AEAD_AES_128_GCM).is_none());
+        assert!(Suite::new(KEM_P256_SHA256, 4, AEAD_AES_128_GCM).is_none());
+        assert!(Suite::new(KEM_P256_SHA256, KDF_HKDF_SHA256, 3).is_none());
+    }
+}
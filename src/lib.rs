@@ -8,11 +8,20 @@ pub mod arch;
 #[cfg(target_pointer_width = "64")]
 #[path = "arch/arch64.rs"]
 pub mod arch;
+pub mod der;
 pub mod gcm;
 pub mod hash256;
 pub mod hash384;
 pub mod hash512;
+#[cfg(any(
+    feature = "c25519",
+    feature = "nist256",
+    feature = "nist384",
+    feature = "nist521"
+))]
+pub mod hpke;
 pub mod nhs;
+pub mod pem;
 pub mod rand;
 pub mod sha3;
 pub mod types;
@@ -196,6 +205,7 @@ pub mod anssi {
     pub mod ecdh;
     pub mod ecp;
     pub mod fp;
+    pub mod sec1;
 }
 
 #[cfg(feature = "brainpool")]
@@ -211,8 +221,10 @@ pub mod brainpool {
     pub mod big;
     pub mod dbig;
     pub mod ecdh;
+    pub mod eckey;
     pub mod ecp;
     pub mod fp;
+    pub mod sec1;
 }
 
 #[cfg(feature = "goldilocks")]
@@ -227,9 +239,14 @@ pub mod goldilocks {
 
     pub mod big;
     pub mod dbig;
+    pub mod decaf448;
     pub mod ecdh;
     pub mod ecp;
+    pub mod eddsa;
     pub mod fp;
+    pub mod sec1;
+    #[path = "xdh.rs"]
+    pub mod x448;
 }
 
 #[cfg(feature = "hifive")]
@@ -247,6 +264,7 @@ pub mod hifive {
     pub mod ecdh;
     pub mod ecp;
     pub mod fp;
+    pub mod sec1;
 }
 
 #[cfg(feature = "nist256")]
@@ -262,8 +280,10 @@ pub mod nist256 {
     pub mod big;
     pub mod dbig;
     pub mod ecdh;
+    pub mod eckey;
     pub mod ecp;
     pub mod fp;
+    pub mod sec1;
 }
 
 #[cfg(feature = "nist384")]
@@ -279,8 +299,10 @@ pub mod nist384 {
     pub mod big;
     pub mod dbig;
     pub mod ecdh;
+    pub mod eckey;
     pub mod ecp;
     pub mod fp;
+    pub mod sec1;
 }
 
 #[cfg(feature = "nist521")]
@@ -296,8 +318,10 @@ pub mod nist521 {
     pub mod big;
     pub mod dbig;
     pub mod ecdh;
+    pub mod eckey;
     pub mod ecp;
     pub mod fp;
+    pub mod sec1;
 }
 
 #[cfg(feature = "nums256e")]
@@ -315,6 +339,7 @@ pub mod nums256e {
     pub mod ecdh;
     pub mod ecp;
     pub mod fp;
+    pub mod sec1;
 }
 
 #[cfg(feature = "nums256w")]
@@ -332,6 +357,7 @@ pub mod nums256w {
     pub mod ecdh;
     pub mod ecp;
     pub mod fp;
+    pub mod sec1;
 }
 
 #[cfg(feature = "nums384e")]
@@ -349,6 +375,7 @@ pub mod nums384e {
     pub mod ecdh;
     pub mod ecp;
     pub mod fp;
+    pub mod sec1;
 }
 
 #[cfg(feature = "nums384w")]
@@ -366,6 +393,7 @@ pub mod nums384w {
     pub mod ecdh;
     pub mod ecp;
     pub mod fp;
+    pub mod sec1;
 }
 
 #[cfg(feature = "nums512w")]
@@ -383,6 +411,7 @@ pub mod nums512w {
     pub mod ecdh;
     pub mod ecp;
     pub mod fp;
+    pub mod sec1;
 }
 
 #[cfg(feature = "nums512e")]
@@ -400,6 +429,7 @@ pub mod nums512e {
     pub mod ecdh;
     pub mod ecp;
     pub mod fp;
+    pub mod sec1;
 }
 
 #[cfg(feature = "secp256k1")]
@@ -415,8 +445,11 @@ pub mod secp256k1 {
     pub mod big;
     pub mod dbig;
     pub mod ecdh;
+    pub mod eckey;
     pub mod ecp;
     pub mod fp;
+    pub mod schnorr;
+    pub mod sec1;
 }
 
 #[cfg(feature = "c25519")]
@@ -434,6 +467,9 @@ pub mod c25519 {
     pub mod ecdh;
     pub mod ecp;
     pub mod fp;
+    pub mod sec1;
+    #[path = "xdh.rs"]
+    pub mod x25519;
 }
 
 #[cfg(feature = "c41417")]
@@ -451,6 +487,7 @@ pub mod c41417 {
     pub mod ecdh;
     pub mod ecp;
     pub mod fp;
+    pub mod sec1;
 }
 
 #[cfg(feature = "ed25519")]
@@ -467,7 +504,10 @@ pub mod ed25519 {
     pub mod dbig;
     pub mod ecdh;
     pub mod ecp;
+    pub mod eddsa;
     pub mod fp;
+    pub mod ristretto255;
+    pub mod sec1;
 }
 
 #[cfg(feature = "bn254CX")]
@@ -492,6 +532,7 @@ pub mod bn254CX {
     pub mod fp4;
     pub mod mpin;
     pub mod pair;
+    pub mod sec1;
 }
 
 #[cfg(feature = "bn254")]
@@ -516,6 +557,7 @@ pub mod bn254 {
     pub mod fp4;
     pub mod mpin;
     pub mod pair;
+    pub mod sec1;
 }
 
 #[cfg(feature = "rsa2048")]
@@ -531,6 +573,10 @@ pub mod rsa2048 {
     #[path = "roms/rom_rsa2048_64.rs"]
     pub mod rom;
     pub mod rsa;
+    pub mod rsabssa;
+    pub mod paillier;
+    pub mod ffdh;
+    pub mod primes;
 }
 
 #[cfg(feature = "rsa3072")]
@@ -546,6 +592,10 @@ pub mod rsa3072 {
     #[path = "roms/rom_rsa3072_64.rs"]
     pub mod rom;
     pub mod rsa;
+    pub mod rsabssa;
+    pub mod paillier;
+    pub mod ffdh;
+    pub mod primes;
 }
 
 #[cfg(feature = "rsa4096")]
@@ -561,4 +611,8 @@ pub mod rsa4096 {
     #[path = "roms/rom_rsa4096_64.rs"]
     mod rom;
     pub mod rsa;
+    pub mod rsabssa;
+    pub mod paillier;
+    pub mod ffdh;
+    pub mod primes;
 }
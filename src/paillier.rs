@@ -0,0 +1,652 @@
+/*
+Licensed to the Apache Software Foundation (ASF) under one
+or more contributor license agreements.  See the NOTICE file
+distributed with this work for additional information
+regarding copyright ownership.  The ASF licenses this file
+to you under the Apache License, Version 2.0 (the
+"License"); you may not use this file except in compliance
+with the License.  You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing,
+software distributed under the License is distributed on an
+"AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+KIND, either express or implied.  See the License for the
+specific language governing permissions and limitations
+under the License.
+*/
+
+/* Paillier additively homomorphic encryption, with g=n+1. Plaintexts are integers mod n
+and ciphertexts integers mod n^2, both big-endian and of fixed length. Decryption uses the
+CRT. A non-interactive proof shows that a ciphertext is well-formed, by knowledge of its
+plaintext and nonce */
+
+use super::big;
+use super::ff::FF;
+
+use crate::hash256::HASH256;
+use crate::rand::RAND;
+
+/* modulus bit lengths for keys and new_key_pair */
+pub const MIN_BITS: usize = 2048;
+pub const MAX_BITS: usize = 4096;
+
+const PROOF_LABEL: &[u8] = b"Paillier proof of plaintext knowledge";
+
+pub struct PaillierPublicKey {
+    n: FF,
+    n2: FF,
+}
+
+pub struct PaillierPrivateKey {
+    p: FF,
+    q: FF,
+    p2: FF,
+    q2: FF,
+    hp: FF,
+    hq: FF,
+    c: FF,
+    n: FF,
+}
+
+/* X mod 2^m, for m the bits in the words of X. lmul can leave bits above that */
+fn low(x: &FF) -> FF {
+    let mut b = vec![0; x.getlen() * big::MODBYTES];
+    x.to_be_bytes(&mut b);
+    FF::from_be_bytes(&b, x.getlen())
+}
+
+/* true if X is coprime to N. FF::gcd is not constant time, so X must be public */
+fn coprime(x: &FF, n: &FF) -> bool {
+    let mut one = FF::new_int(n.getlen());
+    one.one();
+    FF::comp(&FF::gcd(&FF::resize(x, n.getlen()), n), &one) == 0
+}
+
+/* modulus length in bytes. Plaintexts and nonces are this long, ciphertexts twice, and
+proofs four times */
+pub fn public_key_bytes(pbc: &PaillierPublicKey) -> usize {
+    (pbc.n.nbits() + 7) / 8
+}
+
+pub fn ciphertext_bytes(pbc: &PaillierPublicKey) -> usize {
+    2 * public_key_bytes(pbc)
+}
+
+pub fn proof_bytes(pbc: &PaillierPublicKey) -> usize {
+    4 * public_key_bytes(pbc)
+}
+
+/* public key from the big-endian modulus N, odd and of MIN_BITS to MAX_BITS bits. That N
+is the product of two primes is not checked */
+pub fn public_key_from_modulus(n: &[u8]) -> Option<PaillierPublicKey> {
+    let i = n.iter().take_while(|&&x| x == 0).count();
+    if i == n.len() {
+        return None;
+    }
+    let nb = 8 * (n.len() - i) - n[i].leading_zeros() as usize;
+    if !(MIN_BITS..=MAX_BITS).contains(&nb) || n[n.len() - 1] & 1 == 0 {
+        return None;
+    }
+    let x = FF::from_be_bytes(&n[i..], (nb + big::BIGBITS - 1) / big::BIGBITS);
+    Some(new_public_key(&x))
+}
+
+/* big-endian modulus */
+pub fn public_key_modulus(pbc: &PaillierPublicKey) -> Vec<u8> {
+    let mut n = vec![0; public_key_bytes(pbc)];
+    pbc.n.to_be_bytes(&mut n);
+    n
+}
+
+fn new_public_key(n: &FF) -> PaillierPublicKey {
+    let w = n.getlen();
+    let mut n2 = FF::sqr(n);
+    n2.norm();
+    PaillierPublicKey {
+        n: FF::resize(n, w),
+        n2,
+    }
+}
+
+pub fn private_key_to_public(prv: &PaillierPrivateKey) -> PaillierPublicKey {
+    new_public_key(&prv.n)
+}
+
+/* private key from the big-endian primes P and Q, distinct, odd and of the same length.
+Neither their primality nor that they are coprime is checked */
+pub fn private_key_from_primes(p: &[u8], q: &[u8]) -> Option<PaillierPrivateKey> {
+    let i = p.iter().take_while(|&&x| x == 0).count();
+    let j = q.iter().take_while(|&&x| x == 0).count();
+    if p.len() - i != q.len() - j || i == p.len() {
+        return None;
+    }
+    let h = (p.len() - i + big::MODBYTES - 1) / big::MODBYTES;
+    let x = FF::from_be_bytes(&p[i..], h);
+    let y = FF::from_be_bytes(&q[j..], h);
+    let nb = FF::mul(&x, &y).nbits();
+    if x.parity() == 0 || y.parity() == 0 || FF::comp(&x, &y) == 0 {
+        return None;
+    }
+    if !(MIN_BITS..=MAX_BITS).contains(&nb) {
+        return None;
+    }
+    Some(new_private_key(&x, &y))
+}
+
+/* L_p(g^(p-1) mod p^2)^-1 mod p for g=n+1, which is (-q)^-1 mod p */
+fn crt_coefficient(p: &FF, q: &FF) -> FF {
+    let mut t = FF::new_int(p.getlen());
+    t.copy(p);
+    t.sub(&FF::resize(q, 2 * p.getlen()).dmod(p));
+    t.norm();
+    t.invmodp(p);
+    t
+}
+
+fn new_private_key(p: &FF, q: &FF) -> PaillierPrivateKey {
+    let h = p.getlen();
+    let mut prv = PaillierPrivateKey {
+        p: FF::resize(p, h),
+        q: FF::resize(q, h),
+        p2: FF::sqr(p),
+        q2: FF::sqr(q),
+        hp: crt_coefficient(p, q),
+        hq: crt_coefficient(q, p),
+        c: FF::resize(p, 2 * h).dmod(q),
+        n: FF::mul(p, q),
+    };
+    prv.p2.norm();
+    prv.q2.norm();
+    prv.n.norm();
+    prv.c.invmodp(q);
+    prv
+}
+
+/* prime P of NB bits with P=3 mod 4 */
+fn random_prime(rng: &mut RAND, nb: usize, p: &mut FF) {
+    p.random_bits(rng, nb);
+    while p.lastbits(2) != 3 {
+        p.inc(1)
+    }
+    while !FF::prime(p, rng) {
+        p.inc(4);
+    }
+}
+
+/* key pair with an NB bit modulus, from MIN_BITS to MAX_BITS and even. The primes are of
+equal length, so that n is coprime to (p-1)(q-1) */
+pub fn new_key_pair(rng: &mut RAND, nb: usize) -> Option<(PaillierPrivateKey, PaillierPublicKey)> {
+    if !(MIN_BITS..=MAX_BITS).contains(&nb) || nb % 2 != 0 {
+        return None;
+    }
+    let h = (nb / 2 + big::BIGBITS - 1) / big::BIGBITS;
+    let mut p = FF::new_int(h);
+    let mut q = FF::new_int(h);
+    random_prime(rng, nb / 2, &mut p);
+    loop {
+        random_prime(rng, nb / 2, &mut q);
+        if FF::comp(&p, &q) != 0 {
+            break;
+        }
+    }
+    let prv = new_private_key(&p, &q);
+    let pbc = private_key_to_public(&prv);
+    p.zero();
+    q.zero();
+    Some((prv, pbc))
+}
+
+/* destroy the private key */
+pub fn private_key_kill(prv: &mut PaillierPrivateKey) {
+    prv.p.zero();
+    prv.q.zero();
+    prv.p2.zero();
+    prv.q2.zero();
+    prv.hp.zero();
+    prv.hq.zero();
+    prv.c.zero();
+    prv.n.zero();
+}
+
+/* ciphertext C as an FF, if it is of the right length and less than n^2 */
+fn ciphertext(pbc: &PaillierPublicKey, c: &[u8]) -> Option<FF> {
+    if c.len() != ciphertext_bytes(pbc) {
+        return None;
+    }
+    let x = FF::from_be_bytes(c, pbc.n2.getlen());
+    if FF::comp(&x, &pbc.n2) >= 0 {
+        return None;
+    }
+    Some(x)
+}
+
+/* plaintext or nonce X as an FF, if it is of the right length and less than n */
+fn residue(pbc: &PaillierPublicKey, x: &[u8]) -> Option<FF> {
+    if x.len() != public_key_bytes(pbc) {
+        return None;
+    }
+    let y = FF::from_be_bytes(x, pbc.n.getlen());
+    if FF::comp(&y, &pbc.n) >= 0 {
+        return None;
+    }
+    Some(y)
+}
+
+/* (1+n)^m.r^n mod n^2, as (1+m.n).r^n */
+fn gm_rn(pbc: &PaillierPublicKey, m: &FF, r: &FF) -> FF {
+    let w = pbc.n2.getlen();
+    let mut g = FF::mul(m, &pbc.n);
+    g.inc(1);
+    g.norm();
+    let mut x = FF::resize(r, w);
+    x.pow(&FF::resize(&pbc.n, w), &pbc.n2);
+    FF::mul(&g, &x).dmod(&pbc.n2)
+}
+
+/* a random nonce R, 0 < R < n. R is the length of the modulus. It is coprime to n but
+with negligible probability, so that is not checked */
+pub fn random_nonce(pbc: &PaillierPublicKey, rng: &mut RAND, r: &mut [u8]) -> bool {
+    if r.len() != public_key_bytes(pbc) {
+        return false;
+    }
+    let mut x = FF::new_int(pbc.n.getlen());
+    while x.iszilch() {
+        x.randomnum(&pbc.n, rng);
+    }
+    x.to_be_bytes(r);
+    x.zero();
+    true
+}
+
+/* encryption C=(1+n)^M.R^n mod n^2 of the plaintext M with the nonce R, both less than n
+and the length of the modulus. R must be coprime to n, which is checked on the ciphertext
+rather than on the secret R, as C mod n=R^n mod n */
+pub fn encrypt_with_nonce(pbc: &PaillierPublicKey, m: &[u8], r: &[u8], c: &mut [u8]) -> bool {
+    let x = match residue(pbc, m) {
+        Some(x) => x,
+        None => return false,
+    };
+    let mut y = match residue(pbc, r) {
+        Some(y) => y,
+        None => return false,
+    };
+    if c.len() != ciphertext_bytes(pbc) || y.iszilch() {
+        return false;
+    }
+    let mut z = gm_rn(pbc, &x, &y);
+    z.to_be_bytes(c);
+    if !coprime(&z.dmod(&pbc.n), &pbc.n) {
+        c.iter_mut().for_each(|x| *x = 0);
+        return false;
+    }
+    true
+}
+
+/* encryption of M with a random nonce. See encrypt_with_nonce */
+pub fn encrypt(pbc: &PaillierPublicKey, rng: &mut RAND, m: &[u8], c: &mut [u8]) -> bool {
+    let mut r = vec![0; public_key_bytes(pbc)];
+    let ok = random_nonce(pbc, rng, &mut r) && encrypt_with_nonce(pbc, m, &r, c);
+    r.iter_mut().for_each(|x| *x = 0);
+    ok
+}
+
+/* L_p(c^(p-1) mod p^2).hp mod p, with L_p(x)=(x-1)/p found exactly as (x-1)/p mod 2^m */
+fn decrypt_mod(c: &FF, p: &FF, p2: &FF, hp: &FF) -> FF {
+    let h = p.getlen();
+    let mut e = FF::resize(p, 2 * h);
+    e.dec(1);
+    let mut x = FF::resize(c, 4 * h).dmod(p2);
+    x.skpow(&e, p2);
+    x.dec(1);
+    x.norm();
+    let mut l = FF::resize(&x, h);
+    l.lmul(&p.invmod2m());
+    l.norm();
+    FF::mul(&low(&l), hp).dmod(p)
+}
+
+/* decryption of the ciphertext C into M, the length of the modulus */
+pub fn decrypt(prv: &PaillierPrivateKey, c: &[u8], m: &mut [u8]) -> bool {
+    let pbc = private_key_to_public(prv);
+    let x = match ciphertext(&pbc, c) {
+        Some(x) => x,
+        None => return false,
+    };
+    if m.len() != public_key_bytes(&pbc) {
+        return false;
+    }
+    let mp = decrypt_mod(&x, &prv.p, &prv.p2, &prv.hp);
+    let mut mq = decrypt_mod(&x, &prv.q, &prv.q2, &prv.hq);
+
+    /* m=mp+p.((mq-mp).p^-1 mod q) */
+    let mut r = FF::new_int(2 * prv.p.getlen());
+    r.dscopy(&mp);
+    if FF::comp(&mp, &mq) > 0 {
+        mq.add(&prv.q)
+    }
+    mq.sub(&mp);
+    mq.norm();
+    let mut t = FF::mul(&prv.c, &mq);
+    mq = t.dmod(&prv.q);
+    t = FF::mul(&mq, &prv.p);
+    r.add(&t);
+    r.norm();
+    r.to_be_bytes(m);
+    r.zero();
+    true
+}
+
+/* homomorphic addition. C encrypts the sum mod n of the plaintexts of C1 and C2 */
+pub fn add(pbc: &PaillierPublicKey, c1: &[u8], c2: &[u8], c: &mut [u8]) -> bool {
+    let x = match ciphertext(pbc, c1) {
+        Some(x) => x,
+        None => return false,
+    };
+    let y = match ciphertext(pbc, c2) {
+        Some(y) => y,
+        None => return false,
+    };
+    if c.len() != ciphertext_bytes(pbc) {
+        return false;
+    }
+    FF::mul(&x, &y).dmod(&pbc.n2).to_be_bytes(c);
+    true
+}
+
+/* homomorphic scalar multiplication. C encrypts K times the plaintext of C1 mod n. K is
+less than n and the length of the modulus, and is treated as secret */
+pub fn scalar_mul(pbc: &PaillierPublicKey, c1: &[u8], k: &[u8], c: &mut [u8]) -> bool {
+    let mut x = match ciphertext(pbc, c1) {
+        Some(x) => x,
+        None => return false,
+    };
+    let y = match residue(pbc, k) {
+        Some(y) => y,
+        None => return false,
+    };
+    if c.len() != ciphertext_bytes(pbc) {
+        return false;
+    }
+    x.skpow(&FF::resize(&y, pbc.n2.getlen()), &pbc.n2);
+    x.to_be_bytes(c);
+    true
+}
+
+/* true if C is a valid ciphertext, less than n^2 and coprime to n */
+pub fn valid_ciphertext(pbc: &PaillierPublicKey, c: &[u8]) -> bool {
+    match ciphertext(pbc, c) {
+        Some(mut x) => coprime(&x.dmod(&pbc.n), &pbc.n),
+        None => false,
+    }
+}
+
+/* 256-bit Fiat-Shamir challenge on the key, ciphertext, commitment and context */
+fn challenge(pbc: &PaillierPublicKey, c: &[u8], a: &[u8], ctx: &[u8]) -> FF {
+    let mut h = HASH256::new();
+    h.process_array(PROOF_LABEL);
+    h.process_array(&public_key_modulus(pbc));
+    h.process_array(c);
+    h.process_array(a);
+    h.process_array(ctx);
+    FF::from_be_bytes(&h.hash(), pbc.n.getlen())
+}
+
+/* Proof that C encrypts M with the nonce R, without revealing either. This is the sigma
+protocol for knowledge of an n-th root (Damgard-Jurik), made non-interactive with a
+challenge e that also covers CTX, which binds the proof to a session. The proof is the
+commitment A=(1+n)^a.b^n mod n^2, z=a+e.M mod n and w=b.R^e mod n, for random a and b.
+As for random_nonce, b is not checked to be coprime to n */
+pub fn prove(
+    pbc: &PaillierPublicKey,
+    rng: &mut RAND,
+    m: &[u8],
+    r: &[u8],
+    c: &[u8],
+    ctx: &[u8],
+    proof: &mut [u8],
+) -> bool {
+    let k = public_key_bytes(pbc);
+    let mut x = match residue(pbc, m) {
+        Some(x) => x,
+        None => return false,
+    };
+    let mut y = match residue(pbc, r) {
+        Some(y) => y,
+        None => return false,
+    };
+    if proof.len() != proof_bytes(pbc) || ciphertext(pbc, c).is_none() {
+        return false;
+    }
+
+    let mut a = FF::new_int(pbc.n.getlen());
+    let mut b = FF::new_int(pbc.n.getlen());
+    a.randomnum(&pbc.n, rng);
+    while b.iszilch() {
+        b.randomnum(&pbc.n, rng);
+    }
+    gm_rn(pbc, &a, &b).to_be_bytes(&mut proof[0..2 * k]);
+    let e = challenge(pbc, c, &proof[0..2 * k], ctx);
+
+    let mut z = FF::mul(&e, &x);
+    z.add(&FF::resize(&a, 2 * pbc.n.getlen()));
+    z.norm();
+    z.dmod(&pbc.n).to_be_bytes(&mut proof[2 * k..3 * k]);
+    y.pow(&e, &pbc.n);
+    FF::mul(&b, &y)
+        .dmod(&pbc.n)
+        .to_be_bytes(&mut proof[3 * k..]);
+
+    a.zero();
+    b.zero();
+    x.zero();
+    y.zero();
+    true
+}
+
+/* verification of a proof from prove that C is well-formed, for the same CTX */
+pub fn verify_proof(pbc: &PaillierPublicKey, c: &[u8], ctx: &[u8], proof: &[u8]) -> bool {
+    let k = public_key_bytes(pbc);
+    if proof.len() != proof_bytes(pbc) || !valid_ciphertext(pbc, c) {
+        return false;
+    }
+    let x = ciphertext(pbc, c).unwrap();
+    let a = match ciphertext(pbc, &proof[0..2 * k]) {
+        Some(a) => a,
+        None => return false,
+    };
+    let z = residue(pbc, &proof[2 * k..3 * k]);
+    let w = residue(pbc, &proof[3 * k..]);
+    let (z, mut w) = match (z, w) {
+        (Some(z), Some(w)) => (z, w),
+        _ => return false,
+    };
+    if w.iszilch() {
+        return false;
+    }
+    let e = challenge(pbc, c, &proof[0..2 * k], ctx);
+
+    /* (1+n)^z.w^n = A.C^e mod n^2 */
+    let mut t = FF::resize(&x, pbc.n2.getlen());
+    t.pow(&FF::resize(&e, pbc.n2.getlen()), &pbc.n2);
+    let rhs = FF::mul(&a, &t).dmod(&pbc.n2);
+    FF::comp(&gm_rn(pbc, &z, &w), &rhs) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::create_rng;
+
+    // 2048-bit key from the primes of the RSA test key
+    const P: &str = "f48418cc4ee600ba6c6517ebb4560a307e1a28629c8ac47e4afa6006b8094fbbfd9d662979e9d2f095bfa48b195991b0b043e0a6029941c1581578989542fce81e470503fd4b59570c6d612b51996aa40105be1bf30513c3b6448bcdcfd20debaa3c8313855dff34f215f55421c825f733ac011030b7103ba8b8d12b002a0ef1";
+    const Q: &str = "d88ee9bbbc6d4ed79936dd9d8f735cb3616fb446f561666e74533d51b447229b68e5a5922d2968bedf31aca72bc9c98861a94399558c0ea3186572635a80129e6ec3f5011e7a2d34217c047933438d4c7b9f193e90c1129c783b1abe4057fb81d9bf648779df392f4032c1e94b14037dfd048b92794967ce55abdb35e6028a53";
+    const N: &str = "ced7f571aeef4f01714e8f8ac19218ee4f63911feb32edbc87c6b64d903aca1f7162eaee656bfe929b7223e063118066b89b7e85ca2c952445c181e8e8f2dbaab35df4ddd699c74d34865dbce487c66a6c7b45685cf7f1adb87babfd90927262562dff62363e3d47ea9a01ccba49926d7fa3914a186c05ca0f58d455b892734ce995dc65355e5df38bb0d29cf8f6a059e8be7eeccb69462c6305ea772bd7634bacf7e24e2b8b5a3b9161a360a165cbccb117f73ab447ae4f461cd3652dbb5bffc8bf30cfd04496eddbc1566f43650d8f33a6120bda8b45dbe81568c9f509fc33c2e3e1a7f11a80ff21a8ec8a882bc22acef94e4875b5f0b09ca10bad5d92c223";
+
+    // Computed from the definition with Python. C1 encrypts n-1 with the nonce R1, C2 encrypts
+    // "Hello World" with R2, CA=C1.C2 and CS=C2^K mod n^2
+    const R1: &str = "a19f4fe3486cd8f9e61405799236397ee1b85e41ef8f259603b8225aa5e70e1e43d7f5d7459c3ae43d13adc3d7748e5e1847b9c15676dc9cb2ddd7d956ce7284efb847f44ab04b8a8c52c215b2b9a32f0e7d56b620fb877bf35ecbbb29bca53cac1981697fb7009621e919461041dfb66c7cac7212c4ff1d0727ba023794291678c71ee427a88c338232a8ddd9adef0e8e7af51f82f83e7ac323a6a737d214f4386c206fa6399a757e3a82b21b8666f7a8490f89dfa4ccb4ce8b1ad2f7517cbc27969b142a677c0b6f945d78c3117314b6c006b43155fd43815c2a41f03615cbcb0cad1e4d60426388e7e802b627ef1d8e91579a21c3a39e50c191728c541242";
+    const C1: &str = "0c861ff9ff47f544d823798a50df431de6b0a50345b02ccdb82d2f2e421cde3037aeb0a6a06d4e6f91fa337c4336bb817ec006b1b9d18cf438338b35c731d5d66acd6df358c87de807605a85b48aa9586dae9b2f51f3d7a84900ff101a8a7d17ecc13ca05359ae0fd407efaf4e1b4434e0816a5dbbbf44718fc647317b5a52044b1427c114f181f068f28ff4ceaafd93ec8ea9076f8de90369a4398f9a3ba4a5098f4fca64d8c2ba88539be2dfaee1d5fa58e32d9f7af810a4c28907444e183d505564427f0071e5a7dc0c35a501cff8c40b7d072b2592e75b7e96637026a6d95f0b66fb3a2838b7fe177b3f766c8181807c0458f43c2b9316df502fccd7dd0d84b84fac111a7e65d0e67e656f43592f52dcea1f6c206fd69a9d72dae89bb2b8a193813c8160b75679fd354dd98b188dcfe6a9e9326ab18e2dc7e4fef4e12e4787654fd73ac367d9d22339df0ebc4a974a49f176199684fb15beb6792fcec41177c46ed08ef706c8036dd62a0e35fcf470550c82d882bc7cc596b9f18fef234771dd2c65673b784bdfea9e7ab42348fd6bc0e3128e24752a7d32e60ceb94732dbcc655b31653d5c8afbda3945828cf93281c64a1a4c229710b1e535f38829ce8de8fa73260404c81f4404a53fcc4098c249c55810cfbda52ad326d83cfc03bc7e15e901e5717b5de7bc5dec9d1acf9c330949771e3b306549ff0b90b67b68dc1";
+    const R2: &str = "3912525ab7271daced01706c9073e4777778d49531594fdf5e669f56c423ed1127b30625423d89639ac9b4df6d866d4f784a8cbf67cce8a5a39a2d47af059a07e9fba837950fab49ccc2770d003164a551078496e2ed54486b79f8626c1c1d625e135d71c815bf9302a11975c6180d891c703a0b6e2dbcc0c834395451bba2aaa9c4b15888f4390f78c2126854789accfc5fe6bc259b8bb839cb0f5f1d77445ac7567a2c1dcf4c1bb2641c177f38412496fc6a8f4a97ab7d9295f17249fd138c18f87d2d5dc8ff9506cfb25346fa25c89bab5dffc85339f5d8dff4d2d0afb21ccb8463c566d9270d333052252cf535086fffbd3ea71d5d7a5a3602bf23600927";
+    const C2: &str = "33c1b184496a1b9bac611a886ea9cead3236b99155bc492d88595b3c1032191f9d51595aa23abe6e2d7664ea47c679b7a03db76321a85a36eb427fafd3703f3a610500159a851cb3a3322fd6514fdadf5eaa93c089a0956f6096fca1b53054f61a6a8112cb5ccb1f611792fc5c5fd1aa791d8dd41b84e893409e80dc3b15bd386dc7fad6ad5468e43cb8e30eac6946aa2be3a778d0e4aab82a95b2bb58bbfa6292ab47cb84f3e687b81a5196924386cc6f3d175b821059b9080cf685d2e12d613a3623da26c929e3ba458c79bb844240daa5ce94811657748989a63b94f864e544aa8c9d538dd38124b0b524dd8861bcd597e628d9ae93c1537894191fde232925d915af56a35c24bd13266a59983d8362949b115fbaf4fa7889a65860b5fff85528d8fb9fa91c9f9a570c0983428f1ee76f3d5bce0ecd749ec8750b51f2e2b79bfaa216b083593f0ed346be96e31edeeaa6c81d5c57da1c5e8c782ffe09334a4830dae271ef13099e59ef907d2b8dbc344a057af2a9c48397e7480369a46ce4d18e484f4e04f761d6373c8312bd4ac21781cae69a29b2ace3d90be0af0287b3c4063e118da9ee575dfcdf178d11f78ae469a022d7acff8d7387de0b9828bc851e572467fa0ae899ef5e0b9851f3e21b9da07b862d4d8462d58118340c875a032969a01173078ccb96428ef8e10b93e157ec6fb4224bfee49273b2748aa4d78f";
+    const CA: &str = "460b213ede7763b95f3a33665adbd43a43af56202edbc031f7692d961ce547ce4ac9106c6e6ee74506d43518ab27050aaef6304d98a948f0b49e8d2171498549e0f050f3791d2181a96d8fbaf774e73658f1ebce6101a80a6aa8ee5f44bbc4aec7c9263bace6684d69c8a0a2e3b3999fbeb1c126de9dbc332454685cd9ec63c8195f47571596ca9d42d446b25baad0b6ba0510ebbe92a40533af81fbdddc64338e6cc5953b8b94f9284ff350be746a421c76f2754fbe644e2d06960fcb2548a43ab9f56a410453cc6e180db9f7f67e418662f4d4020a10fd4a16d7ec8269e0b0f91a707d05fe44c60f11d7fd12c16e4e98d683a863a23ae5aa359d4b84c18fc19b388de164fb71b09116034ce9dda7a16d3c43268d3b7bfd87949233b1f715cf00e8a19624b4561b11b2a45112b5d663af2cbaf5fe79874dadf7c25d5c6ea2046ff1cf66810f8b8d33a632f8a213e8302d98b4e1f10579e8a57fb0e669c1531df35b9cf06e78ef8a6286f6a6ce993c0255d058ba5f108233af132632dd9bfb6daf989f97d0f310fd9a903a79a8e332d9bccc535ebecad2397f5e87218ab243a8e8eb66d2871f2df2aab8ea500ce60ab193abd42516c96dc88c047535830e4609937a6efc924a23588d805122e4475f21f15a9e112281364ffdf3e11ec3890ea499b37e3a256eb7c7995666e5320309017933b88a135ab5008f739613e455680c";
+    const K: &str = "28e2ba787b951f14160b98cd95aa44d8e98d49d6735176caed6a453818b8d1ad705cc71ed72c0443dd0a138d82988d3672ce28f499d283abdc2ab0c5cebb5e33a5ef453c174c6e75e5ba2bbc5e23add355fb5556a48f7676ae889e6b00be2e61bdd16cac0715215c06e7be67183677eda2dbf4280a984cd44b076374878c84fa7457ce1de743b600032d1c58dd274518b767dc33a7d19d0ac46417196df5cd87938d4fdd6449a1baeae5e29ffaab5e188cb6b629871bb6a391b097146f4cb1e9b1d1646bbba8e5bdcf9f5df094b49b52a72bf46dd3a506b4efa340da46efab6328a394b127b815cb391f94cf2a1eb1b5923c8000ed918bcaa2106fb3d42e0cd1";
+    const CS: &str = "5f1abd099be21d0f4b64a10fd0d157caee00dbe2cb1404e9ae81e87f652d51898637cec6355d75e7027a43e95b856c2cbe375d8f19da21433b2facd0752a9f422dd0092395f1d44be37fe931af77c594552034053a093369c247084ff0c053cbcb28c224da6eb7b80a8ad9b32e31ea03f3fa3bf31a914016c247d6fe6e5c3a60be5e11f4e85abb00d4b02af9df4b9114f2d4abba1df3e8118eb6e2402824288390046a0a5346d17415a609ea5117825b80347c2c76b36e597bac0862c753bc5c27f90b8de23f97a30335c225ee5684df45828be830fcbf060d6bc0d7a6457ad733ad82dc538b1b0d7e196f406c08ee110b4b97f88fe484dff2e0e35a0e7c69db919e80921472355fc51307dd3b3cc0f93a06fbeb048bcc7e838c9e1dc2c3c3129f8657b4f5d2da3104f26d717587c4f6ff884a3dbfb962e500043d582c6aa87098dc3d0b6a3cbdf71e4e69029de3a2a1681c05e0ee6da00ffdad975e433d95c198f057b89d1649a36d7f057f5bbec01194c8947469ed6e99a37797cbdc054dfd75dd063b254d15af3ae75548c2c2bd04f25bdc629a23ffd9ce68f1e4d90821d6f514ef0d4cf8846ff55913605c66c22e004bd276feace8ba651193443aad18bb12c4ce96b0a4066f0f4213107cef993b337e140e358c829f9f280d40e05a2e55634d71f16585e4f120ca96817040e0e38b744bf40ca1ec985b2c2a695742a428";
+
+    fn test_key() -> (PaillierPrivateKey, PaillierPublicKey) {
+        let p = hex::decode(P).unwrap();
+        let q = hex::decode(Q).unwrap();
+        let prv = private_key_from_primes(&p, &q).unwrap();
+        let pbc = private_key_to_public(&prv);
+        (prv, pbc)
+    }
+
+    /* big-endian plaintext of the length of the modulus */
+    fn plaintext(m: &[u8]) -> Vec<u8> {
+        let mut b = vec![0; 256];
+        b[256 - m.len()..].copy_from_slice(m);
+        b
+    }
+
+    #[test]
+    fn test_paillier() {
+        let (prv, pbc) = test_key();
+        let mut rng = create_rng();
+        let mut c = vec![0; 512];
+        let mut m = vec![0; 256];
+        assert_eq!(hex::encode(public_key_modulus(&pbc)), N);
+        assert_eq!(ciphertext_bytes(&pbc), 512);
+
+        let mut n1 = hex::decode(N).unwrap();
+        n1[255] -= 1;
+        let m2 = plaintext(b"Hello World");
+        assert!(encrypt_with_nonce(
+            &pbc,
+            &n1,
+            &hex::decode(R1).unwrap(),
+            &mut c
+        ));
+        assert_eq!(hex::encode(&c), C1);
+        assert!(encrypt_with_nonce(
+            &pbc,
+            &m2,
+            &hex::decode(R2).unwrap(),
+            &mut c
+        ));
+        assert_eq!(hex::encode(&c), C2);
+        assert!(decrypt(&prv, &hex::decode(C1).unwrap(), &mut m));
+        assert_eq!(m, n1);
+        assert!(decrypt(&prv, &c, &mut m));
+        assert_eq!(m, m2);
+
+        // (n-1)+m2 wraps to m2-1
+        let c1 = hex::decode(C1).unwrap();
+        let c2 = hex::decode(C2).unwrap();
+        assert!(add(&pbc, &c1, &c2, &mut c));
+        assert_eq!(hex::encode(&c), CA);
+        assert!(decrypt(&prv, &c, &mut m));
+        assert_eq!(&m[245..], b"Hello Worlc");
+        let k = hex::decode(K).unwrap();
+        assert!(scalar_mul(&pbc, &c2, &k, &mut c));
+        assert_eq!(hex::encode(&c), CS);
+
+        let pbc2 = public_key_from_modulus(&hex::decode(N).unwrap()).unwrap();
+        for len in [0, 1, 100, 255].iter() {
+            let msg: Vec<u8> = (0..*len).map(|i| i as u8 + 1).collect();
+            assert!(encrypt(&pbc2, &mut rng, &plaintext(&msg), &mut c));
+            assert!(valid_ciphertext(&pbc2, &c));
+            assert!(decrypt(&prv, &c, &mut m));
+            assert_eq!(m, plaintext(&msg));
+        }
+
+        // Plaintexts, nonces and ciphertexts out of range, and nonces not coprime to n
+        let n = hex::decode(N).unwrap();
+        assert!(!encrypt(&pbc, &mut rng, &n, &mut c));
+        assert!(!encrypt(&pbc, &mut rng, &m2[1..], &mut c));
+        assert!(!encrypt_with_nonce(&pbc, &m2, &n, &mut c));
+        assert!(!encrypt_with_nonce(&pbc, &m2, &[0; 256], &mut c));
+        let p = plaintext(&hex::decode(P).unwrap());
+        assert!(!encrypt_with_nonce(&pbc, &m2, &p, &mut c));
+        assert!(!decrypt(&prv, &[0xff; 512], &mut m));
+        assert!(!decrypt(&prv, &c1[1..], &mut m));
+        assert!(!add(&pbc, &c1, &[0xff; 512], &mut c));
+        assert!(!scalar_mul(&pbc, &c1, &n, &mut c));
+        assert!(!valid_ciphertext(&pbc, &[0; 512]));
+        let mut pc = vec![0; 256];
+        pc.extend_from_slice(&p);
+        assert!(!valid_ciphertext(&pbc, &pc));
+        assert!(valid_ciphertext(&pbc, &c1));
+
+        assert!(public_key_from_modulus(&n[1..]).is_none());
+        assert!(public_key_from_modulus(&n1).is_none());
+        let q = hex::decode(Q).unwrap();
+        assert!(private_key_from_primes(&q, &q).is_none());
+        assert!(private_key_from_primes(&p[128..], &q[1..]).is_none());
+    }
+
+    #[test]
+    fn test_proof() {
+        let (_, pbc) = test_key();
+        let mut rng = create_rng();
+        let mut proof = vec![0; proof_bytes(&pbc)];
+        let m = plaintext(b"Hello World");
+        let r = hex::decode(R2).unwrap();
+        let c = hex::decode(C2).unwrap();
+
+        assert!(prove(&pbc, &mut rng, &m, &r, &c, b"session", &mut proof));
+        assert!(verify_proof(&pbc, &c, b"session", &proof));
+        assert!(!verify_proof(&pbc, &c, b"other", &proof));
+        assert!(!verify_proof(
+            &pbc,
+            &hex::decode(C1).unwrap(),
+            b"session",
+            &proof
+        ));
+        for i in [0, 511, 512, 767, 768, 1023].iter() {
+            let mut bad = proof.clone();
+            bad[*i] ^= 1;
+            assert!(!verify_proof(&pbc, &c, b"session", &bad));
+        }
+        assert!(!verify_proof(&pbc, &c, b"session", &proof[1..]));
+
+        // A proof made with the wrong plaintext or nonce fails
+        let mut r1 = vec![0; 256];
+        assert!(random_nonce(&pbc, &mut rng, &mut r1));
+        assert!(prove(&pbc, &mut rng, &m, &r1, &c, b"", &mut proof));
+        assert!(!verify_proof(&pbc, &c, b"", &proof));
+        assert!(prove(
+            &pbc,
+            &mut rng,
+            &plaintext(b"Hello"),
+            &r,
+            &c,
+            b"",
+            &mut proof
+        ));
+        assert!(!verify_proof(&pbc, &c, b"", &proof));
+    }
+
+    #[test]
+    fn test_key_pair() {
+        let mut rng = create_rng();
+        let (mut prv, pbc) = new_key_pair(&mut rng, 2048).unwrap();
+        let mut c = vec![0; 512];
+        let mut m = vec![0; 256];
+        let mut proof = vec![0; 1024];
+        let mut r = vec![0; 256];
+        let msg = plaintext(b"abc");
+        assert_eq!(public_key_bytes(&pbc), 256);
+        assert!(random_nonce(&pbc, &mut rng, &mut r));
+        assert!(encrypt_with_nonce(&pbc, &msg, &r, &mut c));
+        assert!(decrypt(&prv, &c, &mut m));
+        assert_eq!(m, msg);
+        assert!(prove(&pbc, &mut rng, &msg, &r, &c, b"", &mut proof));
+        assert!(verify_proof(&pbc, &c, b"", &proof));
+        private_key_kill(&mut prv);
+
+        assert!(new_key_pair(&mut rng, 1024).is_none());
+        assert!(new_key_pair(&mut rng, 2047).is_none());
+        assert!(new_key_pair(&mut rng, 8192).is_none());
+    }
+}
@@ -0,0 +1,143 @@
+/*
+Licensed to the Apache Software Foundation (ASF) under one
+or more contributor license agreements.  See the NOTICE file
+distributed with this work for additional information
+regarding copyright ownership.  The ASF licenses this file
+to you under the Apache License, Version 2.0 (the
+"License"); you may not use this file except in compliance
+with the License.  You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing,
+software distributed under the License is distributed on an
+"AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+KIND, either express or implied.  See the License for the
+specific language governing permissions and limitations
+under the License.
+*/
+
+/* PEM armouring of DER (RFC 7468), with base64 (RFC 4648) */
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Base64 encoding with padding
+pub fn base64_encode(b: &[u8]) -> String {
+    let mut s = String::with_capacity((b.len() + 2) / 3 * 4);
+    for c in b.chunks(3) {
+        let n = (c[0] as u32) << 16
+            | (*c.get(1).unwrap_or(&0) as u32) << 8
+            | *c.get(2).unwrap_or(&0) as u32;
+        for i in 0..4 {
+            if i <= c.len() {
+                s.push(ALPHABET[(n >> (18 - 6 * i)) as usize & 0x3f] as char);
+            } else {
+                s.push('=');
+            }
+        }
+    }
+    s
+}
+
+fn base64_value(c: u8) -> Option<u32> {
+    match c {
+        b'A'..=b'Z' => Some((c - b'A') as u32),
+        b'a'..=b'z' => Some((c - b'a' + 26) as u32),
+        b'0'..=b'9' => Some((c - b'0' + 52) as u32),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    }
+}
+
+/// Strict base64 decoding. Whitespace is skipped, padding is required and
+/// unused bits in the final group must be zero
+pub fn base64_decode(s: &str) -> Option<Vec<u8>> {
+    let t: Vec<u8> = s.bytes().filter(|c| !c.is_ascii_whitespace()).collect();
+    if t.len() % 4 != 0 {
+        return None;
+    }
+    let mut b = Vec::with_capacity(t.len() / 4 * 3);
+    for (k, q) in t.chunks(4).enumerate() {
+        let last = k == t.len() / 4 - 1;
+        let pad = q.iter().rev().take_while(|&&c| c == b'=').count();
+        if pad > 2 || (pad > 0 && !last) {
+            return None;
+        }
+        let mut n = 0;
+        for c in q[0..4 - pad].iter() {
+            n = n << 6 | base64_value(*c)?;
+        }
+        n <<= 6 * pad as u32;
+        if n & ((1 << (8 * pad)) - 1) != 0 {
+            return None;
+        }
+        for i in 0..3 - pad {
+            b.push((n >> (16 - 8 * i)) as u8);
+        }
+    }
+    Some(b)
+}
+
+/// PEM encode DER under LABEL, e.g. "PUBLIC KEY", with 64 character lines
+pub fn encode(label: &str, der: &[u8]) -> String {
+    let b = base64_encode(der);
+    let mut s = format!("-----BEGIN {}-----\n", label);
+    for line in b.as_bytes().chunks(64) {
+        s.push_str(&String::from_utf8_lossy(line));
+        s.push('\n');
+    }
+    s.push_str(&format!("-----END {}-----\n", label));
+    s
+}
+
+/// DER from the first PEM block with LABEL. Text around the block is ignored
+pub fn decode(label: &str, pem: &str) -> Option<Vec<u8>> {
+    let begin = format!("-----BEGIN {}-----", label);
+    let end = format!("-----END {}-----", label);
+    let start = pem.find(&begin)? + begin.len();
+    let stop = start + pem[start..].find(&end)?;
+    base64_decode(&pem[start..stop])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64() {
+        // RFC 4648 section 10
+        let vectors = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+        for v in vectors.iter() {
+            assert_eq!(base64_encode(v.0.as_bytes()), v.1);
+            assert_eq!(base64_decode(v.1).unwrap(), v.0.as_bytes());
+        }
+        assert!(base64_decode("Zg=").is_none());
+        assert!(base64_decode("Zh==").is_none());
+        assert!(base64_decode("Zg==Zg==").is_none());
+        assert!(base64_decode("Z===").is_none());
+        assert!(base64_decode("Zm9*").is_none());
+        assert_eq!(base64_decode("Zm\r\n9v").unwrap(), b"foo");
+    }
+
+    #[test]
+    fn test_pem() {
+        let der: Vec<u8> = (0..100).collect();
+        let p = encode("TEST", &der);
+        assert!(p.starts_with("-----BEGIN TEST-----\n"));
+        assert!(p.ends_with("-----END TEST-----\n"));
+        assert_eq!(p.lines().nth(1).unwrap().len(), 64);
+        assert_eq!(decode("TEST", &p).unwrap(), der);
+        assert!(decode("OTHER", &p).is_none());
+        let framed = format!("comment\n{}trailer", p);
+        assert_eq!(decode("TEST", &framed).unwrap(), der);
+    }
+}
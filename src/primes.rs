@@ -0,0 +1,483 @@
+/*
+Licensed to the Apache Software Foundation (ASF) under one
+or more contributor license agreements.  See the NOTICE file
+distributed with this work for additional information
+regarding copyright ownership.  The ASF licenses this file
+to you under the Apache License, Version 2.0 (the
+"License"); you may not use this file except in compliance
+with the License.  You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing,
+software distributed under the License is distributed on an
+"AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+KIND, either express or implied.  See the License for the
+specific language governing permissions and limitations
+under the License.
+*/
+
+/* Prime generation. Random primes of a given bit length in a residue class, safe primes
+p=2q+1 with q prime, and provable primes by the Shawe-Taylor method of FIPS 186-5. Candidates
+are sieved by the odd primes below SIEVE_LIMIT before any exponentiation. Primes are returned
+as an FF of the fewest BIGBITS-bit words that hold them */
+
+use super::big;
+use super::ff::FF;
+
+use crate::hash256::HASH256;
+use crate::rand::RAND;
+
+/* smallest bit length for random_prime and safe_prime, so that no sieving prime is a candidate */
+pub const MIN_BITS: usize = 16;
+
+const SIEVE_LIMIT: usize = 8192;
+/* candidates tried from one random start */
+const WINDOW: usize = 1 << 16;
+
+/* X+V, for a word size V */
+fn add_small(x: &mut FF, v: u64) {
+    x.add(&FF::from_be_bytes(&v.to_be_bytes(), x.getlen()));
+    x.norm();
+}
+
+/* 2*T*C0+1, all of LEN words */
+fn candidate(t: &FF, two_c0: &FF, len: usize) -> FF {
+    let mut c = FF::mul(t, two_c0);
+    c.norm();
+    let mut c = FF::resize(&c, len);
+    c.inc(1);
+    c.norm();
+    c
+}
+
+/* words needed for NB bits */
+fn words(nb: usize) -> usize {
+    (nb + big::BIGBITS - 1) / big::BIGBITS
+}
+
+/* the odd primes below SIEVE_LIMIT, by the sieve of Eratosthenes */
+fn small_primes() -> Vec<u64> {
+    let mut composite = vec![false; SIEVE_LIMIT];
+    let mut sp = Vec::new();
+    for i in 3..SIEVE_LIMIT {
+        if i % 2 == 0 || composite[i] {
+            continue;
+        }
+        sp.push(i as u64);
+        for j in (i * i..SIEVE_LIMIT).step_by(2 * i) {
+            composite[j] = true;
+        }
+    }
+    sp
+}
+
+/* X mod S, for S < 2^48 */
+fn mod_small(x: &FF, s: u64) -> u64 {
+    let mut b = vec![0; x.getlen() * big::MODBYTES];
+    x.to_be_bytes(&mut b);
+    b.iter().fold(0, |r, &b| ((r << 8) | b as u64) % s)
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        let t = a % b;
+        a = b;
+        b = t;
+    }
+    a
+}
+
+/* 2^k in LEN words */
+fn power_of_two(k: usize, len: usize) -> FF {
+    let mut b = vec![0; len * big::MODBYTES];
+    let n = b.len();
+    b[n - 1 - k / 8] = 1 << (k % 8);
+    FF::from_be_bytes(&b, len)
+}
+
+/* floor(X/D), X and D of the same length and D nonzero. Bitwise long division */
+fn divide(x: &FF, d: &FF) -> FF {
+    let n = x.getlen();
+    let mut q = FF::new_int(n);
+    let mut r = FF::new_int(n);
+    let mut xb = vec![0; n * big::MODBYTES];
+    x.to_be_bytes(&mut xb);
+    for b in xb {
+        for i in (0..8).rev() {
+            r.shl();
+            r.inc(((b >> i) & 1) as isize);
+            q.shl();
+            if FF::comp(&r, d) >= 0 {
+                r.sub(d);
+                r.norm();
+                q.inc(1);
+            }
+        }
+    }
+    q
+}
+
+/* true if 2^(X-1) = 1 mod X, for odd X > 1 */
+fn fermat(x: &FF) -> bool {
+    let n = x.getlen();
+    let mut e = FF::new_int(n);
+    let mut y = FF::new_int(n);
+    e.copy(x);
+    e.dec(1);
+    y.set(2);
+    y.pow(&e, x);
+    let mut one = FF::new_int(n);
+    one.one();
+    FF::comp(&y, &one) == 0
+}
+
+/* Search for an NB bit prime P=R mod M, with P=3 mod 4 and (P-1)/2 prime if SAFE */
+fn search(rng: &mut RAND, nb: usize, r: u64, m: u64, safe: bool) -> Option<FF> {
+    if nb < MIN_BITS || m == 0 || m >= 1 << 32 {
+        return None;
+    }
+    let (base, target) = if safe { (4, 3) } else { (2, 1) };
+    let mm = m / gcd(m, base) * base;
+    let rr = (0..mm / m)
+        .map(|t| r % m + m * t)
+        .find(|x| x % base == target)?;
+    /* for safe primes, Q=(R-1)/2 mod M/2 must also be coprime to its modulus */
+    if gcd(rr, mm) != 1 || (safe && gcd((rr - 1) / 2, mm / 2) != 1) {
+        return None;
+    }
+    let sp = small_primes();
+    let step: Vec<u64> = sp.iter().map(|s| mm % s).collect();
+    let len = words(nb);
+    let mut p = FF::new_int(len);
+    let mut q = FF::new_int(len);
+    let mut c = FF::new_int(len);
+    loop {
+        if !p.random_bits(rng, nb) {
+            return None;
+        }
+        let d = (rr + mm - mod_small(&p, mm)) % mm;
+        add_small(&mut p, d);
+        let mut res: Vec<u64> = sp.iter().map(|&s| mod_small(&p, s)).collect();
+        for k in 0..WINDOW {
+            /* P=1 mod s makes s a factor of (P-1)/2 */
+            if res.iter().all(|&x| x != 0 && (!safe || x != 1)) {
+                c.copy(&p);
+                add_small(&mut c, k as u64 * mm);
+                if c.nbits() != nb {
+                    break;
+                }
+                if !safe {
+                    if FF::prime(&c, rng) {
+                        return Some(c);
+                    }
+                } else {
+                    q.copy(&c);
+                    q.shr();
+                    /* if Q is prime, P is prime when 2^(P-1)=1 mod P, by Pocklington with
+                    a=2, as 2^2-1=3 does not divide P */
+                    if fermat(&q) && fermat(&c) && FF::prime(&q, rng) {
+                        return Some(c);
+                    }
+                }
+            }
+            for (x, (&s, &t)) in res.iter_mut().zip(sp.iter().zip(step.iter())) {
+                *x += t;
+                if *x >= s {
+                    *x -= s;
+                }
+            }
+        }
+    }
+}
+
+/* Random prime P of exactly NB bits with P=R mod M, for NB>=MIN_BITS and 0<M<2^32. The top
+two bits are set, so that the product of two such primes has exactly 2*NB bits. Returns
+None if the residue class holds no such primes, as when R and M have a common factor */
+pub fn random_prime(rng: &mut RAND, nb: usize, r: u64, m: u64) -> Option<FF> {
+    search(rng, nb, r, m, false)
+}
+
+/* Random safe prime P=2Q+1 of exactly NB bits with Q prime and P=R mod M, as for
+random_prime. Safe primes are 3 mod 4, so M=1, R=0 asks for no further condition. Returns None
+if the class also holds no Q, as when P=1 mod 3 */
+pub fn safe_prime(rng: &mut RAND, nb: usize, r: u64, m: u64) -> Option<FF> {
+    search(rng, nb, r, m, true)
+}
+
+/* SEED+I, as a big-endian integer of SEED's length */
+fn seed_add(seed: &[u8], i: usize) -> Vec<u8> {
+    let mut s = seed.to_vec();
+    let mut carry = i;
+    for b in s.iter_mut().rev() {
+        carry += *b as usize;
+        *b = carry as u8;
+        carry >>= 8;
+    }
+    s
+}
+
+fn sha256(b: &[u8]) -> [u8; 32] {
+    let mut h = HASH256::new();
+    h.process_array(b);
+    h.hash()
+}
+
+/* Hash(SEED+N) || .. || Hash(SEED+1) || Hash(SEED), big-endian */
+fn hash_blocks(seed: &[u8], n: usize) -> Vec<u8> {
+    let mut x = Vec::with_capacity(32 * (n + 1));
+    for i in (0..=n).rev() {
+        x.extend_from_slice(&sha256(&seed_add(seed, i)));
+    }
+    x
+}
+
+/* 2^(LENGTH-1) + X mod 2^(LENGTH-1), for X of at least LENGTH bits */
+fn top_bits(x: &[u8], length: usize) -> Vec<u8> {
+    let nbytes = (length + 7) / 8;
+    let mut t = x[x.len() - nbytes..].to_vec();
+    let tb = length - 8 * (nbytes - 1);
+    t[0] &= (1 << (tb - 1)) - 1;
+    t[0] |= 1 << (tb - 1);
+    t
+}
+
+fn small_prime_test(c: u64) -> bool {
+    if c < 2 {
+        return false;
+    }
+    let mut d = 2;
+    while d * d <= c {
+        if c % d == 0 {
+            return false;
+        }
+        d += 1;
+    }
+    true
+}
+
+/* Shawe-Taylor random prime of exactly LENGTH bits from SEED, with SHA-256, as the ST_Random_Prime
+routine of FIPS 186-5. Returns the prime, the updated prime seed and the generation counter, or
+None on failure. The same seed always gives the same prime, with a proof of primality by
+Pocklington's theorem from the smaller prime found in the recursion. Candidates with a small
+factor are rejected before exponentiation, but still consume their seed values, so that the
+output is that of the standard */
+pub fn provable_prime(length: usize, seed: &[u8]) -> Option<(FF, Vec<u8>, usize)> {
+    if length < 2 || seed.is_empty() {
+        return None;
+    }
+    if length < 33 {
+        let mut prime_seed = seed.to_vec();
+        let mut counter = 0;
+        loop {
+            let h0 = sha256(&prime_seed);
+            let h1 = sha256(&seed_add(&prime_seed, 1));
+            let x = h0
+                .iter()
+                .zip(h1.iter())
+                .skip(28)
+                .fold(0, |r, (a, b)| (r << 8) | (a ^ b) as u64);
+            let c = ((1 << (length - 1)) + x % (1 << (length - 1))) | 1;
+            counter += 1;
+            prime_seed = seed_add(&prime_seed, 2);
+            if small_prime_test(c) {
+                return Some((
+                    FF::from_be_bytes(&c.to_be_bytes(), words(length)),
+                    prime_seed,
+                    counter,
+                ));
+            }
+            if counter > 4 * length {
+                return None;
+            }
+        }
+    }
+
+    let (c0, mut prime_seed, mut counter) = provable_prime((length + 1) / 2 + 1, seed)?;
+    let iterations = (length + 255) / 256 - 1;
+    let old_counter = counter;
+    let x = top_bits(&hash_blocks(&prime_seed, iterations), length);
+    prime_seed = seed_add(&prime_seed, iterations + 1);
+
+    let len = words(length + 1);
+    let x = FF::from_be_bytes(&x, len);
+    let mut c0 = FF::resize(&c0, len);
+    c0.norm();
+    let mut two_c0 = FF::new_int(len);
+    two_c0.copy(&c0);
+    two_c0.shl();
+    let mut dm1 = FF::new_int(len);
+    dm1.copy(&two_c0);
+    dm1.dec(1);
+    let ceil_div = |n: &FF| {
+        let mut t = FF::new_int(len);
+        t.copy(n);
+        t.add(&dm1);
+        t.norm();
+        divide(&t, &two_c0)
+    };
+    let max = power_of_two(length, len);
+    let mut t = ceil_div(&x);
+    let sp = small_primes();
+    let mut one = FF::new_int(len);
+    one.one();
+    loop {
+        let mut c = candidate(&t, &two_c0, len);
+        if FF::comp(&c, &max) > 0 {
+            t = ceil_div(&power_of_two(length - 1, len));
+            c = candidate(&t, &two_c0, len);
+        }
+        counter += 1;
+        if sp.iter().all(|&s| mod_small(&c, s) != 0) {
+            /* a = 2 + (hash mod (c-3)) */
+            let h = hash_blocks(&prime_seed, iterations);
+            let mut a = FF::from_be_bytes(&h, words(8 * h.len()).max(len));
+            let mut cm3 = FF::new_int(len);
+            cm3.copy(&c);
+            cm3.dec(3);
+            cm3.norm();
+            a = if a.getlen() == len {
+                a.rmod(&cm3);
+                a
+            } else {
+                let mut m = FF::resize(&cm3, a.getlen());
+                m.norm();
+                a.rmod(&m);
+                FF::resize(&a, len)
+            };
+            a.inc(2);
+            a.norm();
+
+            let mut e = FF::new_int(len);
+            e.copy(&t);
+            e.shl();
+            let mut z = FF::new_int(len);
+            z.copy(&a);
+            z.pow(&e, &c);
+            let mut zm1 = FF::new_int(len);
+            zm1.copy(&z);
+            zm1.dec(1);
+            zm1.norm();
+            if FF::comp(&FF::gcd(&zm1, &c), &one) == 0 {
+                z.pow(&c0, &c);
+                if FF::comp(&z, &one) == 0 {
+                    prime_seed = seed_add(&prime_seed, iterations + 1);
+                    let mut p = FF::resize(&c, words(length));
+                    p.norm();
+                    return Some((p, prime_seed, counter));
+                }
+            }
+        }
+        prime_seed = seed_add(&prime_seed, iterations + 1);
+        if counter >= 4 * length + old_counter {
+            return None;
+        }
+        t.inc(1);
+        t.norm();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::create_rng;
+
+    // Shawe-Taylor primes from the seed SHA256("shawe-taylor"), with the prime seed and
+    // counter returned, computed with Python from FIPS 186-5 without the sieve
+    const SEED: &str = "260e3e93f3cc724d6d888a8768cfeff224dfe11566fe61f68045a55f7501540d";
+    const VECTORS: [(usize, &str, &str, usize); 4] = [
+        (
+            24,
+            "9d09c3",
+            "260e3e93f3cc724d6d888a8768cfeff224dfe11566fe61f68045a55f75015415",
+            4,
+        ),
+        (
+            160,
+            "ed612a25659865db2128415118db7237fec550a1",
+            "260e3e93f3cc724d6d888a8768cfeff224dfe11566fe61f68045a55f75015428",
+            22,
+        ),
+        (
+            521,
+            "0137b6788bdd89f4283e50aa697adf36af52d5489f8baa1eed60d412c4a83a0d6d8b7d2aa92353d297abdfec653dd605ce706dc6ca92b0d06fc367ddb06b19206925",
+            "260e3e93f3cc724d6d888a8768cfeff224dfe11566fe61f68045a55f75015610",
+            267,
+        ),
+        (
+            2048,
+            "d39f9b46a7b18dbfe9f21bef7e6a596d43530b67073246136a4b22862c19f6c670e94d85fd6e67a0b596e7c0a318ae069d3ee64f96cbe4e97d8ff70a9ae045c38ed8cff40bfb685dbae24f80290e8bbb31c957bd47e32ce4a209daf12b1987ada84ba982b79c3644c63117e9c5a72ed9d62c73d6d85e8e40bc00068d28f27bca41fc1bcb4b5230f725c78f623c5f9d75530270cff0ef7a104c965a7bc1ac344dfb45676c2353b854d1c399604153e8668c958e20e0ed5407e4850ef80cf3dc7ba5ed26b1c78e92d3c2d8f32f467e5aa8944426b5917c5b683fcdc7adad9d9e5f42b71b6bb3fe6ff1d011042afaa4a81430a525c567125dfe2afb19cc309d1c61",
+            "260e3e93f3cc724d6d888a8768cfeff224dfe11566fe61f68045a55f750161f0",
+            764,
+        ),
+    ];
+
+    fn hex_of(x: &FF, nb: usize) -> String {
+        let mut b = vec![0; (nb + 7) / 8];
+        x.to_be_bytes(&mut b);
+        hex::encode(&b)
+    }
+
+    #[test]
+    fn test_provable_prime() {
+        let seed = hex::decode(SEED).unwrap();
+        for v in VECTORS.iter() {
+            let (p, prime_seed, counter) = provable_prime(v.0, &seed).unwrap();
+            assert_eq!(p.getlen(), words(v.0));
+            assert_eq!(p.nbits(), v.0);
+            assert_eq!(hex_of(&p, v.0), v.1);
+            assert_eq!(hex::encode(&prime_seed), v.2);
+            assert_eq!(counter, v.3);
+        }
+        assert!(provable_prime(1, &seed).is_none());
+        assert!(provable_prime(256, &[]).is_none());
+    }
+
+    #[test]
+    fn test_random_prime() {
+        let mut rng = create_rng();
+        let sp = small_primes();
+        assert_eq!(sp.len(), 1027);
+        assert_eq!(sp[..5], [3, 5, 7, 11, 13]);
+
+        let mut x = FF::new_int(2);
+        assert!(!x.random_bits(&mut rng, 1));
+        assert!(!x.random_bits(&mut rng, 2 * big::BIGBITS + 1));
+        assert!(x.iszilch());
+        for &nb in [2, big::BIGBITS, 2 * big::BIGBITS].iter() {
+            assert!(x.random_bits(&mut rng, nb));
+            assert_eq!(x.nbits(), nb);
+        }
+
+        for &(nb, r, m) in [(16, 1, 1), (256, 1, 4), (521, 2, 3), (512, 12345, 65537)].iter() {
+            let p = random_prime(&mut rng, nb, r, m).unwrap();
+            assert_eq!(p.getlen(), words(nb));
+            assert_eq!(p.nbits(), nb);
+            assert_eq!(mod_small(&p, m), r % m);
+            assert!(FF::prime(&p, &mut rng));
+        }
+
+        for &(nb, r, m) in [(128, 0, 1), (192, 7, 8), (256, 11, 12)].iter() {
+            let p = safe_prime(&mut rng, nb, r, m).unwrap();
+            assert_eq!(p.nbits(), nb);
+            assert_eq!(mod_small(&p, 4), 3);
+            assert_eq!(mod_small(&p, m), r % m);
+            let mut q = FF::new_int(p.getlen());
+            q.copy(&p);
+            q.shr();
+            assert!(FF::prime(&p, &mut rng));
+            assert!(FF::prime(&q, &mut rng));
+        }
+
+        assert!(random_prime(&mut rng, 15, 1, 2).is_none());
+        assert!(random_prime(&mut rng, 256, 0, 0).is_none());
+        assert!(random_prime(&mut rng, 256, 1, 1 << 32).is_none());
+        assert!(random_prime(&mut rng, 256, 2, 4).is_none());
+        assert!(random_prime(&mut rng, 256, 3, 9).is_none());
+        assert!(safe_prime(&mut rng, 256, 1, 4).is_none());
+        assert!(safe_prime(&mut rng, 256, 1, 8).is_none());
+        assert!(safe_prime(&mut rng, 256, 5, 12).is_none());
+        assert!(safe_prime(&mut rng, 256, 1, 3).is_none());
+        assert!(safe_prime(&mut rng, 256, 1, 65537).is_none());
+    }
+}
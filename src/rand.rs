@@ -162,6 +162,19 @@ impl RAND {
     }
 }
 
+/* generator seeded with 0,1,..,99, shared by the tests */
+#[cfg(test)]
+pub fn create_rng() -> RAND {
+    let mut raw: [u8; 100] = [0; 100];
+    let mut rng = RAND::new();
+    rng.clean();
+    for (i, r) in raw.iter_mut().enumerate() {
+        *r = i as u8
+    }
+    rng.seed(100, &raw);
+    rng
+}
+
 /* test main program */
 /*
 fn main() {
@@ -0,0 +1,544 @@
+/*
+Licensed to the Apache Software Foundation (ASF) under one
+or more contributor license agreements.  See the NOTICE file
+distributed with this work for additional information
+regarding copyright ownership.  The ASF licenses this file
+to you under the Apache License, Version 2.0 (the
+"License"); you may not use this file except in compliance
+with the License.  You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing,
+software distributed under the License is distributed on an
+"AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+KIND, either express or implied.  See the License for the
+specific language governing permissions and limitations
+under the License.
+*/
+
+/* RFC 9496 Ristretto255 prime order group, on top of the ed25519 curve.
+Group elements are ECP points, operated on with the usual ECP methods, and must only
+be encoded, decoded and compared with the functions here */
+
+use super::big;
+use super::big::Big;
+use super::dbig::DBig;
+use super::ecp::ECP;
+use super::fp::FP;
+use super::rom;
+
+use rand::RAND;
+
+/// Size of an encoded element and of a scalar
+pub const EFS: usize = big::MODBYTES;
+pub const EGS: usize = big::MODBYTES;
+/// Input size of the one-way map
+pub const UNIFORM_BYTES: usize = 2 * EFS;
+
+const SQRT_M1: &str = "2b8324804fc1df0b2b4d00993dfbd7a72f431806ad2fe478c4ee1b274a0ea0b0";
+const SQRT_AD_MINUS_ONE: &str = "376931bf2b8348ac0f3cfcc931f5d1fdaf9d8e0c1b7854bd7e97f6a0497b2e1b";
+const INVSQRT_A_MINUS_D: &str = "786c8905cfaffca216c27b91fe01d8409d2f16175a4172be99c8fdaa805d40ea";
+const ONE_MINUS_D_SQ: &str = "029072a8b2b3e0d79994abddbe70dfe42c81a138cd5e350fe27c09c1945fc176";
+const D_MINUS_ONE_SQ: &str = "5968b37af66c22414cdcd32f529b4eebd29e4a2cb01e199931ad5aaa44ed4d20";
+
+fn constant(hex: &str) -> FP {
+    FP::new_big(&Big::fromstring(hex.to_string()))
+}
+
+/// Low bit of the canonical encoding
+fn is_negative(x: &FP) -> isize {
+    FP::new_copy(x).redc().parity()
+}
+
+/// Absolute value, the non-negative one of x and -x
+fn ct_abs(x: &mut FP) {
+    let mut n = FP::new_copy(x);
+    n.neg();
+    n.norm();
+    let d = is_negative(x);
+    x.cmove(&n, d);
+    x.reduce();
+}
+
+/// (was_square, r) where r = +sqrt(u/v), or +sqrt(i.u/v) when u/v is not square
+fn sqrt_ratio_m1(u: &FP, v: &FP) -> (bool, FP) {
+    let sqrt_m1 = constant(SQRT_M1);
+    let mut v3 = FP::new_copy(v);
+    v3.sqr();
+    v3.mul(v);
+    let mut v7 = FP::new_copy(&v3);
+    v7.sqr();
+    v7.mul(v);
+    let mut uv7 = FP::new_copy(u);
+    uv7.mul(&v7);
+
+    let mut e = Big::new_ints(&rom::MODULUS);
+    e.dec(5);
+    e.norm();
+    e.shr(3);
+    let mut r = uv7.pow(&mut e);
+    r.mul(u);
+    r.mul(&v3);
+
+    let mut check = FP::new_copy(&r);
+    check.sqr();
+    check.mul(v);
+
+    let mut nu = FP::new_copy(u);
+    nu.neg();
+    nu.norm();
+    let mut nui = FP::new_copy(&nu);
+    nui.mul(&sqrt_m1);
+
+    let correct = check.equals(u);
+    let flipped = check.equals(&nu);
+    let flipped_i = check.equals(&nui);
+
+    let mut ri = FP::new_copy(&r);
+    ri.mul(&sqrt_m1);
+    r.cmove(&ri, (flipped || flipped_i) as isize);
+    ct_abs(&mut r);
+    (correct || flipped, r)
+}
+
+/// The Ristretto255 generator, the Ed25519 base point
+pub fn generator() -> ECP {
+    ECP::generator()
+}
+
+/// Canonical encoding of an element
+#[allow(non_snake_case)]
+pub fn encode(P: &ECP, b: &mut [u8]) {
+    let x0 = FP::new_big(&P.getx());
+    let y0 = FP::new_big(&P.gety());
+    let one = FP::new_int(1);
+    let mut t0 = FP::new_copy(&x0);
+    t0.mul(&y0);
+
+    let mut u1 = FP::new_copy(&one);
+    u1.add(&y0);
+    u1.norm();
+    let mut w = FP::new_copy(&one);
+    w.sub(&y0);
+    w.norm();
+    u1.mul(&w);
+    let u2 = FP::new_copy(&t0);
+
+    let mut w = FP::new_copy(&u2);
+    w.sqr();
+    w.mul(&u1);
+    let (_, invsqrt) = sqrt_ratio_m1(&one, &w);
+    let mut den1 = FP::new_copy(&invsqrt);
+    den1.mul(&u1);
+    let mut den2 = FP::new_copy(&invsqrt);
+    den2.mul(&u2);
+    let mut z_inv = FP::new_copy(&den1);
+    z_inv.mul(&den2);
+    z_inv.mul(&t0);
+
+    let sqrt_m1 = constant(SQRT_M1);
+    let mut ix0 = FP::new_copy(&x0);
+    ix0.mul(&sqrt_m1);
+    let mut iy0 = FP::new_copy(&y0);
+    iy0.mul(&sqrt_m1);
+    let mut enchanted = FP::new_copy(&den1);
+    enchanted.mul(&constant(INVSQRT_A_MINUS_D));
+
+    let mut w = FP::new_copy(&t0);
+    w.mul(&z_inv);
+    let rotate = is_negative(&w);
+    let mut x = FP::new_copy(&x0);
+    let mut y = FP::new_copy(&y0);
+    x.cmove(&iy0, rotate);
+    y.cmove(&ix0, rotate);
+    let mut den_inv = FP::new_copy(&den2);
+    den_inv.cmove(&enchanted, rotate);
+
+    let mut w = FP::new_copy(&x);
+    w.mul(&z_inv);
+    let mut ny = FP::new_copy(&y);
+    ny.neg();
+    ny.norm();
+    y.cmove(&ny, is_negative(&w));
+
+    let mut s = FP::new_copy(&one);
+    s.sub(&y);
+    s.norm();
+    s.mul(&den_inv);
+    ct_abs(&mut s);
+    scalar_to_bytes(&s.redc(), b);
+}
+
+/// Decode an element, rejecting non-canonical and invalid encodings
+pub fn decode(b: &[u8]) -> Option<ECP> {
+    if b.len() != EFS {
+        return None;
+    }
+    let mut t: [u8; EFS] = [0; EFS];
+    for (i, c) in t.iter_mut().rev().enumerate() {
+        *c = b[i];
+    }
+    let sb = Big::frombytes(&t);
+    if Big::comp(&sb, &Big::new_ints(&rom::MODULUS)) >= 0 || sb.parity() == 1 {
+        return None;
+    }
+    let s = FP::new_big(&sb);
+    let one = FP::new_int(1);
+    let mut ss = FP::new_copy(&s);
+    ss.sqr();
+    // a = -1
+    let mut u1 = FP::new_copy(&one);
+    u1.sub(&ss);
+    u1.norm();
+    let mut u2 = FP::new_copy(&one);
+    u2.add(&ss);
+    u2.norm();
+    let mut u2_sqr = FP::new_copy(&u2);
+    u2_sqr.sqr();
+
+    // v = -(d.u1^2) - u2^2
+    let mut v = FP::new_copy(&u1);
+    v.sqr();
+    v.mul(&FP::new_big(&Big::new_ints(&rom::CURVE_B)));
+    v.add(&u2_sqr);
+    v.norm();
+    v.neg();
+    v.norm();
+
+    let mut w = FP::new_copy(&v);
+    w.mul(&u2_sqr);
+    let (was_square, invsqrt) = sqrt_ratio_m1(&one, &w);
+    let mut den_x = FP::new_copy(&invsqrt);
+    den_x.mul(&u2);
+    let mut den_y = FP::new_copy(&invsqrt);
+    den_y.mul(&den_x);
+    den_y.mul(&v);
+
+    let mut x = FP::new_copy(&s);
+    x.add(&s);
+    x.norm();
+    x.mul(&den_x);
+    ct_abs(&mut x);
+    let mut y = FP::new_copy(&u1);
+    y.mul(&den_y);
+    y.reduce();
+    let mut t = FP::new_copy(&x);
+    t.mul(&y);
+
+    if !was_square || is_negative(&t) == 1 || y.iszilch() {
+        return None;
+    }
+    Some(ECP::new_bigs(&x.redc(), &y.redc()))
+}
+
+/// Equality of elements, which may be represented by different curve points
+#[allow(non_snake_case)]
+pub fn equals(P: &ECP, Q: &ECP) -> bool {
+    let x1 = FP::new_big(&P.getx());
+    let y1 = FP::new_big(&P.gety());
+    let x2 = FP::new_big(&Q.getx());
+    let y2 = FP::new_big(&Q.gety());
+    let mut a = FP::new_copy(&x1);
+    a.mul(&y2);
+    let mut b = FP::new_copy(&y1);
+    b.mul(&x2);
+    let mut c = FP::new_copy(&y1);
+    c.mul(&y2);
+    let mut d = FP::new_copy(&x1);
+    d.mul(&x2);
+    a.equals(&b) || c.equals(&d)
+}
+
+/// Map a field element to a curve point, the MAP function of RFC 9496
+fn map(t: &FP) -> ECP {
+    let one = FP::new_int(1);
+    let d = FP::new_big(&Big::new_ints(&rom::CURVE_B));
+    let mut r = FP::new_copy(t);
+    r.sqr();
+    r.mul(&constant(SQRT_M1));
+
+    let mut u = FP::new_copy(&r);
+    u.add(&one);
+    u.norm();
+    u.mul(&constant(ONE_MINUS_D_SQ));
+    // v = (-1 - r.d).(r + d)
+    let mut v = FP::new_copy(&r);
+    v.mul(&d);
+    v.add(&one);
+    v.norm();
+    v.neg();
+    v.norm();
+    let mut w = FP::new_copy(&r);
+    w.add(&d);
+    w.norm();
+    v.mul(&w);
+
+    let (was_square, mut s) = sqrt_ratio_m1(&u, &v);
+    let mut s_prime = FP::new_copy(&s);
+    s_prime.mul(t);
+    ct_abs(&mut s_prime);
+    s_prime.neg();
+    s_prime.norm();
+    s.cmove(&s_prime, !was_square as isize);
+    let mut c = FP::new_copy(&one);
+    c.neg();
+    c.norm();
+    c.cmove(&r, !was_square as isize);
+
+    // N = c.(r - 1).(d - 1)^2 - v
+    let mut n = FP::new_copy(&r);
+    n.sub(&one);
+    n.norm();
+    n.mul(&c);
+    n.mul(&constant(D_MINUS_ONE_SQ));
+    n.sub(&v);
+    n.norm();
+
+    let mut ss = FP::new_copy(&s);
+    ss.sqr();
+    let mut w0 = FP::new_copy(&s);
+    w0.add(&s);
+    w0.norm();
+    w0.mul(&v);
+    let mut w1 = FP::new_copy(&n);
+    w1.mul(&constant(SQRT_AD_MINUS_ONE));
+    let mut w2 = FP::new_copy(&one);
+    w2.sub(&ss);
+    w2.norm();
+    let mut w3 = FP::new_copy(&one);
+    w3.add(&ss);
+    w3.norm();
+
+    // affine x = w0/w1, y = w2/w3
+    w1.inverse();
+    w0.mul(&w1);
+    w3.inverse();
+    w2.mul(&w3);
+    ECP::new_bigs(&w0.redc(), &w2.redc())
+}
+
+/// One-way map from 64 uniformly random bytes to an element. Used as hash-to-group
+/// on the output of a 512-bit hash
+#[allow(non_snake_case)]
+pub fn from_uniform_bytes(b: &[u8]) -> ECP {
+    let p = Big::new_ints(&rom::MODULUS);
+    let mut t: [u8; EFS] = [0; EFS];
+    let mut P = ECP::new();
+    for half in b[0..UNIFORM_BYTES].chunks(EFS) {
+        for (i, c) in t.iter_mut().rev().enumerate() {
+            *c = half[i];
+        }
+        t[0] &= 0x7f;
+        let mut x = Big::frombytes(&t);
+        x.rmod(&p);
+        P.add(&map(&FP::new_big(&x)));
+    }
+    P
+}
+
+/// Decode a canonical little-endian scalar, which must be less than the group order
+pub fn scalar_from_bytes(b: &[u8]) -> Option<Big> {
+    if b.len() != EGS {
+        return None;
+    }
+    let mut t: [u8; EGS] = [0; EGS];
+    for (i, c) in t.iter_mut().rev().enumerate() {
+        *c = b[i];
+    }
+    let s = Big::frombytes(&t);
+    if Big::comp(&s, &Big::new_ints(&rom::CURVE_ORDER)) >= 0 {
+        return None;
+    }
+    Some(s)
+}
+
+/// Encode a scalar as EGS little-endian bytes
+pub fn scalar_to_bytes(s: &Big, b: &mut [u8]) {
+    let mut t: [u8; EGS] = [0; EGS];
+    Big::new_copy(s).tobytes(&mut t);
+    for (i, c) in t.iter().rev().enumerate() {
+        b[i] = *c;
+    }
+}
+
+/// Reduce 64 uniformly random little-endian bytes to a scalar
+pub fn scalar_from_uniform_bytes(b: &[u8]) -> Big {
+    let mut t: [u8; UNIFORM_BYTES] = [0; UNIFORM_BYTES];
+    for (i, c) in t.iter_mut().rev().enumerate() {
+        *c = b[i];
+    }
+    DBig::frombytes(&t).dmod(&Big::new_ints(&rom::CURVE_ORDER))
+}
+
+/// Random non-zero scalar
+pub fn random_scalar(rng: &mut RAND) -> Big {
+    let r = Big::new_ints(&rom::CURVE_ORDER);
+    loop {
+        let s = Big::randomnum(&r, rng);
+        if !s.iszilch() {
+            return s;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hash512::HASH512;
+
+    // From https://www.rfc-editor.org/rfc/rfc9496#appendix-A.1, multiples of the generator
+    const MULTIPLES: [&str; 16] = [
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76",
+        "6a493210f7499cd17fecb510ae0cea23a110e8d5b901f8acadd3095c73a3b919",
+        "94741f5d5d52755ece4f23f044ee27d5d1ea1e2bd196b462166b16152a9d0259",
+        "da80862773358b466ffadfe0b3293ab3d9fd53c5ea6c955358f568322daf6a57",
+        "e882b131016b52c1d3337080187cf768423efccbb517bb495ab812c4160ff44e",
+        "f64746d3c92b13050ed8d80236a7f0007c3b3f962f5ba793d19a601ebb1df403",
+        "44f53520926ec81fbd5a387845beb7df85a96a24ece18738bdcfa6a7822a176d",
+        "903293d8f2287ebe10e2374dc1a53e0bc887e592699f02d077d5263cdd55601c",
+        "02622ace8f7303a31cafc63f8fc48fdc16e1c8c8d234b2f0d6685282a9076031",
+        "20706fd788b2720a1ed2a5dad4952b01f413bcf0e7564de8cdc816689e2db95f",
+        "bce83f8ba5dd2fa572864c24ba1810f9522bc6004afe95877ac73241cafdab42",
+        "e4549ee16b9aa03099ca208c67adafcafa4c3f3e4e5303de6026e3ca8ff84460",
+        "aa52e000df2e16f55fb1032fc33bc42742dad6bd5a8fc0be0167436c5948501f",
+        "46376b80f409b29dc2b5f6f0c52591990896e5716f41477cd30085ab7f10301e",
+        "e0c418f7c8d9c4cdd7395b93ea124f3ad99021bb681dfc3302a9d99a2e53e64e",
+    ];
+
+    // Appendix A.2, invalid encodings
+    const BAD: [&str; 29] = [
+        // Non-canonical field encodings
+        "00ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        "f3ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        "edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        // Negative field elements
+        "0100000000000000000000000000000000000000000000000000000000000000",
+        "01ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        "ed57ffd8c914fb201471d1c3d245ce3c746fcbe63a3679d51b6a516ebebe0e20",
+        "c34c4e1826e5d403b78e246e88aa051c36ccf0aafebffe137d148a2bf9104562",
+        "c940e5a4404157cfb1628b108db051a8d439e1a421394ec4ebccb9ec92a8ac78",
+        "47cfc5497c53dc8e61c91d17fd626ffb1c49e2bca94eed052281b510b1117a24",
+        "f1c6165d33367351b0da8f6e4511010c68174a03b6581212c71c0e1d026c3c72",
+        "87260f7a2f12495118360f02c26a470f450dadf34a413d21042b43b9d93e1309",
+        // Non-square x^2
+        "26948d35ca62e643e26a83177332e6b6afeb9d08e4268b650f1f5bbd8d81d371",
+        "4eac077a713c57b4f4397629a4145982c661f48044dd3f96427d40b147d9742f",
+        "de6a7b00deadc788eb6b6c8d20c0ae96c2f2019078fa604fee5b87d6e989ad7b",
+        "bcab477be20861e01e4a0e295284146a510150d9817763caf1a6f4b422d67042",
+        "2a292df7e32cababbd9de088d1d1abec9fc0440f637ed2fba145094dc14bea08",
+        "f4a9e534fc0d216c44b218fa0c42d99635a0127ee2e53c712f70609649fdff22",
+        "8268436f8c4126196cf64b3c7ddbda90746a378625f9813dd9b8457077256731",
+        "2810e5cbc2cc4d4eece54f61c6f69758e289aa7ab440b3cbeaa21995c2f4232b",
+        // Negative xy value
+        "3eb858e78f5a7254d8c9731174a94f76755fd3941c0ac93735c07ba14579630e",
+        "a45fdc55c76448c049a1ab33f17023edfb2be3581e9c7aade8a6125215e04220",
+        "d483fe813c6ba647ebbfd3ec41adca1c6130c2beeee9d9bf065c8d151c5f396e",
+        "8a2e1d30050198c65a54483123960ccc38aef6848e1ec8f5f780e8523769ba32",
+        "32888462f8b486c68ad7dd9610be5192bbeaf3b443951ac1a8118419d9fa097b",
+        "227142501b9d4355ccba290404bde41575b037693cef1f438c47f8fbf35d1165",
+        "5c37cc491da847cfeb9281d407efc41e15144c876e0170b499a96a22ed31e01e",
+        "445425117cb8c90edcbc7c1cc0e74f747f2c1efa5630a967c64f287792a48a4b",
+        // s = -1, which causes y = 0
+        "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+    ];
+
+    // Appendix A.3, SHA-512 of the label mapped to an element
+    const HASHES: [(&str, &str); 7] = [
+        (
+            "Ristretto is traditionally a short shot of espresso coffee",
+            "3066f82a1a747d45120d1740f14358531a8f04bbffe6a819f86dfe50f44a0a46",
+        ),
+        (
+            "made with the normal amount of ground coffee but extracted with",
+            "f26e5b6f7d362d2d2a94c5d0e7602cb4773c95a2e5c31a64f133189fa76ed61b",
+        ),
+        (
+            "about half the amount of water in the same amount of time",
+            "006ccd2a9e6867e6a2c5cea83d3302cc9de128dd2a9a57dd8ee7b9d7ffe02826",
+        ),
+        (
+            "by using a finer grind.",
+            "f8f0c87cf237953c5890aec3998169005dae3eca1fbb04548c635953c817f92a",
+        ),
+        (
+            "This produces a concentrated shot of coffee per volume.",
+            "ae81e7dedf20a497e10c304a765c1767a42d6e06029758d2d7e8ef7cc4c41179",
+        ),
+        (
+            "Just pulling a normal shot short will produce a weaker shot",
+            "e2705652ff9f5e44d3e841bf1c251cf7dddb77d140870d1ab2ed64f1a9ce8628",
+        ),
+        (
+            "and is not a Ristretto as some believe.",
+            "80bd07262511cdde4863f8a7434cef696750681cb9510eea557088f76d9e5065",
+        ),
+    ];
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_multiples() {
+        let G = generator();
+        let mut P = ECP::new();
+        let mut b: [u8; EFS] = [0; EFS];
+        for v in MULTIPLES.iter() {
+            encode(&P, &mut b);
+            assert_eq!(hex::encode(&b[..]), *v);
+            let Q = decode(&b).unwrap();
+            assert!(equals(&P, &Q));
+            P.add(&G);
+        }
+    }
+
+    #[test]
+    fn test_bad_encodings() {
+        for v in BAD.iter() {
+            assert!(decode(&hex::decode(v).unwrap()).is_none());
+        }
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_from_uniform_bytes() {
+        let mut b: [u8; EFS] = [0; EFS];
+        for v in HASHES.iter() {
+            let mut h = HASH512::new();
+            h.process_array(v.0.as_bytes());
+            let P = from_uniform_bytes(&h.hash());
+            encode(&P, &mut b);
+            assert_eq!(hex::encode(&b[..]), v.1);
+        }
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_torsion_equality() {
+        // adding the 2-torsion point (0,-1) gives the same element
+        let G = generator();
+        let mut m1 = Big::new_ints(&rom::MODULUS);
+        m1.dec(1);
+        m1.norm();
+        let T = ECP::new_bigs(&Big::new(), &m1);
+        assert!(!T.is_infinity());
+        let mut P = G.mul(&Big::new_int(7));
+        assert!(!P.equals(&T));
+        let mut Q = ECP::new();
+        Q.copy(&P);
+        Q.add(&T);
+        assert!(equals(&P, &Q));
+        let mut b1: [u8; EFS] = [0; EFS];
+        let mut b2: [u8; EFS] = [0; EFS];
+        encode(&P, &mut b1);
+        encode(&Q, &mut b2);
+        assert_eq!(b1, b2);
+        P.add(&G);
+        assert!(!equals(&P, &Q));
+
+        let mut s: [u8; EGS] = [0; EGS];
+        let l = Big::new_ints(&rom::CURVE_ORDER);
+        scalar_to_bytes(&l, &mut s);
+        assert!(scalar_from_bytes(&s).is_none());
+    }
+}
@@ -72,3 +72,6 @@ pub const ATE_BITS: usize = 0;
 pub const SIGN_OF_X: SignOfX = SignOfX::Not;
 pub const HASH_TYPE: usize = 32;
 pub const AESKEY: usize = 16;
+
+// Named curve OID 1.3.36.3.3.2.8.1.1.8 brainpoolP256t1, DER content octets
+pub const CURVE_OID: [u8; 9] = [0x2b, 0x24, 0x03, 0x03, 0x02, 0x08, 0x01, 0x01, 0x08];
@@ -101,3 +101,6 @@ pub const ATE_BITS: usize = 0;
 pub const SIGN_OF_X: SignOfX = SignOfX::Not;
 pub const HASH_TYPE: usize = 48;
 pub const AESKEY: usize = 24;
+
+// Named curve OID 1.3.132.0.34 secp384r1, DER content octets
+pub const CURVE_OID: [u8; 5] = [0x2b, 0x81, 0x04, 0x00, 0x22];
@@ -77,3 +77,6 @@ pub const ATE_BITS: usize = 0;
 pub const SIGN_OF_X: SignOfX = SignOfX::Not;
 pub const HASH_TYPE: usize = 64;
 pub const AESKEY: usize = 32;
+
+// Named curve OID 1.3.132.0.35 secp521r1, DER content octets
+pub const CURVE_OID: [u8; 5] = [0x2b, 0x81, 0x04, 0x00, 0x23];
@@ -68,3 +68,6 @@ pub const ATE_BITS: usize = 0;
 pub const SIGN_OF_X: SignOfX = SignOfX::Not;
 pub const HASH_TYPE: usize = 32;
 pub const AESKEY: usize = 16;
+
+// Named curve OID 1.3.132.0.10 secp256k1, DER content octets
+pub const CURVE_OID: [u8; 5] = [0x2b, 0x81, 0x04, 0x00, 0x0a];
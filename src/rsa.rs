@@ -21,15 +21,28 @@ use super::big;
 use super::ff;
 use super::ff::FF;
 
+use crate::der;
 use crate::hash256::HASH256;
 use crate::hash384::HASH384;
 use crate::hash512::HASH512;
+use crate::pem;
 use crate::rand::RAND;
+use crate::sha3::SHA3;
 
+/* byte length of the compile time modulus, used by key_pair */
 pub const RFS: usize = (big::MODBYTES as usize) * ff::FFLEN;
+/* modulus bit lengths accepted for public keys, and for new_key_pair */
+pub const MIN_BITS: usize = 1024;
+pub const MAX_BITS: usize = 8192;
+pub const MIN_KEYGEN_BITS: usize = 2048;
+pub const MAX_KEYGEN_BITS: usize = 4096;
 pub const SHA256: usize = 32;
 pub const SHA384: usize = 48;
 pub const SHA512: usize = 64;
+/* SHA3 identifiers, with the digest length in the low byte */
+pub const SHA3_256: usize = 0x100 | 32;
+pub const SHA3_384: usize = 0x100 | 48;
+pub const SHA3_512: usize = 0x100 | 64;
 
 pub const HASH_TYPE: usize = SHA256;
 
@@ -39,6 +52,21 @@ pub struct RsaPrivateKey {
     dp: FF,
     dq: FF,
     c: FF,
+    d: FF,
+    n: FF,
+    others: Vec<OtherPrime>,
+    e: isize,
+    blind: bool,
+    check: bool,
+}
+
+/* prime, CRT exponent and CRT coefficient of each prime after the first two of a
+multi-prime key, as in the OtherPrimeInfo of RFC 8017. T is the inverse mod R of the
+product of the primes before it */
+struct OtherPrime {
+    r: FF,
+    d: FF,
+    t: FF,
 }
 
 pub struct RsaPublicKey {
@@ -53,6 +81,12 @@ pub fn new_private_key(n: usize) -> RsaPrivateKey {
         dp: FF::new_int(n),
         dq: FF::new_int(n),
         c: FF::new_int(n),
+        d: FF::new_int(2 * n),
+        n: FF::new_int(2 * n),
+        others: Vec::new(),
+        e: 0,
+        blind: true,
+        check: true,
     }
 }
 
@@ -63,7 +97,226 @@ pub fn new_public_key(m: usize) -> RsaPublicKey {
     }
 }
 
+/* Base blinding and the fault check in decrypt are on by default. Turning them off saves
+an exponentiation by E and a modular inverse, and is only meant for benchmarks */
+pub fn private_key_countermeasures(prv: &mut RsaPrivateKey, blind: bool, check: bool) {
+    prv.blind = blind;
+    prv.check = check;
+}
+
+/* FF words in each prime of an NB bit modulus. The modulus takes twice as many */
+fn half_len(nb: usize) -> usize {
+    ((nb + 1) / 2 + big::BIGBITS - 1) / big::BIGBITS
+}
+
+/* modulus length in bytes. Signatures, ciphertexts and encoded messages are this long */
+pub fn public_key_bytes(pbc: &RsaPublicKey) -> usize {
+    (pbc.n.nbits() + 7) / 8
+}
+
+pub fn private_key_bytes(prv: &RsaPrivateKey) -> usize {
+    (prv.n.nbits() + 7) / 8
+}
+
+/* most primes in a key with an NB bit modulus, so that none is much shorter than 1024 bits.
+These are the limits of OpenSSL */
+pub fn max_primes(nb: usize) -> usize {
+    if nb < 1024 {
+        2
+    } else if nb < 4096 {
+        3
+    } else if nb < 8192 {
+        4
+    } else {
+        5
+    }
+}
+
+/* big-endian modulus, the length of the modulus in bytes, and the public exponent */
+pub fn public_key_components(pbc: &RsaPublicKey) -> (Vec<u8>, isize) {
+    let mut n = vec![0; public_key_bytes(pbc)];
+    pbc.n.to_be_bytes(&mut n);
+    (n, pbc.e)
+}
+
+/* public key of the private key PRV */
+pub fn private_key_to_public(prv: &RsaPrivateKey) -> RsaPublicKey {
+    RsaPublicKey {
+        e: prv.e,
+        n: FF::resize(&prv.n, prv.n.getlen()),
+    }
+}
+
+/* big-endian V less any leading zeros, right aligned in OUT */
+fn fixed(v: &[u8], out: &mut [u8]) -> bool {
+    let i = v.iter().take_while(|&&x| x == 0).count();
+    der::to_fixed(&v[i..], out)
+}
+
+/* X mod M, at the length of M. X is twice the length of M, or longer */
+fn residue(x: &FF, m: &FF) -> FF {
+    let n = m.getlen();
+    let mut t = FF::resize(x, x.getlen().max(2 * n));
+    if t.getlen() == 2 * n {
+        return t.dmod(m);
+    }
+    t.rmod(&FF::resize(m, t.getlen()));
+    FF::resize(&t, n)
+}
+
+/* product of the primes R, W words long */
+fn product(r: &[FF], w: usize) -> FF {
+    let mut m = FF::new_int(w);
+    m.one();
+    for x in r.iter() {
+        let mut t = FF::mul(&m, &FF::resize(x, w));
+        t.norm();
+        m = FF::resize(&t, w);
+    }
+    m
+}
+
+/* RSA public key from the big-endian modulus N and public exponent E. N must be odd and
+of MIN_BITS to MAX_BITS bits, E odd and between 3 and 2^31-1 */
+pub fn public_key_from_components(n: &[u8], e: isize) -> Option<RsaPublicKey> {
+    let i = n.iter().take_while(|&&x| x == 0).count();
+    if e < 3 || e > i32::MAX as isize || e % 2 == 0 || i == n.len() {
+        return None;
+    }
+    let nb = 8 * (n.len() - i) - n[i].leading_zeros() as usize;
+    if !(MIN_BITS..=MAX_BITS).contains(&nb) || n[n.len() - 1] & 1 == 0 {
+        return None;
+    }
+    let mut pbc = new_public_key(2 * half_len(nb));
+    pbc.n = FF::from_be_bytes(&n[i..], pbc.n.getlen());
+    pbc.e = e;
+    Some(pbc)
+}
+
+/* RSA private key from the big-endian modulus N, public exponent E, private exponent D and
+primes P and Q. The CRT values are derived, with Q^-1 mod P as the coefficient as in
+PKCS#1. None if the values do not form a consistent key */
+pub fn private_key_from_components(
+    n: &[u8],
+    e: isize,
+    d: &[u8],
+    p: &[u8],
+    q: &[u8],
+) -> Option<RsaPrivateKey> {
+    private_key_from_primes(n, e, d, &[p, q])
+}
+
+/* As private_key_from_components, for a key with two or more primes in the order of
+PKCS#1, up to max_primes of the modulus length */
+pub fn private_key_from_primes(
+    n: &[u8],
+    e: isize,
+    d: &[u8],
+    primes: &[&[u8]],
+) -> Option<RsaPrivateKey> {
+    let pbc = public_key_from_components(n, e)?;
+    let k = primes.len();
+    let w = pbc.n.getlen();
+    if k < 2 || k > max_primes(pbc.n.nbits()) {
+        return None;
+    }
+    /* primes of a multi-prime key take only the words they need */
+    let mut h = w / 2;
+    if k > 2 {
+        let nb = primes
+            .iter()
+            .map(|x| x.iter().skip_while(|&&b| b == 0).count());
+        h = (nb.max().unwrap_or(0) + big::MODBYTES - 1) / big::MODBYTES;
+        if 2 * h > w {
+            return None;
+        }
+    }
+    let mut prv = new_private_key(h);
+    let mut r = Vec::new();
+    let mut b = vec![0; h * big::MODBYTES];
+    for x in primes.iter() {
+        if !fixed(x, &mut b) {
+            return None;
+        }
+        let mut t = FF::new_int(h);
+        FF::frombytes(&mut t, &b);
+        if t.parity() == 0 {
+            return None;
+        }
+        r.push(t);
+    }
+
+    /* internally c=p^-1 mod q, so the primes are held swapped */
+    prv.p.copy(&r[1]);
+    prv.q.copy(&r[0]);
+    let mut b = vec![0; w * big::MODBYTES];
+    if !fixed(d, &mut b) {
+        return None;
+    }
+    prv.d = FF::new_int(w);
+    FF::frombytes(&mut prv.d, &b);
+    prv.n = product(&r, w);
+    prv.e = e;
+
+    if FF::comp(&prv.n, &pbc.n) != 0 || prv.d.iszilch() || FF::comp(&prv.d, &pbc.n) >= 0 {
+        return None;
+    }
+
+    let mut t = FF::new_int(h);
+    t.copy(&prv.p);
+    t.dec(1);
+    prv.dp = residue(&prv.d, &t);
+    t.copy(&prv.q);
+    t.dec(1);
+    prv.dq = residue(&prv.d, &t);
+    prv.c = residue(&prv.p, &prv.q);
+    prv.c.invmodp(&prv.q);
+    for (i, x) in r.iter().enumerate().skip(2) {
+        t.copy(x);
+        t.dec(1);
+        let d = residue(&prv.d, &t);
+        let mut c = residue(&product(&r[0..i], w), x);
+        c.invmodp(x);
+        prv.others.push(OtherPrime {
+            r: FF::resize(x, h),
+            d,
+            t: c,
+        });
+    }
+
+    /* check that decryption inverts encryption, on a public message that needs no blinding */
+    let k = public_key_bytes(&pbc);
+    let mut m = vec![0; k];
+    let mut c = vec![0; k];
+    let mut f = vec![0; k];
+    m[k - 1] = 2;
+    encrypt(&pbc, &m, &mut c);
+    if !crt_decrypt(&prv, None, &c, &mut f) || f != m {
+        return None;
+    }
+    Some(prv)
+}
+
+/* digest length of hash SHA */
+fn hash_len(sha: usize) -> usize {
+    sha & 0xff
+}
+
 fn hashit(sha: usize, a: Option<&[u8]>, n: isize, w: &mut [u8]) {
+    if sha == SHA3_256 || sha == SHA3_384 || sha == SHA3_512 {
+        let mut h = SHA3::new(hash_len(sha));
+        if let Some(x) = a {
+            for b in x.iter() {
+                h.process(*b);
+            }
+        }
+        if n >= 0 {
+            for b in (n as u32).to_be_bytes().iter() {
+                h.process(*b);
+            }
+        }
+        h.hash(w);
+    }
     if sha == SHA256 {
         let mut h = HASH256::new();
         if let Some(x) = a {
@@ -105,79 +358,138 @@ fn hashit(sha: usize, a: Option<&[u8]>, n: isize, w: &mut [u8]) {
     }
 }
 
-pub fn key_pair(rng: &mut RAND, e: isize, prv: &mut RsaPrivateKey, pbc: &mut RsaPublicKey) {
-    /* IEEE1363 A16.11/A16.12 more or less */
-    let n = pbc.n.getlen() / 2;
-    let mut t = FF::new_int(n);
-    let mut p1 = FF::new_int(n);
-    let mut q1 = FF::new_int(n);
-
+/* prime P of NB bits with P=3 mod 4 and P-1 coprime to E. P1 is set to P-1 */
+fn random_prime(rng: &mut RAND, e: isize, nb: usize, p: &mut FF, p1: &mut FF) {
     loop {
-        prv.p.random(rng);
-        while prv.p.lastbits(2) != 3 {
-            prv.p.inc(1)
+        p.random_bits(rng, nb);
+        while p.lastbits(2) != 3 {
+            p.inc(1)
         }
-        while !FF::prime(&prv.p, rng) {
-            prv.p.inc(4);
+        while !FF::prime(p, rng) {
+            p.inc(4);
         }
 
-        p1.copy(&prv.p);
+        p1.copy(p);
         p1.dec(1);
 
-        if p1.cfactor(e) {
-            continue;
+        if !p1.cfactor(e) {
+            break;
         }
-        break;
     }
+}
 
-    loop {
-        prv.q.random(rng);
-        while prv.q.lastbits(2) != 3 {
-            prv.q.inc(1)
-        }
-        while !FF::prime(&prv.q, rng) {
-            prv.q.inc(4);
-        }
-
-        q1.copy(&prv.q);
-        q1.dec(1);
-
-        if q1.cfactor(e) {
-            continue;
-        }
+/* key pair for the compile time modulus size, with keys from new_private_key(ff::HFLEN)
+and new_public_key(ff::FFLEN) */
+pub fn key_pair(rng: &mut RAND, e: isize, prv: &mut RsaPrivateKey, pbc: &mut RsaPublicKey) {
+    let nb = pbc.n.getlen() * big::BIGBITS;
+    let (x, y) = generate(rng, e, nb, 2);
+    *prv = x;
+    *pbc = y;
+}
 
-        break;
+/* key pair with an NB bit modulus, from MIN_KEYGEN_BITS to MAX_KEYGEN_BITS and even, and
+public exponent E, odd and between 3 and 2^31-1 */
+pub fn new_key_pair(rng: &mut RAND, nb: usize, e: isize) -> Option<(RsaPrivateKey, RsaPublicKey)> {
+    if nb % 2 != 0 {
+        return None;
     }
+    new_multi_prime_key_pair(rng, nb, 2, e)
+}
 
-    pbc.n = FF::mul(&prv.p, &prv.q);
-    pbc.e = e;
-
-    t.copy(&p1);
-    t.shr();
-    prv.dp.set(e);
-    prv.dp.invmodp(&t);
-    if prv.dp.parity() == 0 {
-        prv.dp.add(&t)
+/* key pair as new_key_pair, with a modulus that is the product of K primes, up to
+max_primes(NB). Each prime is about NB/K bits, and private key operations are faster with
+more of them, at some cost in the effort needed to factor the modulus */
+pub fn new_multi_prime_key_pair(
+    rng: &mut RAND,
+    nb: usize,
+    k: usize,
+    e: isize,
+) -> Option<(RsaPrivateKey, RsaPublicKey)> {
+    if !(MIN_KEYGEN_BITS..=MAX_KEYGEN_BITS).contains(&nb) || k < 2 || k > max_primes(nb) {
+        return None;
     }
-    prv.dp.norm();
+    if e < 3 || e > i32::MAX as isize || e % 2 == 0 {
+        return None;
+    }
+    Some(generate(rng, e, nb, k))
+}
 
-    t.copy(&q1);
+/* D=1/E mod (P-1)/2, made odd so that it inverts E mod P-1 too. P1 is P-1 */
+fn crt_exponent(e: isize, p1: &FF) -> FF {
+    let mut t = FF::new_int(p1.getlen());
+    let mut d = FF::new_int(p1.getlen());
+    t.copy(p1);
     t.shr();
-    prv.dq.set(e);
-    prv.dq.invmodp(&t);
-    if prv.dq.parity() == 0 {
-        prv.dq.add(&t)
+    d.set(e);
+    d.invmodp(&t);
+    if d.parity() == 0 {
+        d.add(&t)
     }
-    prv.dq.norm();
+    d.norm();
+    d
+}
+
+fn generate(rng: &mut RAND, e: isize, nb: usize, k: usize) -> (RsaPrivateKey, RsaPublicKey) {
+    /* IEEE1363 A16.11/A16.12 more or less */
+    let h = ((nb + k - 1) / k + big::BIGBITS - 1) / big::BIGBITS;
+    let w = 2 * half_len(nb);
+    let mut r: Vec<FF> = (0..k).map(|_| FF::new_int(h)).collect();
+    let mut r1: Vec<FF> = (0..k).map(|_| FF::new_int(h)).collect();
 
+    /* with more than two primes the product can fall a bit short */
+    loop {
+        for (i, (p, p1)) in r.iter_mut().zip(r1.iter_mut()).enumerate() {
+            random_prime(rng, e, nb / k + usize::from(i < nb % k), p, p1);
+        }
+        let distinct = (1..k).all(|i| (0..i).all(|j| FF::comp(&r[i], &r[j]) != 0));
+        if distinct && product(&r, w).nbits() == nb {
+            break;
+        }
+    }
+    let mut prv = new_private_key(h);
+
+    prv.n = product(&r, w);
+    prv.e = e;
+    let pbc = RsaPublicKey {
+        e,
+        n: FF::resize(&prv.n, w),
+    };
+
+    prv.p.copy(&r[0]);
+    prv.q.copy(&r[1]);
+    prv.dp = crt_exponent(e, &r1[0]);
+    prv.dq = crt_exponent(e, &r1[1]);
     prv.c.copy(&prv.p);
     prv.c.invmodp(&prv.q);
+    for i in 2..k {
+        let mut t = residue(&product(&r[0..i], w), &r[i]);
+        t.invmodp(&r[i]);
+        prv.others.push(OtherPrime {
+            r: FF::resize(&r[i], h),
+            d: crt_exponent(e, &r1[i]),
+            t,
+        });
+    }
+
+    /* d=1/e mod the product of the (r-1)/2, made odd so that it inverts e mod lcm(r-1) too */
+    for x in r1.iter_mut() {
+        x.shr();
+    }
+    let t = product(&r1, w);
+    prv.d = FF::new_int(w);
+    prv.d.set(e);
+    prv.d.invmodp(&t);
+    if prv.d.parity() == 0 {
+        prv.d.add(&t)
+    }
+    prv.d.norm();
+    (prv, pbc)
 }
 
 /* Mask Generation Function */
 
 pub fn mgf1(sha: usize, z: &[u8], olen: usize, k: &mut [u8]) {
-    let hlen = sha;
+    let hlen = hash_len(sha);
 
     let mut j = 0;
     for i in 0..k.len() {
@@ -219,12 +531,42 @@ const SHA512ID: [u8; 19] = [
     0x30, 0x51, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03, 0x05,
     0x00, 0x04, 0x40,
 ];
+const SHA3_256ID: [u8; 19] = [
+    0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x08, 0x05,
+    0x00, 0x04, 0x20,
+];
+const SHA3_384ID: [u8; 19] = [
+    0x30, 0x41, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x09, 0x05,
+    0x00, 0x04, 0x30,
+];
+const SHA3_512ID: [u8; 19] = [
+    0x30, 0x51, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x0a, 0x05,
+    0x00, 0x04, 0x40,
+];
 
+/* DER DigestInfo prefix for hash SHA */
+fn digest_info(sha: usize) -> Option<&'static [u8]> {
+    match sha {
+        SHA256 => Some(&SHA256ID),
+        SHA384 => Some(&SHA384ID),
+        SHA512 => Some(&SHA512ID),
+        SHA3_256 => Some(&SHA3_256ID),
+        SHA3_384 => Some(&SHA3_384ID),
+        SHA3_512 => Some(&SHA3_512ID),
+        _ => None,
+    }
+}
+
+/* EMSA-PKCS1-v1_5 encoding of M into W, the length of the modulus */
 pub fn pkcs15(sha: usize, m: &[u8], w: &mut [u8]) -> bool {
-    let olen = ff::FF_BITS / 8;
-    let hlen = sha;
+    let olen = w.len();
+    let hlen = hash_len(sha);
     let idlen = 19;
     let mut b: [u8; 64] = [0; 64]; /* Not good */
+    let id = match digest_info(sha) {
+        Some(id) => id,
+        None => return false,
+    };
 
     if olen < idlen + hlen + 10 {
         return false;
@@ -246,24 +588,8 @@ pub fn pkcs15(sha: usize, m: &[u8], w: &mut [u8]) -> bool {
     w[i] = 0;
     i += 1;
 
-    if hlen == SHA256 {
-        for j in 0..idlen {
-            w[i] = SHA256ID[j];
-            i += 1
-        }
-    }
-    if hlen == SHA384 {
-        for j in 0..idlen {
-            w[i] = SHA384ID[j];
-            i += 1
-        }
-    }
-    if hlen == SHA512 {
-        for j in 0..idlen {
-            w[i] = SHA512ID[j];
-            i += 1
-        }
-    }
+    w[i..i + idlen].copy_from_slice(id);
+    i += idlen;
     for j in 0..hlen {
         w[i] = b[j];
         i += 1
@@ -272,21 +598,119 @@ pub fn pkcs15(sha: usize, m: &[u8], w: &mut [u8]) -> bool {
     return true;
 }
 
-/* OAEP Message Encoding for Encryption */
+fn hash_ok(sha: usize) -> bool {
+    digest_info(sha).is_some()
+}
+
+/* PSS hash H = Hash(0x00^8 || Hash(M) || salt) */
+fn pss_hash(sha: usize, m: &[u8], salt: &[u8], h: &mut [u8]) {
+    let hlen = hash_len(sha);
+    let mut mp: Vec<u8> = vec![0; 8 + hlen + salt.len()];
+    hashit(sha, Some(m), -1, &mut mp[8..8 + hlen]);
+    mp[8 + hlen..].copy_from_slice(salt);
+    hashit(sha, Some(&mp), -1, h);
+}
+
+/* EMSA-PSS Message Encoding for Signature (RFC 8017 9.1.1). M is hashed with SHA, the mask
+is generated by MGF1 with MGF. EMBITS is one less than the bit length of the modulus.
+W is the length of the modulus, with the encoded message right aligned */
+pub fn pss_encode(
+    sha: usize,
+    mgf: usize,
+    m: &[u8],
+    salt: &[u8],
+    embits: usize,
+    w: &mut [u8],
+) -> bool {
+    let hlen = hash_len(sha);
+    let slen = salt.len();
+    let emlen = (embits + 7) / 8;
+
+    if !hash_ok(sha) || !hash_ok(mgf) || emlen > w.len() {
+        return false;
+    }
+    if emlen < hlen + slen + 2 {
+        return false;
+    }
+    let dblen = emlen - hlen - 1;
+    let mut h: [u8; 64] = [0; 64];
+    let mut db = vec![0; dblen];
+
+    pss_hash(sha, m, salt, &mut h);
+    mgf1(mgf, &h[0..hlen], dblen, &mut db);
+
+    /* DB = PS || 0x01 || salt */
+    db[dblen - slen - 1] ^= 0x01;
+    for (x, y) in db[dblen - slen..dblen].iter_mut().zip(salt) {
+        *x ^= y;
+    }
+    db[0] &= 0xff >> (8 * emlen - embits);
+
+    let d = w.len() - emlen;
+    w.iter_mut().for_each(|x| *x = 0);
+    w[d..d + dblen].copy_from_slice(&db[0..dblen]);
+    w[d + dblen..d + dblen + hlen].copy_from_slice(&h[0..hlen]);
+    w[d + emlen - 1] = 0xbc;
+    true
+}
+
+/* EMSA-PSS Verification (RFC 8017 9.1.2) of the encoded message W against M, for a salt
+of SLEN bytes. See pss_encode */
+pub fn pss_verify(sha: usize, mgf: usize, m: &[u8], slen: usize, embits: usize, w: &[u8]) -> bool {
+    let hlen = hash_len(sha);
+    let emlen = (embits + 7) / 8;
+
+    if !hash_ok(sha) || !hash_ok(mgf) || emlen > w.len() {
+        return false;
+    }
+    if emlen < hlen + slen + 2 {
+        return false;
+    }
+    let d = w.len() - emlen;
+    let dblen = emlen - hlen - 1;
+    let mask = 0xff >> (8 * emlen - embits);
+
+    if w[0..d].iter().any(|&x| x != 0) {
+        return false;
+    }
+    if w[d + emlen - 1] != 0xbc || w[d] & !mask != 0 {
+        return false;
+    }
+
+    let mut db = vec![0; dblen];
+    let mut h: [u8; 64] = [0; 64];
+    mgf1(mgf, &w[d + dblen..d + dblen + hlen], dblen, &mut db);
+    for (x, y) in db[0..dblen].iter_mut().zip(&w[d..d + dblen]) {
+        *x ^= y;
+    }
+    db[0] &= mask;
+
+    if db[0..dblen - slen - 1].iter().any(|&x| x != 0) {
+        return false;
+    }
+    if db[dblen - slen - 1] != 0x01 {
+        return false;
+    }
+
+    pss_hash(sha, m, &db[dblen - slen..dblen], &mut h);
+    h[0..hlen] == w[d + dblen..d + dblen + hlen]
+}
+
+/* OAEP Message Encoding for Encryption. F is the length of the modulus */
 pub fn oaep_encode(sha: usize, m: &[u8], rng: &mut RAND, p: Option<&[u8]>, f: &mut [u8]) -> bool {
-    let olen = RFS - 1;
+    let olen = f.len() - 1;
     let mlen = m.len();
 
-    let hlen = sha;
+    let hlen = hash_len(sha);
 
     let mut seed: [u8; 64] = [0; 64];
 
     let seedlen = hlen;
-    if mlen > olen - hlen - seedlen - 1 {
+    if mlen + hlen + seedlen + 1 > olen {
         return false;
     }
 
-    let mut dbmask: [u8; RFS] = [0; RFS];
+    let mut dbmask = vec![0; olen];
 
     hashit(sha, p, -1, f);
     let slen = olen - mlen - hlen - seedlen - 1;
@@ -319,9 +743,9 @@ pub fn oaep_encode(sha: usize, m: &[u8], rng: &mut RAND, p: Option<&[u8]>, f: &m
         f[i + seedlen] = dbmask[i]
     }
 
-    /* pad to length RFS */
+    /* pad to the length of the modulus */
     let d = 1;
-    for i in (d..RFS).rev() {
+    for i in (d..f.len()).rev() {
         f[i] = f[i - d];
     }
     for i in (0..d).rev() {
@@ -330,31 +754,20 @@ pub fn oaep_encode(sha: usize, m: &[u8], rng: &mut RAND, p: Option<&[u8]>, f: &m
     return true;
 }
 
-/* OAEP Message Decoding for Decryption */
+/* OAEP Message Decoding for Decryption. F is the length of the modulus */
 pub fn oaep_decode(sha: usize, p: Option<&[u8]>, f: &mut [u8]) -> usize {
-    let olen = RFS - 1;
-
-    let hlen = sha;
+    let hlen = hash_len(sha);
     let mut seed: [u8; 64] = [0; 64];
     let seedlen = hlen;
     let mut chash: [u8; 64] = [0; 64];
 
-    if olen < seedlen + hlen + 1 {
+    if f.len() < seedlen + hlen + 2 {
         return 0;
     }
-    let mut dbmask: [u8; RFS] = [0; RFS];
+    let olen = f.len() - 1;
+    let mut dbmask = vec![0; olen];
     //for i in 0..olen-seedlen {dbmask[i]=0}
 
-    if f.len() < RFS {
-        let d = RFS - f.len();
-        for i in (d..RFS).rev() {
-            f[i] = f[i - d];
-        }
-        for i in (0..d).rev() {
-            f[i] = 0;
-        }
-    }
-
     hashit(sha, p, -1, &mut chash);
 
     let x = f[0];
@@ -425,32 +838,63 @@ pub fn private_key_kill(prv: &mut RsaPrivateKey) {
     prv.dp.zero();
     prv.dq.zero();
     prv.c.zero();
+    prv.d.zero();
+    prv.n.zero();
+    for o in prv.others.iter_mut() {
+        o.r.zero();
+        o.d.zero();
+        o.t.zero();
+    }
 }
 
-/* RSA encryption with the public key */
+/* RSA encryption with the public key. F and G are big-endian, normally the length of the
+modulus */
 pub fn encrypt(pbc: &RsaPublicKey, f: &[u8], g: &mut [u8]) {
-    let m = pbc.n.getlen();
-    let mut r = FF::new_int(m);
-
-    FF::frombytes(&mut r, f);
+    let mut r = FF::from_be_bytes(f, pbc.n.getlen());
     r.power(pbc.e, &pbc.n);
-    r.tobytes(g);
+    r.to_be_bytes(g);
 }
 
-/* RSA decryption with the private key */
-pub fn decrypt(prv: &RsaPrivateKey, g: &[u8], f: &mut [u8]) {
+/* RSA decryption with the private key. See encrypt. The input is blinded by R^E for a
+random R from RNG, and the result is encrypted again and compared with the input so that a fault
+in the CRT exponentiation cannot leak a factor of the modulus (Bellcore attack). False,
+with F cleared, if that check fails */
+pub fn decrypt(prv: &RsaPrivateKey, rng: &mut RAND, g: &[u8], f: &mut [u8]) -> bool {
+    if prv.blind {
+        crt_decrypt(prv, Some(rng), g, f)
+    } else {
+        crt_decrypt(prv, None, g, f)
+    }
+}
+
+/* decrypt, blinded only if there is an RNG */
+fn crt_decrypt(prv: &RsaPrivateKey, rng: Option<&mut RAND>, g: &[u8], f: &mut [u8]) -> bool {
+    let blind = rng.is_some();
     let n = prv.p.getlen();
-    let mut r = FF::new_int(2 * n);
+    let w = prv.n.getlen();
+    let mut r = FF::new_int(w);
+    let mut x = FF::from_be_bytes(g, w);
+    let mut bi = FF::new_int(w);
+
+    x.rmod(&prv.n);
+    r.copy(&x);
+    if let Some(rng) = rng {
+        let mut b = FF::new_int(w);
+        b.randomnum(&prv.n, rng);
+        bi.copy(&b);
+        bi.invmodp(&prv.n);
+        b.power(prv.e, &prv.n);
+        r = FF::mul(&r, &b).dmod(&prv.n);
+    }
 
-    FF::frombytes(&mut r, g);
-    let mut jp = r.dmod(&prv.p);
-    let mut jq = r.dmod(&prv.q);
+    let mut jp = residue(&r, &prv.p);
+    let mut jq = residue(&r, &prv.q);
 
     jp.skpow(&prv.dp, &prv.p);
     jq.skpow(&prv.dq, &prv.q);
 
-    r.zero();
-    r.dscopy(&jp);
+    let mut s = FF::new_int(2 * n);
+    s.dscopy(&jp);
     jp.rmod(&prv.q);
     if FF::comp(&jp, &jq) > 0 {
         jq.add(&prv.q)
@@ -462,8 +906,996 @@ pub fn decrypt(prv: &RsaPrivateKey, g: &[u8], f: &mut [u8]) {
     jq = t.dmod(&prv.q);
 
     t = FF::mul(&jq, &prv.p);
-    r.add(&t);
-    r.norm();
+    s.add(&t);
+    s.norm();
+    let mut s = FF::resize(&s, w);
+
+    /* Garner's algorithm (RFC 8017 5.1.2) for any more primes, with M the product of
+    those before */
+    if !prv.others.is_empty() {
+        let mut m = FF::resize(&FF::mul(&prv.p, &prv.q), w);
+        for o in prv.others.iter() {
+            let mut j = residue(&r, &o.r);
+            j.skpow(&o.d, &o.r);
+            let u = residue(&s, &o.r);
+            if FF::comp(&u, &j) > 0 {
+                j.add(&o.r)
+            }
+            j.sub(&u);
+            j.norm();
+            let h = FF::mul(&o.t, &j).dmod(&o.r);
+            t = FF::mul(&m, &FF::resize(&h, w));
+            t.norm();
+            s.add(&FF::resize(&t, w));
+            s.norm();
+            t = FF::mul(&m, &FF::resize(&o.r, w));
+            t.norm();
+            m = FF::resize(&t, w);
+        }
+    }
+
+    if blind {
+        s = FF::mul(&s, &bi).dmod(&prv.n);
+    }
+    if prv.check {
+        let mut y = FF::new_int(w);
+        y.copy(&s);
+        y.power(prv.e, &prv.n);
+        if FF::comp(&y, &x) != 0 {
+            s.zero();
+            f.iter_mut().for_each(|x| *x = 0);
+            return false;
+        }
+    }
+    s.to_be_bytes(f);
+    true
+}
+
+/* RSASSA-PSS signature (RFC 8017 8.1) on M with a random salt of SLEN bytes.
+SIG is the length of the modulus. See pss_encode for SHA and MGF */
+pub fn sign_pss(
+    prv: &RsaPrivateKey,
+    sha: usize,
+    mgf: usize,
+    slen: usize,
+    rng: &mut RAND,
+    m: &[u8],
+    sig: &mut [u8],
+) -> bool {
+    let k = private_key_bytes(prv);
+    let mut salt: Vec<u8> = vec![0; slen];
+    let mut w = vec![0; k];
+
+    if sig.len() != k {
+        return false;
+    }
+
+    for x in salt.iter_mut() {
+        *x = rng.getbyte()
+    }
+    if !pss_encode(sha, mgf, m, &salt, prv.n.nbits() - 1, &mut w) {
+        return false;
+    }
+    decrypt(prv, rng, &w, sig)
+}
+
+/* RSASSA-PSS verification (RFC 8017 8.2) of signature SIG on M with a salt of SLEN bytes */
+pub fn verify_pss(
+    pbc: &RsaPublicKey,
+    sha: usize,
+    mgf: usize,
+    slen: usize,
+    m: &[u8],
+    sig: &[u8],
+) -> bool {
+    let k = public_key_bytes(pbc);
+    if sig.len() != k {
+        return false;
+    }
+    let s = FF::from_be_bytes(sig, pbc.n.getlen());
+    if FF::comp(&s, &pbc.n) >= 0 {
+        return false;
+    }
+    let mut w = vec![0; k];
+    encrypt(pbc, sig, &mut w);
+    pss_verify(sha, mgf, m, slen, pbc.n.nbits() - 1, &w)
+}
+
+/* constant time comparison of equal length slices */
+fn ct_equal(a: &[u8], b: &[u8]) -> bool {
+    let mut d = 0;
+    for (x, y) in a.iter().zip(b) {
+        d |= x ^ y;
+    }
+    d == 0
+}
+
+/* RSASSA-PKCS1-v1_5 signature (RFC 8017 8.2.1) on M, hashed with SHA. SIG is the length of
+the modulus. RNG only blinds the exponentiation, see decrypt */
+pub fn sign_pkcs1v15(
+    prv: &RsaPrivateKey,
+    sha: usize,
+    rng: &mut RAND,
+    m: &[u8],
+    sig: &mut [u8],
+) -> bool {
+    let k = private_key_bytes(prv);
+    let mut w = vec![0; k];
+    if sig.len() != k || !pkcs15(sha, m, &mut w) {
+        return false;
+    }
+    decrypt(prv, rng, &w, sig)
+}
+
+/* RSASSA-PKCS1-v1_5 verification (RFC 8017 8.2.2) of signature SIG on M. The encoded
+message is rebuilt and compared in constant time rather than parsed. The DigestInfo
+may have NULL or absent hash parameters */
+pub fn verify_pkcs1v15(pbc: &RsaPublicKey, sha: usize, m: &[u8], sig: &[u8]) -> bool {
+    let k = public_key_bytes(pbc);
+    let mut w = vec![0; k];
+    let mut em = vec![0; k];
+    if sig.len() != k || !pkcs15(sha, m, &mut w) {
+        return false;
+    }
+    let s = FF::from_be_bytes(sig, pbc.n.getlen());
+    if FF::comp(&s, &pbc.n) >= 0 {
+        return false;
+    }
+    encrypt(pbc, sig, &mut em);
+    let good = ct_equal(&w, &em);
+
+    /* DigestInfo without the NULL parameters. The separator at SEP moves two bytes right */
+    let sep = k - hash_len(sha) - 20;
+    let mut alt = w.clone();
+    alt[sep] = 0xff;
+    alt[sep + 1] = 0xff;
+    alt[sep + 2] = 0x00;
+    alt[sep + 3] = 0x30;
+    alt[sep + 4] = w[sep + 2] - 2;
+    alt[sep + 5] = 0x30;
+    alt[sep + 6] = 0x0b;
+    alt[sep + 7..sep + 18].copy_from_slice(&w[sep + 5..sep + 16]);
+    good | ct_equal(&alt, &em)
+}
+
+/* RSAES-PKCS1-v1_5 encryption (RFC 8017 7.2.1) of M, at most k-11 bytes for a k byte
+modulus. C is k bytes */
+pub fn encrypt_pkcs1v15(pbc: &RsaPublicKey, rng: &mut RAND, m: &[u8], c: &mut [u8]) -> bool {
+    let k = public_key_bytes(pbc);
+    let mlen = m.len();
+    if mlen + 11 > k || c.len() != k {
+        return false;
+    }
+    let mut w = vec![0; k];
+    w[1] = 2;
+    for x in w[2..k - mlen - 1].iter_mut() {
+        while *x == 0 {
+            *x = rng.getbyte();
+        }
+    }
+    w[k - mlen..].copy_from_slice(m);
+    encrypt(pbc, &w, c);
+    true
+}
+
+/* all ones if a<b, else zero */
+fn ct_lt(a: usize, b: usize) -> usize {
+    ((a as isize).wrapping_sub(b as isize) >> (isize::BITS - 1)) as usize
+}
+
+/* a if mask m is all ones, b if it is zero */
+fn ct_select(m: usize, a: usize, b: usize) -> usize {
+    (a & m) | (b & !m)
+}
+
+/* HMAC-SHA256 PRF in counter mode for implicit rejection */
+fn rejection_prf(kdk: &[u8], label: &[u8], out: &mut [u8]) {
+    let bits = (out.len() * 8) as u16;
+    for (i, o) in out.chunks_mut(32).enumerate() {
+        let mut t: Vec<u8> = Vec::with_capacity(label.len() + 4);
+        t.extend_from_slice(&(i as u16).to_be_bytes());
+        t.extend_from_slice(label);
+        t.extend_from_slice(&bits.to_be_bytes());
+        let h = HASH256::hmac(kdk, &t);
+        let n = o.len();
+        o.copy_from_slice(&h[0..n]);
+    }
+}
+
+/* RSAES-PKCS1-v1_5 decryption (RFC 8017 7.2.2) of the k byte ciphertext C into M.
+Malformed padding is never reported. Instead a synthetic message derived from the private
+exponent and the ciphertext is returned, with no branches on secret data, as in OpenSSL
+(implicit rejection, draft-irtf-cfrg-rsa-guidance). This keeps Bleichenbacher and Marvin
+padding oracles closed. Returns the message length, or None if C is out of range or the
+decryption failed its fault check. RNG blinds the exponentiation, see decrypt */
+pub fn decrypt_pkcs1v15(
+    prv: &RsaPrivateKey,
+    rng: &mut RAND,
+    c: &[u8],
+    m: &mut [u8],
+) -> Option<usize> {
+    let k = private_key_bytes(prv);
+    if c.len() != k || k < 11 {
+        return None;
+    }
+    let cf = FF::from_be_bytes(c, prv.n.getlen());
+    if FF::comp(&cf, &prv.n) >= 0 {
+        return None;
+    }
+    let mut em = vec![0; k];
+    if !decrypt(prv, rng, c, &mut em) {
+        return None;
+    }
+
+    /* key derivation key HMAC-SHA256(SHA256(d), C) */
+    let mut db = vec![0; k];
+    prv.d.to_be_bytes(&mut db);
+    let mut h = HASH256::new();
+    h.process_array(&db);
+    let kdk = HASH256::hmac(&h.hash(), c);
+    for x in db.iter_mut() {
+        *x = 0
+    }
+
+    /* synthetic message, with a length taken from candidates below the maximum */
+    let mut lens: [u8; 256] = [0; 256];
+    let mut syn = vec![0; k];
+    rejection_prf(&kdk, b"length", &mut lens);
+    rejection_prf(&kdk, b"message", &mut syn);
+    let max = k - 10;
+    let mut mask = max;
+    mask |= mask >> 1;
+    mask |= mask >> 2;
+    mask |= mask >> 4;
+    mask |= mask >> 8;
+    let mut slen = 0;
+    for l in lens.chunks(2) {
+        let x = ((l[0] as usize) << 8 | l[1] as usize) & mask;
+        slen = ct_select(ct_lt(x, max), x, slen);
+    }
+
+    /* 0x00 || 0x02 || PS || 0x00 || M, with at least 8 bytes of nonzero PS */
+    let mut good = ct_lt(em[0] as usize, 1) & ct_lt((em[1] ^ 2) as usize, 1);
+    let mut found = 0;
+    let mut zero = 0;
+    for (i, x) in em.iter().enumerate().skip(2) {
+        let z = ct_lt(*x as usize, 1);
+        zero = ct_select(!found & z, i, zero);
+        found |= z;
+    }
+    good &= !ct_lt(zero, 10);
+    good &= !ct_lt(m.len(), k - zero - 1);
+    let mi = ct_select(good, zero + 1, k - slen);
 
-    r.tobytes(f);
+    let g = good as u8;
+    for (j, x) in m.iter_mut().take(k - mi).enumerate() {
+        *x = (em[mi + j] & g) | (syn[mi + j] & !g);
+    }
+    Some((k - mi).min(m.len()))
+}
+
+/* rsaEncryption, 1.2.840.113549.1.1.1 */
+const RSA_ENCRYPTION_OID: [u8; 9] = [0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01];
+
+const PKCS1_PRIVATE_LABEL: &str = "RSA PRIVATE KEY";
+const PKCS1_PUBLIC_LABEL: &str = "RSA PUBLIC KEY";
+const PKCS8_LABEL: &str = "PRIVATE KEY";
+const SPKI_LABEL: &str = "PUBLIC KEY";
+
+/* big-endian bytes of X */
+fn ff_bytes(x: &FF) -> Vec<u8> {
+    let mut b = vec![0; x.getlen() * big::MODBYTES];
+    x.to_be_bytes(&mut b);
+    b
+}
+
+/* true if the INTEGER magnitude V equals X */
+fn ff_equals(x: &FF, v: &[u8]) -> bool {
+    let b = ff_bytes(x);
+    let mut t = vec![0; b.len()];
+    fixed(v, &mut t) && t == b
+}
+
+/* AlgorithmIdentifier for rsaEncryption, with NULL parameters */
+fn algorithm(out: &mut Vec<u8>) {
+    let mut a = Vec::new();
+    der::encode_tlv(der::OID, &RSA_ENCRYPTION_OID, &mut a);
+    der::encode_tlv(der::NULL, &[], &mut a);
+    der::encode_sequence(&a, out);
+}
+
+fn read_algorithm(r: &mut der::Reader) -> bool {
+    let mut a = match r.read_sequence() {
+        Some(a) => a,
+        None => return false,
+    };
+    a.read(der::OID) == Some(&RSA_ENCRYPTION_OID[..])
+        && a.read(der::NULL) == Some(&[][..])
+        && a.is_empty()
+}
+
+/* PKCS#1 RSAPublicKey DER encoding */
+pub fn public_key_to_pkcs1_der(pbc: &RsaPublicKey) -> Vec<u8> {
+    let mut k = Vec::new();
+    der::encode_integer(&ff_bytes(&pbc.n), &mut k);
+    der::encode_integer(&(pbc.e as u32).to_be_bytes(), &mut k);
+    let mut out = Vec::new();
+    der::encode_sequence(&k, &mut out);
+    out
+}
+
+/* Public key from PKCS#1 RSAPublicKey DER. See public_key_from_components for the limits */
+pub fn public_key_from_pkcs1_der(der: &[u8]) -> Option<RsaPublicKey> {
+    let mut r = der::Reader::new(der);
+    let mut k = r.read_sequence()?;
+    let n = k.read_integer()?;
+    let e = k.read_small_integer()?;
+    if !r.is_empty() || !k.is_empty() || e > i32::MAX as usize {
+        return None;
+    }
+    public_key_from_components(n, e as isize)
+}
+
+/* SubjectPublicKeyInfo DER encoding */
+pub fn public_key_to_der(pbc: &RsaPublicKey) -> Vec<u8> {
+    let mut p = Vec::new();
+    algorithm(&mut p);
+    der::encode_bit_string(&public_key_to_pkcs1_der(pbc), &mut p);
+    let mut out = Vec::new();
+    der::encode_sequence(&p, &mut out);
+    out
+}
+
+/* Public key from SubjectPublicKeyInfo DER */
+pub fn public_key_from_der(der: &[u8]) -> Option<RsaPublicKey> {
+    let mut r = der::Reader::new(der);
+    let mut p = r.read_sequence()?;
+    if !r.is_empty() || !read_algorithm(&mut p) {
+        return None;
+    }
+    let k = p.read_bit_string()?;
+    if !p.is_empty() {
+        return None;
+    }
+    public_key_from_pkcs1_der(k)
+}
+
+/* PKCS#1 RSAPrivateKey DER encoding, with the CRT values */
+pub fn private_key_to_der(prv: &RsaPrivateKey) -> Vec<u8> {
+    let mut k = Vec::new();
+    /* version multi(1) when otherPrimeInfos follow */
+    der::encode_integer(&[prv.others.len().min(1) as u8], &mut k);
+    der::encode_integer(&ff_bytes(&prv.n), &mut k);
+    der::encode_integer(&(prv.e as u32).to_be_bytes(), &mut k);
+    for x in [&prv.d, &prv.q, &prv.p, &prv.dq, &prv.dp, &prv.c].iter() {
+        der::encode_integer(&ff_bytes(x), &mut k);
+    }
+    if !prv.others.is_empty() {
+        let mut s = Vec::new();
+        for o in prv.others.iter() {
+            let mut i = Vec::new();
+            der::encode_integer(&ff_bytes(&o.r), &mut i);
+            der::encode_integer(&ff_bytes(&o.d), &mut i);
+            der::encode_integer(&ff_bytes(&o.t), &mut i);
+            der::encode_sequence(&i, &mut s);
+        }
+        der::encode_sequence(&s, &mut k);
+    }
+    let mut out = Vec::new();
+    der::encode_sequence(&k, &mut out);
+    out
+}
+
+/* Private key from PKCS#1 RSAPrivateKey DER, with two primes or, as version multi(1), with
+otherPrimeInfos for more. The CRT values must agree with those derived from the primes and
+private exponent */
+pub fn private_key_from_der(der: &[u8]) -> Option<RsaPrivateKey> {
+    let mut r = der::Reader::new(der);
+    let mut k = r.read_sequence()?;
+    let version = k.read_small_integer()?;
+    if !r.is_empty() || version > 1 {
+        return None;
+    }
+    let n = k.read_integer()?;
+    let e = k.read_small_integer()?;
+    let d = k.read_integer()?;
+    let p = k.read_integer()?;
+    let q = k.read_integer()?;
+    let dp = k.read_integer()?;
+    let dq = k.read_integer()?;
+    let qinv = k.read_integer()?;
+    let mut primes = vec![p, q];
+    let mut crt = Vec::new();
+    if version == 1 {
+        let mut s = k.read_sequence()?;
+        while !s.is_empty() {
+            let mut i = s.read_sequence()?;
+            primes.push(i.read_integer()?);
+            crt.push((i.read_integer()?, i.read_integer()?));
+            if !i.is_empty() {
+                return None;
+            }
+        }
+        if crt.is_empty() {
+            return None;
+        }
+    }
+    if !k.is_empty() || e > i32::MAX as usize {
+        return None;
+    }
+    let prv = private_key_from_primes(n, e as isize, d, &primes)?;
+    if !ff_equals(&prv.dq, dp) || !ff_equals(&prv.dp, dq) || !ff_equals(&prv.c, qinv) {
+        return None;
+    }
+    for (o, v) in prv.others.iter().zip(crt.iter()) {
+        if !ff_equals(&o.d, v.0) || !ff_equals(&o.t, v.1) {
+            return None;
+        }
+    }
+    Some(prv)
+}
+
+/* PKCS#8 PrivateKeyInfo DER encoding */
+pub fn private_key_to_pkcs8_der(prv: &RsaPrivateKey) -> Vec<u8> {
+    let mut p = Vec::new();
+    der::encode_integer(&[0], &mut p);
+    algorithm(&mut p);
+    der::encode_tlv(der::OCTET_STRING, &private_key_to_der(prv), &mut p);
+    let mut out = Vec::new();
+    der::encode_sequence(&p, &mut out);
+    out
+}
+
+/* Private key from PKCS#8 PrivateKeyInfo DER */
+pub fn private_key_from_pkcs8_der(der: &[u8]) -> Option<RsaPrivateKey> {
+    let mut r = der::Reader::new(der);
+    let mut p = r.read_sequence()?;
+    if !r.is_empty() || p.read_small_integer()? != 0 || !read_algorithm(&mut p) {
+        return None;
+    }
+    let k = p.read(der::OCTET_STRING)?;
+    /* attributes [0] may follow */
+    if p.peek() == Some(der::CONTEXT_0) {
+        p.read(der::CONTEXT_0);
+    }
+    if !p.is_empty() {
+        return None;
+    }
+    private_key_from_der(k)
+}
+
+/* PKCS#1 "RSA PRIVATE KEY" PEM encoding */
+pub fn private_key_to_pem(prv: &RsaPrivateKey) -> String {
+    pem::encode(PKCS1_PRIVATE_LABEL, &private_key_to_der(prv))
+}
+
+/* Private key from PKCS#1 "RSA PRIVATE KEY" PEM */
+pub fn private_key_from_pem(p: &str) -> Option<RsaPrivateKey> {
+    private_key_from_der(&pem::decode(PKCS1_PRIVATE_LABEL, p)?)
+}
+
+/* PKCS#8 "PRIVATE KEY" PEM encoding */
+pub fn private_key_to_pkcs8_pem(prv: &RsaPrivateKey) -> String {
+    pem::encode(PKCS8_LABEL, &private_key_to_pkcs8_der(prv))
+}
+
+/* Private key from PKCS#8 "PRIVATE KEY" PEM */
+pub fn private_key_from_pkcs8_pem(p: &str) -> Option<RsaPrivateKey> {
+    private_key_from_pkcs8_der(&pem::decode(PKCS8_LABEL, p)?)
+}
+
+/* PKCS#1 "RSA PUBLIC KEY" PEM encoding */
+pub fn public_key_to_pkcs1_pem(pbc: &RsaPublicKey) -> String {
+    pem::encode(PKCS1_PUBLIC_LABEL, &public_key_to_pkcs1_der(pbc))
+}
+
+/* Public key from PKCS#1 "RSA PUBLIC KEY" PEM */
+pub fn public_key_from_pkcs1_pem(p: &str) -> Option<RsaPublicKey> {
+    public_key_from_pkcs1_der(&pem::decode(PKCS1_PUBLIC_LABEL, p)?)
+}
+
+/* SubjectPublicKeyInfo "PUBLIC KEY" PEM encoding */
+pub fn public_key_to_pem(pbc: &RsaPublicKey) -> String {
+    pem::encode(SPKI_LABEL, &public_key_to_der(pbc))
+}
+
+/* Public key from "PUBLIC KEY" PEM */
+pub fn public_key_from_pem(p: &str) -> Option<RsaPublicKey> {
+    public_key_from_der(&pem::decode(SPKI_LABEL, p)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::create_rng;
+
+    // 2048-bit test key generated with OpenSSL, e = 65537
+    const N: &str = "ced7f571aeef4f01714e8f8ac19218ee4f63911feb32edbc87c6b64d903aca1f7162eaee656bfe929b7223e063118066b89b7e85ca2c952445c181e8e8f2dbaab35df4ddd699c74d34865dbce487c66a6c7b45685cf7f1adb87babfd90927262562dff62363e3d47ea9a01ccba49926d7fa3914a186c05ca0f58d455b892734ce995dc65355e5df38bb0d29cf8f6a059e8be7eeccb69462c6305ea772bd7634bacf7e24e2b8b5a3b9161a360a165cbccb117f73ab447ae4f461cd3652dbb5bffc8bf30cfd04496eddbc1566f43650d8f33a6120bda8b45dbe81568c9f509fc33c2e3e1a7f11a80ff21a8ec8a882bc22acef94e4875b5f0b09ca10bad5d92c223";
+    const P: &str = "f48418cc4ee600ba6c6517ebb4560a307e1a28629c8ac47e4afa6006b8094fbbfd9d662979e9d2f095bfa48b195991b0b043e0a6029941c1581578989542fce81e470503fd4b59570c6d612b51996aa40105be1bf30513c3b6448bcdcfd20debaa3c8313855dff34f215f55421c825f733ac011030b7103ba8b8d12b002a0ef1";
+    const Q: &str = "d88ee9bbbc6d4ed79936dd9d8f735cb3616fb446f561666e74533d51b447229b68e5a5922d2968bedf31aca72bc9c98861a94399558c0ea3186572635a80129e6ec3f5011e7a2d34217c047933438d4c7b9f193e90c1129c783b1abe4057fb81d9bf648779df392f4032c1e94b14037dfd048b92794967ce55abdb35e6028a53";
+    const D: &str = "4a95264472ae898f5c9ad6395328e9e26c2fdbb165ae7c78a180ae55736f2f587d1b0cd91f2afca54275baa7fb30e15aa8b030c3ef2d7a2d95157c514e45db9d4685d778721c0af834bb4a6b84ffcc34ad9f195cd340f81d0acb552dde066046430adcee3ddedd5be424da074718a63ae36e48422e622d0839d66ee1e5e6b0b9e1b2f0f9c5622d324f1590cdbd636c37ba84070a345d7f08c870f925e02bb9106ba34f878f4a7cc14ab3d97e2bf82cfd9dc3e4458c683bf3244af831928dc88d6250cdfcf36e69ab1b4224ea061fada4081ae25df58aa89a854591bf9d9a7ec0ab081c6ab47f74e1a0f4d0e357b8b0b8b43549d60d8906d31575716b72161d21";
+
+    // RSASSA-PSS (hash, MGF1 hash, message, salt, signature). Checked against OpenSSL
+    const PSS_VECTORS: [(usize, usize, &[u8], &str, &str); 3] = [
+        (
+            SHA256,
+            SHA256,
+            b"abc",
+            "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+            "cb81eeaf811ebd57d7b632b9840af425c8e8967c144ab3ccdb922b792336ec11d5b99f9e15481da61da58df5bf364570e23ff72ce4eed8e4794ca838dc1191848ed6194f329cbb29df7dd6e1d08ff891062df75d28ce7f11311ad04128ff2bf810d7eb4f29171289a9403a1f1663d292380138843ba15dd1d00e865bff8220f9865d4b1eca40c3dcaaad5058a57449e55d5b7fb0fe7b3e999dd57b293c31d4ad776520158242c0f979a6acf57267c8d0b5eba51e74bcbe0408242be9def19fe2d06bc8fa57bbd8c30d47046e4187866c9808dc91f7db1765b6a0d4de33f41547b78af2db5b8bd00aea0eb456125d3d9b38f6908749922968a59cd9150a1a788c",
+        ),
+        (
+            SHA384,
+            SHA256,
+            b"Hello World",
+            "",
+            "1e71a40716a5d12ffdcf19e809cb75759e2cd3a213ee367fa072429880423ae002410aaf15471b676ad1952068dcb6e2d6710bd91d500ba7923375470b5c9b2860f7a6b70c9e3271aa665e550de3117b9b5e8acb33419e29a494035e69cba1206a8cea12c4588b88bdd5bbc122cfba4667db0c77de573bee598d67b5fcf2593a19de1373b0e3c726aed304ec33bcc28f2415d1fe4934ca7b5e03bfd6d4681f539cb58cf33edb2b187363121d2c987795adbfc3e4eef1c4eae2bfcae61de824923ada52fec0805d448c9da255b0d95788da630c98688b1491d4d48f71b0ac7b920faea1c4b4d9283d52c85f50b29f1f47bd6b91463be3d3a316bcbc5beda0495c",
+        ),
+        (
+            SHA512,
+            SHA512,
+            &[0; 100],
+            "6465666768696a6b6c6d6e6f7071727374757677",
+            "67a5b2fdea51eaad751b47b1dec8a04b1104b5e345ad3e7fae22878f8e674afa16756c162ddcb7454285d9821845137ab0fee200fa5e69ac9dcd883ec2ea8f89d8cf2e024114da890996afd04db29ab9980e3374030619f44264dd19f0d0b5e79948bae1ddea3f2b7b884bda3306c50e0477c20919c2cbc5191b79c45aa674742b7ee9257afd1cf72ef7a80e9164d2839a283c63b8fbacbbfdaa58fcd2a2cc2f2147b8350b6fec931ba87bd0e836210253605be497977383dfeb92d739839ba88f6540d9c935ffebe70241457a1848d51f660e15f811c258a7c1c16b79dc729e959b60f2c8e6bfc5c7d7aec750dc28732ee2519acdfe725df8597948cd7ba6d3",
+        ),
+    ];
+
+    // RSASSA-PKCS1-v1_5 signatures on "Hello World" made by OpenSSL
+    const PKCS1_VECTORS: [(usize, &str); 6] = [
+        (SHA256, "12eac3057b02b8911f70f2d3a2417193abe94ee5986e8d0358bf792f1356f81af896437a05bbd87fbf4e9921200016ea6ebe40896526bd6705330d90e86724d0009a9fecb91334dc828eae4f66288bdec245010a1048a6a5e3efc2a2952f8298f809eba0dc8ffbd55c43599b549f2b69e9e3bebe6a1c35452450c7f07e9fa3a5a0a778e81043bf5f8404767718ddde4eb2d3d2c534e342bc0f2f54497575fa6ef211a846275185de46f74092858e8961f1cb363511dff5ef40042b44ba6dd2578519ffda8d0ae637e7a19f7de86ec465c5bff7cfacfe0bcb7a9bbd270ca3ea36835aaed1bd6e47bbfb49e566e5a58875685eede464cf107c87e90ed05cdbd31e"),
+        (SHA384, "99a25645a672a1d39e3d0f7be8794caa1b5b9c995408af17fd01f0694bd64af4bad9561c7678450212369d9a38eccad55c04f295b4e6abe71dc1f0d8297249828c0f133a0ce41190a7bdf7fc0b7374899a1e21239e009a69ab53ab438e2f357dacb8d7b0a0b9e006b48a0220d92e67a30cb0f8d17a526c64db6c68a24076ed8806f7fa1679e837f7ab7caf1b567fb712b9209f9c547e899046e7b7f8374e06fbac2d9ecd74d526b4025216ff2a09fcb030a49c029f9b870c52283e2dab807b4b26e9e58a626e46b2dfdfc23416d27814acc6a425a734883a6843bfded8ef8dfce665bcbd82b4d2b28ae71a8ef1ad80b03be58e821758de19ec425be265df4725"),
+        (SHA512, "028a81846a3a3fb30fbac9f7fb55499267dd896880e043676a8122d7158bdf88f2ebf5604d589ad8dd60626f7f861aa4685408283246b4fbb5baa7bfe363d3c839746cbda3e225f84d4f8a288f4af46e72a1d582a2433b0864eef43a61cd7b18ff7bf2aa096c9b92b15f5c8f31c2c35b3544204121afdc8ae07191d6c596c5cd596bd810f71486aa363023d813b15d338f0aef665d93254b24f5d7074b4014ce8f66e12bdde79998bdd40fad29582225902f252a2f27822b07266466ab2f9598f1ce31dcd0d229cf2a9b70e93e858263813bf6d9d4b8c9baab302df85b33f613df8c6b066be293cccd24d14dd750701397842aeace2fa5fe726d27508da95e91"),
+        (SHA3_256, "28b138889c2ea87b2b90cccd41b0bd8b1754577119a3ebd6b989d89ebcb3de7528d521dd31bcbfc6d6f248fcfc286784d41ed302a7f2a1d75b64466761d00ffdc7427247ebe4ef9355adfab201033a7f149de15a223efbdd240721231e1e6187534bc5b567ac1b605acd67f1cdb7b5e1b96954b7072a65302b5f334224e3124bc4e4a979b51f0a9d6e8f6054cd6f0fdc20f709de4419625f56c4c7364680b8ab37a8e73de0d64867667cda4e1209c691cf02dd2a9189bf3412ac85693fe99304705fcd9231d0a73c77c0b45eb3dcadc29cd4be4cc4baa6868990cbc77f32097c9eec9bc4a9108787007d8787058adde814955699a185c47689b072188e0a8e03"),
+        (SHA3_384, "4466d0dbb61c88844c5fb444c3b35ee034183fa7459a36efed656ab4d1e6f89db1d6365d6ebed35ad395029176a839ae7e88aee4e8a11a6c6890c567250ae07eea3ed038b20d417ff9493f5d7ca565b7eb771f2f1afae508a851fcf8f5d68884182d06aca74421ca85923e2d7d68d0ded3ee235cac5e30e212489e624151a1f18b8941dbdf5b7850d8e34816a8fe6417bcd911da62d3e455aa466175f896014ddc109917e5ba82ac117eb5e2d21a1f3953a74d0244df04a2b3ccf4af8125b8677732ee6f4ac70df23cc74e81735a982d50fd2b6c05058e896bfa60f53ec893630e1982ed61f1a07d29604ff428db1208882c21a3412062d1cd517000c52d43d0"),
+        (SHA3_512, "5443e24c436abbfc1799651a35235f2e5b269c26ca622d63db021bdf547df56a9b2eeb244974564d544c34a785e0b9d0a268ec44e1963d777d74a770ae9c322075a14c1ffa5dae183e12aabe8f4604b5238b7dd363a2adfba63fc6d5f624ce668c3cf813c8701702a97a0c7196207ff0852e4f458b44324366bba90d313778e1381c722d58487be08b7d5dbdce0b76632bc3fe93aec80475ebbb52424090646dd204a68b1e4935878853905bcc59639e723bd9da52384530f097528effe94d1abc258214b6841c4e93641ff018283ac2ae47c1eeaf4540f9073fbda9643cbe8e1211cac07a862d94f5a6d105797b73317289d49465b59a8424e4057f7fd02d3e"),
+    ];
+
+    // SHA256 encodings signed with the raw private key. DigestInfo without NULL parameters,
+    // trailing data after a short padding, a corrupt padding byte and a block type of 2
+    const PKCS1_NO_NULL: &str = "4e5329997fd6368f44deed956fc7337d62f6aac0862a245537a96564371870a39d7222d61994a22b492a6f8acdbc3ed28d8e81005eb348b7eac911f401466f3d082765b10ce15a80411d1132ac618e6af6670d5d94f7d7f68d7c0099f2e6abdf2ff5901a2920cdfd34afd30a64b28c2a1ad245a390ddf873f75f7620f6d170198a630e3a4c5552c30a29e9dfadd2dae10ab10c543aadd17cdf92f5338878c1a2cd7e11a8b5f5c4259e610cae0c9632c825665312bdbb56db5bbbee528193b379b6addf25392de36d418d226a8881cee90fdf05c4491729fbd262c1a63ac05b5a92ac6cea3315267d1034b8823be7b1dcfa8afaa461dc3a470b237c6f0f0cf190";
+    const PKCS1_BAD: [&str; 3] = [
+        "0755ee92de47248c03d835d2dd3686888402fd965d8d86578385ceef2194feac00e3227ce3c30e8d8aeed535cb67cae72b5b50433c43b45ccc7378283dd7c39be9b6341e59d106f088fcabd16938f5439743f20b3fdf56af3a030cba401f9733871b4fbb2e01f2086e571cbf99f37c957cd4c910baf75a75e903703791fd924457948e3f2c400fc42e0dff2d50d92d28a91a5f494a4336dc1e6e89117c72d8c26c875f23ace60a09f0a8f87fc47b239d2012f541074729278fe9256f03078ce3250e4bcd18bda06bb7feb6588c96035b81a63a44c25e83d4fbf548f920f614f9036cde1014c2bd32ed5474a257bdc6dbaaeb40ce4f4681f500ca0e3269c0d79e",
+        "6f91b57950d9b9a0a7d4dc8c4582f4631bb80cc5017488e8c7a965ff090d074701a0b2cb48a78b86511483f61d01d628ee60d28a08d287518416a094e78b2f2aaa1e263d5b52f21c542f8e99b41b52d7faee8a235f025a46aa71c95cef3317ad800f8be0b310ee6d66e689404ccc796e293e3f289727ef3598b394c7f0c8af6f84d340d6e3b0635fa36f4be215a6bc9945ffcd8118cb681b612c45bb1e4586e3bf4d7b2d6e69efebf636c0ce92f1ecc160eda238ce6ac6e506fe770f17c2dc153ec10968cc1d4f8c3e560aab2c6cbf7efb3349bc4564a799091368b034257d3cb732c529fe34783e9e0bdfa92e57bbd59aafc7b549b2ec0774e16a0fc1533a7b",
+        "85580058b5af470a25878b763b64d683708ab9dd404354ee47fa217d77b8a40358333e4cac21025a1e05602f1f4d6998326d37b5b5ce7618ce5adedfacdca259aabfa3728f60652edc8b37629dc370845ff8d749ab5f1154acb766e08159343f87f4c5016d1c73edadeb59ec5ec73eb0b48eefb38b6fdb2d90c329427c8b3d09cdd58e0894d3789e31a2b4e362c77a919095a02903d8b4ad89d0d667e69905db98ac4987129335a66fcef95290a14189e7a7000226de9274b0684090aa8b57ed6624413be15bf3e4ca9671bde97aafd1614ea1f81059a26c9e5bee73a4f90daac620f9c44c01509421055e2cd9fab91baf42ed7875a38bb2d956d3dd1aba50ef",
+    ];
+
+    // RSAES-PKCS1-v1_5 ciphertexts and OpenSSL decryptions. The first two are valid, the rest
+    // give the synthetic messages of implicit rejection
+    const DECRYPT_VECTORS: [(&str, &str); 7] = [
+        // ok
+        (
+            "7519fa4550e7ff00f9e6f46683e1252f03f9a5e6e6ec14690084a9c84fe29e885af89afb4978984f6c4abc474ea047aafe500064a3fe01524cb0a630a93e350d0e31e1c00a6c8f5cdc2674211066f7415db05b6fdd812a86f3856ecc16e4b4b17fe0f77ee63df33f9bf82dbb07756c0dc61478494bac92605bd88332bccaae62623baa4b456ca7284d00f4840e170f8cf1f8b84bf75877c0bac40cd482d9ad14269a4de1b36fb5daa6253ebddc5cf16f7cbb4e8ddac3da8718024a221312878c6c13cec1821f7adaec7ced49ecfedd1706c4b3e61a3ae30a2df5f30c3260421c414861b022a665e4e270b757c07dbcb8c465c19ae1037dd41d4468ecf889a6ba",
+            "48656c6c6f20576f726c64",
+        ),
+        // empty
+        (
+            "18dd3f86b9367968567d7d5c2d660bdaed063368ca8b88d32a0e96025dff438628efceb7e40f4c22142430aa6409ea0879fa7e86339c4f8c1b36590459f712e003374961c7a7810ca4a24404e335a1df2f075ee836dfaa1b9b97e4151bea21445caf6dfbbf73fa103a535b3ae28353cb2a92666ddfbf96c69bbcac99dbd092db7c3ca3fffe7c18305e86bacc3b297fc2c232ad0bb6ea548b1fae39a1a57f3b8c8486cdd1d85112a8b2dcdeac1b01064ab9c99dd3f39526cf3651eee80b465a9129fa8e64a21f7c82bf182ed0eeae2dcee820ccf31de9cdd3df88b6fb0cdf405dfab2cc4ad156d92346625458075414ced0bcda727c29ff8bbcbd23080059dbcb",
+            "",
+        ),
+        // random
+        (
+            "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000003039",
+            "9d576a27cc625a8f71405b092b270e2b3701ef74076f98a6068a07e2810f077c7692a85cacc9ecbd5a5c24b1c0a033bd9712d74773ea8438c8bd4073e96d2b5ed9266fd6e94b472eff6ce8c354735ecda227b4fc844e8ef79930b7cb2388d04fb76cd92f768167d1e266eead068f049cac1126a880c8f1573b03def12dd79199fe84bceaf31827925bdbc16b68e4f1482a9dbb11013ea793dc9d2c4a45f8",
+        ),
+        // type1
+        (
+            "bd3b4f091d1103ab9903a96bc3a2fae2df054c44a68ecbcde938ca06c7a9a5823d37c2f953e394950b3cd5657b9645fd71c75e468179e0f78127f7e8c997996add5199315ecd0540196096fa1845717c597133e5616b7af228e24c405065d8fe6f5624b7ac31eed6dfa07e39ed2321ea3b0fd8908c4d0aa97fb7484a5effb1b33f3bce9fa8a712bb473b7c3c4cfe645e151481ac0c8fcef3d57c4199b6eb40d78baf4420db0f9a0173807e8e2f418c1def9174d26657277dba013f12ebc33da936f6218ba8bf4e492169623ed7f0d84bc4492b9b3fc35ccbbbaf2b199fe3299964f03b12d7c06a38434b593702e626adc1d40677d396f3ff6b551e1841835703",
+            "7beedce49176297096e5da67b9937be90a5f1b2b345445171af2b8ed334edd5e4cb9ffb95cb88c03e0b4cdd34d94e1d2968e068f29e096fe51ebf7859c8853e733822667640c656b751e048694e9886476ffaa53f28a6bfc834c9f6e37f32b5c251add1e7e1424f0341c27091deb95dd0f2ffc56fed771f586347b200d13c1e14ac2085c667a3a4ec7d68c274e",
+        ),
+        // shortps
+        (
+            "bd59133d6a9c71ab7fa5486e4207b50d61d4b7a7dccc40c8da0f5def3e4f2839f61804c2f1232a26d6bea0f01f3cc0dbbe32fc0760d05c2de3d4cd6eb4238463eecdd2bdb572849f9c49883147e06c2e60db98e9aef68e841113b7ebc148b9629186b7313c929d42e61b3b26217ae381ea71a49bc1d439700efc38463746685c1e14f4057b0ce48395b0663ac5f31c923bc0501f50e04c1ec3a299ecdc29de81825a658da4c0742eeffb1c26e115b2b6d7a82a50c637c0d63b5dcec7f5ffc1aae73dcfa672a5a2ffa7f2c14e2c89ea7abdb9f0d60f4c96ea884e3a26b1f11849ebe9a29eda5ac93ea15bb06c6235ca0570a8b301eeb626d5f5e24ff60563c9e1",
+            "1c3f09b5fedf52b12bf2dfab8f77ee93dd2ba7e8518a0e45843931cc91528f9f0a0f542c2b474b38f2bf13bf4c450671857af21f5e06ff98b7dd4d09f2f7d5991516e26fb9ca05f1e15f42af4a93ffd3580852953b90ccf71a093266ce4ae7227244fcbd646c4d368fd72241fa5eeb496ce7247dbc325635b5f72b3a13585eec769295404eef9e4bc2c3cfb045d5bd286a24dce9769bf60616deae9fdda17756a874b9599de2",
+        ),
+        // nozero
+        (
+            "469b8d0e904acc9a61824c3a7b7306c076c31dd57b571fefe282efabde4eac08a04b05eb035c974909d356c72d249b80c21457dd3e3957b119ec46ea457dfaf3a3a3be31b252d59eddc1afa191bdb157ba8bd156600de55ab3445028c59e626237763be21938f99eabf1498a948bc468694e8ab287fea63a00a48190f2ddb0a5a60e77af4fe75f054c298d75324adfbd08db960c1aa54c1f94b3603a8a504d90f4319ac300733553c21815b35a1d2bb651c203b2ca78ea6c73db6ddd3c8e5fc20d1c1da2389ab279c038270f0bf4216e2854a389aa445b28e3b1ae47fe455412b4df566982b854d000c2461abea57e309cb9b06eefbb20cd42408883ea9b8dab",
+            "5417b89791d0a2d7f6f5909a1406bc6b88187a0d2b5d8596c4c544a42b5e319eb7b9f9f2dea66830871367e87352ca6815444e882f15ce6d8c6769d278bd4c2ef3ed2975e9a90716a69f483f18e2e212d07da8a74cd26dabffbc7139e66f1f44a27bb97e77bf891ae7eda1cc77dded4b6b3c2d285cb127fb42d92c041a2069290d0e014d097185f807a46c9e4ab93be673b4e97af8243813d57f6b1645a75def",
+        ),
+        // lead
+        (
+            "2bd576fab3045d1f7051025b60df64f704bea7083872685755c697ba2307933ce3834a60e23e39928bf8e36560857f3acc8802f50a5aea800d7939fe83955a93303665129d24e380469d9094528f7ee32d9f0abdc3c193b59d72b0a9b108ad87088aefea7104f455cb3bfc33c7aa3a65fdb8d26f8d6ef07f6aa7d02e732161bb626e7332fe4e5e1814dfcb95f20ce72df5cd696f6cb41f184666d7247365b1773129ba8b69b7e59f713156ac56c763c636b94f7c5e7e463e0880ed81f0bb631bd12c7b2be1e689dc1b336ad2af9639161f95f4bc2420c3bf700bb3281125c2616fcbc3ec9f70e2608b0dfa52761169f57f71c538909ba26e666a41bcfe69bc78",
+            "ca187567a715e272e7f42c72bc7edefaf2c1b5c9c301e4e3ba5ae10eda9f9d200ccfc6fbaa6fb2eba6954c0ea290a0a0fa61e9b5de46cce9b46f9b86c9206c5096a5cd564c718b66ab0d863f541cc5e2f52079c5d5182c9bcbd8dd0de087116d986316251e2e6acd496161f1237b410bc12dbeff1a6f4f0154f857c0ad312b42db543269901d000a73ad805c3a0105e8e7",
+        ),
+    ];
+
+    // OpenSSL encodings of the test key. PKCS#1 RSAPrivateKey, PKCS#8, PKCS#1 RSAPublicKey
+    // and SubjectPublicKeyInfo
+    const PKCS1_PRIVATE: &str = "308204a50201000282010100ced7f571aeef4f01714e8f8ac19218ee4f63911feb32edbc87c6b64d903aca1f7162eaee656bfe929b7223e063118066b89b7e85ca2c952445c181e8e8f2dbaab35df4ddd699c74d34865dbce487c66a6c7b45685cf7f1adb87babfd90927262562dff62363e3d47ea9a01ccba49926d7fa3914a186c05ca0f58d455b892734ce995dc65355e5df38bb0d29cf8f6a059e8be7eeccb69462c6305ea772bd7634bacf7e24e2b8b5a3b9161a360a165cbccb117f73ab447ae4f461cd3652dbb5bffc8bf30cfd04496eddbc1566f43650d8f33a6120bda8b45dbe81568c9f509fc33c2e3e1a7f11a80ff21a8ec8a882bc22acef94e4875b5f0b09ca10bad5d92c2230203010001028201004a95264472ae898f5c9ad6395328e9e26c2fdbb165ae7c78a180ae55736f2f587d1b0cd91f2afca54275baa7fb30e15aa8b030c3ef2d7a2d95157c514e45db9d4685d778721c0af834bb4a6b84ffcc34ad9f195cd340f81d0acb552dde066046430adcee3ddedd5be424da074718a63ae36e48422e622d0839d66ee1e5e6b0b9e1b2f0f9c5622d324f1590cdbd636c37ba84070a345d7f08c870f925e02bb9106ba34f878f4a7cc14ab3d97e2bf82cfd9dc3e4458c683bf3244af831928dc88d6250cdfcf36e69ab1b4224ea061fada4081ae25df58aa89a854591bf9d9a7ec0ab081c6ab47f74e1a0f4d0e357b8b0b8b43549d60d8906d31575716b72161d2102818100f48418cc4ee600ba6c6517ebb4560a307e1a28629c8ac47e4afa6006b8094fbbfd9d662979e9d2f095bfa48b195991b0b043e0a6029941c1581578989542fce81e470503fd4b59570c6d612b51996aa40105be1bf30513c3b6448bcdcfd20debaa3c8313855dff34f215f55421c825f733ac011030b7103ba8b8d12b002a0ef102818100d88ee9bbbc6d4ed79936dd9d8f735cb3616fb446f561666e74533d51b447229b68e5a5922d2968bedf31aca72bc9c98861a94399558c0ea3186572635a80129e6ec3f5011e7a2d34217c047933438d4c7b9f193e90c1129c783b1abe4057fb81d9bf648779df392f4032c1e94b14037dfd048b92794967ce55abdb35e6028a5302818100daefc0a88768c87424046ce3bee85f5d463c33b70045fc64bccbecd97745d720139930d5a874a64c87393ea2d202f2418bc26b4f19fa91f705e0cbc78bb69c6f5654eef7c0800c31b64de5f87caaeae2888bd4ca7fd226fd51a194b0a709852f6391a528007dc6ba689d22babf38d04dc4f5994a1ee47b637fd2646fa54903710281810085b7c1257614fcb0eddf4dfa6340758621ed0aa12215315d94a06d658ceebbb4c091c4a9bd0f2ba96841d0fd2c719dea837b33cd7360570d681c0404cb35f7c93255f76be878c27ec50a41ece0b59e8312764bed310eb6c23d0f29d53dffd5c1f9454ffac34b0d915a8fbce2a3c448dfbba2d9d7e8e265a1a78ec57dc377e01702818100ab957c12acd4950b993eec0c0483789eededdd4f4899e3b3e0e66eaa1846d8b6f9350366f37e0973f9c9e31f9df01d6276b92a234cee7e521e5223a17297b7e88ec4af1eb4e3b01b6f291d264562c10c7d737fa85e5ec7ff270e3abace3f69d26fd20216e6c6455f88d8c61f8271f33960be986d4f100e8be692bed034e01be9";
+    const PKCS8_PRIVATE: &str = "308204bf020100300d06092a864886f70d0101010500048204a9308204a50201000282010100ced7f571aeef4f01714e8f8ac19218ee4f63911feb32edbc87c6b64d903aca1f7162eaee656bfe929b7223e063118066b89b7e85ca2c952445c181e8e8f2dbaab35df4ddd699c74d34865dbce487c66a6c7b45685cf7f1adb87babfd90927262562dff62363e3d47ea9a01ccba49926d7fa3914a186c05ca0f58d455b892734ce995dc65355e5df38bb0d29cf8f6a059e8be7eeccb69462c6305ea772bd7634bacf7e24e2b8b5a3b9161a360a165cbccb117f73ab447ae4f461cd3652dbb5bffc8bf30cfd04496eddbc1566f43650d8f33a6120bda8b45dbe81568c9f509fc33c2e3e1a7f11a80ff21a8ec8a882bc22acef94e4875b5f0b09ca10bad5d92c2230203010001028201004a95264472ae898f5c9ad6395328e9e26c2fdbb165ae7c78a180ae55736f2f587d1b0cd91f2afca54275baa7fb30e15aa8b030c3ef2d7a2d95157c514e45db9d4685d778721c0af834bb4a6b84ffcc34ad9f195cd340f81d0acb552dde066046430adcee3ddedd5be424da074718a63ae36e48422e622d0839d66ee1e5e6b0b9e1b2f0f9c5622d324f1590cdbd636c37ba84070a345d7f08c870f925e02bb9106ba34f878f4a7cc14ab3d97e2bf82cfd9dc3e4458c683bf3244af831928dc88d6250cdfcf36e69ab1b4224ea061fada4081ae25df58aa89a854591bf9d9a7ec0ab081c6ab47f74e1a0f4d0e357b8b0b8b43549d60d8906d31575716b72161d2102818100f48418cc4ee600ba6c6517ebb4560a307e1a28629c8ac47e4afa6006b8094fbbfd9d662979e9d2f095bfa48b195991b0b043e0a6029941c1581578989542fce81e470503fd4b59570c6d612b51996aa40105be1bf30513c3b6448bcdcfd20debaa3c8313855dff34f215f55421c825f733ac011030b7103ba8b8d12b002a0ef102818100d88ee9bbbc6d4ed79936dd9d8f735cb3616fb446f561666e74533d51b447229b68e5a5922d2968bedf31aca72bc9c98861a94399558c0ea3186572635a80129e6ec3f5011e7a2d34217c047933438d4c7b9f193e90c1129c783b1abe4057fb81d9bf648779df392f4032c1e94b14037dfd048b92794967ce55abdb35e6028a5302818100daefc0a88768c87424046ce3bee85f5d463c33b70045fc64bccbecd97745d720139930d5a874a64c87393ea2d202f2418bc26b4f19fa91f705e0cbc78bb69c6f5654eef7c0800c31b64de5f87caaeae2888bd4ca7fd226fd51a194b0a709852f6391a528007dc6ba689d22babf38d04dc4f5994a1ee47b637fd2646fa54903710281810085b7c1257614fcb0eddf4dfa6340758621ed0aa12215315d94a06d658ceebbb4c091c4a9bd0f2ba96841d0fd2c719dea837b33cd7360570d681c0404cb35f7c93255f76be878c27ec50a41ece0b59e8312764bed310eb6c23d0f29d53dffd5c1f9454ffac34b0d915a8fbce2a3c448dfbba2d9d7e8e265a1a78ec57dc377e01702818100ab957c12acd4950b993eec0c0483789eededdd4f4899e3b3e0e66eaa1846d8b6f9350366f37e0973f9c9e31f9df01d6276b92a234cee7e521e5223a17297b7e88ec4af1eb4e3b01b6f291d264562c10c7d737fa85e5ec7ff270e3abace3f69d26fd20216e6c6455f88d8c61f8271f33960be986d4f100e8be692bed034e01be9";
+    const PKCS1_PUBLIC: &str = "3082010a0282010100ced7f571aeef4f01714e8f8ac19218ee4f63911feb32edbc87c6b64d903aca1f7162eaee656bfe929b7223e063118066b89b7e85ca2c952445c181e8e8f2dbaab35df4ddd699c74d34865dbce487c66a6c7b45685cf7f1adb87babfd90927262562dff62363e3d47ea9a01ccba49926d7fa3914a186c05ca0f58d455b892734ce995dc65355e5df38bb0d29cf8f6a059e8be7eeccb69462c6305ea772bd7634bacf7e24e2b8b5a3b9161a360a165cbccb117f73ab447ae4f461cd3652dbb5bffc8bf30cfd04496eddbc1566f43650d8f33a6120bda8b45dbe81568c9f509fc33c2e3e1a7f11a80ff21a8ec8a882bc22acef94e4875b5f0b09ca10bad5d92c2230203010001";
+    const SPKI_PUBLIC: &str = "30820122300d06092a864886f70d01010105000382010f003082010a0282010100ced7f571aeef4f01714e8f8ac19218ee4f63911feb32edbc87c6b64d903aca1f7162eaee656bfe929b7223e063118066b89b7e85ca2c952445c181e8e8f2dbaab35df4ddd699c74d34865dbce487c66a6c7b45685cf7f1adb87babfd90927262562dff62363e3d47ea9a01ccba49926d7fa3914a186c05ca0f58d455b892734ce995dc65355e5df38bb0d29cf8f6a059e8be7eeccb69462c6305ea772bd7634bacf7e24e2b8b5a3b9161a360a165cbccb117f73ab447ae4f461cd3652dbb5bffc8bf30cfd04496eddbc1566f43650d8f33a6120bda8b45dbe81568c9f509fc33c2e3e1a7f11a80ff21a8ec8a882bc22acef94e4875b5f0b09ca10bad5d92c2230203010001";
+    const SPKI_PEM: &str = "-----BEGIN PUBLIC KEY-----\n\
+MIIBIjANBgkqhkiG9w0BAQEFAAOCAQ8AMIIBCgKCAQEAztf1ca7vTwFxTo+KwZIY\n\
+7k9jkR/rMu28h8a2TZA6yh9xYuruZWv+kptyI+BjEYBmuJt+hcoslSRFwYHo6PLb\n\
+qrNd9N3WmcdNNIZdvOSHxmpse0VoXPfxrbh7q/2QknJiVi3/YjY+PUfqmgHMukmS\n\
+bX+jkUoYbAXKD1jUVbiSc0zpldxlNV5d84uw0pz49qBZ6L5+7MtpRixjBep3K9dj\n\
+S6z34k4ri1o7kWGjYKFly8yxF/c6tEeuT0Yc02Utu1v/yL8wz9BElu3bwVZvQ2UN\n\
+jzOmEgvai0Xb6BVoyfUJ/DPC4+Gn8RqA/yGo7IqIK8IqzvlOSHW18LCcoQutXZLC\n\
+IwIDAQAB\n\
+-----END PUBLIC KEY-----\n\
+";
+
+    // PS256 signature on "PS256" made by OpenSSL with a random salt
+    const PSS_OPENSSL: &str = "bf44dd5ea674d51bf3a71b4df5299ead79dd924ca7e332a14affb979a6fcc12b7da030a83c35f5ed221698d60d2efcd0bd402a6f7c03173b65563cc3f3e2062f88979a501a1c65aaaab0e8e56df860a6a9d77584bc75d5b73b5f7133228cfa06c9b41e84e2118ce28b0bdde2940304e8728eb41d005064cd5406c022fa008e3b4a62894c2ad6c0b0ff98c3192df4d3b90b435f9dd6f676623376a6c846af46e846d9969ce7c60464f9817b5724948a72dfa2630b6ed13fb430281865d9c3f8a3330007d73d3b832a8e78285a242a0153a66439c78ffee70e4a96e715ba71f836e7235a751fd87cb7c0fe51cb72842bbddad88e5c523922a60b6965ca5165a344";
+
+    // OpenSSL keys of other sizes as SubjectPublicKeyInfo, with PKCS1-v1_5 and PSS (32 byte
+    // salt) SHA256 signatures on "Hello World"
+    const SIZE_VECTORS: [(usize, &str, &str, &str); 3] = [
+        (
+            1024,
+            "30819f300d06092a864886f70d010101050003818d0030818902818100bba9493e2b52f42a6704198c60182518a43267a220509b9c7dbee3a60ca9f558f1d41192ffab7b2375841c75aead0c62b49d23a67366106201178ba01315248976778556bfe5d7550e78f0fe0df9fe71b97ee1ba99ff83d27cfd2fec63cef5eebc524ae321e7aa6fd64f61a8fbdd413176b1379f1ffe64f5c2e0ffe6f848d9890203010001",
+            "41cd5db394ac94f010a344ee56bba6965440855fc99d98485bb3af5a00b5cca26a3cb0e60e2730b1a7716451ae22cb05e8b5e1606a43b0af9f835b499e4337cd5d7de65bcd21a916fd32786ee7f6a371cbad0f38973ef1ac57d79ee42e291135445133122641fc793056b8dd8250551394318c374cceb99c60c64fa1328efde3",
+            "728c2131559732fea5995e71d997c742b6eec30e1dcd7dd8c06ce257086b03c2601529e509787e16ba19a73af3690cdb053201b4aeebc1b04f9877ccce6d060e071ff336763dcdd01b1ecced6b96238591ee45b7c5466421ca6644ea2483590ce514e4ea8d9944fb63e0df4307153c82a392ad442b2acdc9027589e9997651e5",
+        ),
+        (
+            3072,
+            "308201a2300d06092a864886f70d01010105000382018f003082018a0282018100974c858cde962fae4c9a4c2f28ae0d270bba067847b1cad1a1a4d802eba37d03a1a4f12f68f8aa9f9664fb1e709d19ab55199469a320816e3281b00ed277dd00c5acf72ca28b11b3abedbffd32b3af93734bbeb260ed2422e9521e4629b5e95b02b04c47481223f83afebf6ee6473e39206f506738a56e44f299be26de46deef561b8533a9f5016d05e472b21593b854220939c49e9ccd64376c451d6f28705051fab929588b6368d91111172c77e2f363496cc57b48bfa42bf65c4a0138d9c89f5fd251ea8521ea89895e718c2c31612b68ac54863bd6b649258b4f2f518c8484bc0e938d36a2648673175e348e162ccf8138387613394950e6b014f308a4f8c2972bc5a1b2172793b6b7911c27b0b601846528d2eb80d9eeb5110228677800b2e04792667c1b9408fa23190fd6b32b2c8bac8dab1b3038064690e78b52c43e2abdfcf584a218b34805ab74b7db0f688e4655c78822939539e7d9fc495c9f13492776ec5c0519f9ec0f6c68054586365177aae3c320ae9a893a1cd365514fab0203010001",
+            "1dcacba1c91d7c31ec2057ad30035a107f997b66eeca9452084dae015a290c0acafdb5a618a47698404210477abf32286782e7b779d7027b1b1baf6eb345b9ced740caa847bdb28d6bc3610f2562fa872ca01b6855b31274cf6d44de7347e8ec797af386626578b94677a8c17b979d80a203c6859a73eeac60a72f025eabddbc0a36522b954c6c877077d90590226e8e437d6266aa3ac3c175469bab455039ac023d5668c9ce8fd6cb274ca3de355ffff6a18c5d8065a3a11b1767c2b52242bc4f0f29f7fa230bed8ce61189820b89ddce2761523a9f7d9a1499b24cbd9559a3e9efcbc18f5111fa1554d402396e196ee6aa0cce74e6cf5b1e1e43be4a9f4c814dcd674a1fcaf7648df25e3a950e8de6b222fea9c87158a253ffcb252ec438def921078a58d0c00876b07da064adccf15d2cf9b281a187399faed347782a0312b739a6f8403376742670e031cf4881c229349e34d783d7159d8c1e2342371880d95bf7bcc0fe46c3297ae9374681879347683f618762c507f26052f7903819cd",
+            "1540003dd6a54eebcf27965468680b3217616d8b259fcc0baaba370f992438b171fce5e38d9460c9abd1c35b1f4c2f9ef6ecba6fcd8630d6b3e369ecca9722b3a9518646c1251437e13ad79431ac074012b47bc7450bf5a31a6317880fbe25c8df500a919353c2e52bfca661dbd2c3f3c9d24de3f8362d5c1246bbd9fbdc8d64c8504c30a03ccfdbff83f6e2c2e7ba2d91b8983beb23abec3d593ff5ca3106711a0f6ed5471a6afc5d8c86f9796fe15b8f6d1709422d33d184402c47acef850e88e6e96b58934e49f482b75313c93cb225ec4b14323062a1be29783321047569d41387421c0600c42d4e2fa5159cc575130f350cf717224739d539ee2eb50376d9d25fddd746a179c317c95d609d9879e6dd16c08b004f5ab30624190eaff85cb1134e31818223688212046f7317f36fa8553e2c7a5348f667eabbd2136f64aea5726bc28d6dfe56a9760bc621d86606392675b3acf68b7d9d71ace25df11fb11bd62b2a72c6794def3e68e6ec89108e13baf63a59da4dffe8b417ced05ffead",
+        ),
+        (
+            8192,
+            "30820422300d06092a864886f70d01010105000382040f003082040a0282040100a20831cdb7d9ae74d8333752e028568900e2f8f51f909a9f5a8b5335fe6515ec6c13849e2a80d16685a54a4349fd4b0a6a5c0d93108ac8c1690dc7d7006077dc08ae6a816e8cfc679058adb39d41379c632b70f410ebc113296fa60da34e2d79ca5bc4843687b4537e10c71a61e9537437e0da174ca9180b1e120de32757e7871dbf68277a59c2e0757edbf9f159e0d782d42f8e869e6f8b6fe44aeeffeba66b7cd647af4a5e4f318c2ceecfb1f4d379860f6be70bb1e6c9c558bbd5b261ecbe1de2e8f2053f84da858e2c968d22ad9dd29e134e93a8020bec07fb496a9cfa78350588823430bd6821990819e7d2cf310b127f7aaab13711f72bb2405aba4c7b56caa89997676fbad88ea42b66d4c9a23e10a728e38837716609d33052b19cbd644300ec95ba979cb146780777b1a4209537d79f97a56c1d5a95a42e200cebcb2771579e105e996414c7035d635fdfb6ac1b04ccb3c65b5db23de5bf569f948753013a7b4486f25b7f6d3943189ab3d003fa979c1d0ab194f2c396264b22063bb859329872c4bc63835f5bfaca2d20ad6f8fd540790bd22c2b8bee7d581902e4fa34c0deab0c6e4bd022da908b64de38dcd813c854ed1a3f92ab79ec60445f03f688e7c652c0985e5f0b63875b21eabb30b0cab6715cc14a41d4ec4e0de366b592741f297149bf744c522d197b0678024c84fe476c0e813a912281a068dc5839b4f7379d66137f3f808c896b05ed71c9a48badc5ad7af6217b8f1b3f2276de121e006adfe9277dcbf3e0689c968c5e4527384283994a16df76918f60adf1e2de781723fdef36a36cd09eb65ca514349f03d230d5be7644a39f49bc337bcb0219b7fdd9da7f7b74ab8a5042809176ee31b3c7c38dfea730a0010faadfb83169498a7f00e75bec6cade868024e88d6e940fdf9027b59c115c088a97d740ba0fcf40595b8e717fa143a507d528910e796933c19c04cdf5532ed6b48d2678bc1474ff4dd036da95f252420286b4f44f53913dd1b5cd358f18b717974b1f4ad74ff52d370eb9f990ec72a36ffad96a9e5047552a27dc3817278ba4702702102986f99e99acfc869378984fb3bf82a0f939c8d45f2bce7b5decf05ebd183149efb26e0498022661a83852ce98c94761b7b13b91dc89ff16d56ca84b9919e1cae9921dbd06de676cb8c257ab08337ac863636fb1b78006afcf5e1f4cfd4135bfae9ff409ec5a0ea55a7ba1a3f4204ab2acddb7fcdc533a49b8043a934f8369fb33d74198c3f2f91884a221093985be8b4e7c3586ee998f9582de4a7309ecece9d673d260bd42800125ca36b166b5454c42c48707e68c5ab8b315950b85410c7887f5dd5beed3dd71a10d405fc8ba625138be9b6b19686d43602c4643611b15859512e2ea6ccf0ec05c1f9084aac2231a8d4697f46de648c6ed881c957cd21e28f4616750203010001",
+            "0122ea95ab5400bd81523c3dd84947919f2eb997cb4214f16246647978087f878f268738d673e563e68bed040d5e464e9279b5e9a61194b7204c3f665fc78584dfcbad6f4a8d3cf19f2bb612bbb933accabae67e95fadd01a42647641f7f84eb8295833deea9ba29951342e4b1393ee46d912c5e5741d2c5c1f95434121c2d74c7e00d2e43e5eec90896abef9023412740ddc67ed05c5fe5afd312191ae9efd24ba8832b4dc998db102c40145697ea9c0c0ee4511fd7993631684926093c3dc42fabc3e4ae7307d3e7e7b98fa8dd7d8d83083b2add5f77215f85eb4b3121b37cd3d2ee9425cfc62c1806786d4dbece3f5ae647f5224910791e17f7ae4613d1fd13194f4eede9dae71ce8b5dd25e180996dd76b01592b44ba27aa6eea933d472f59a63c36130915c8dc21c61a5ee3337fabbbc04c1207e9267e093fda778d8c76398cbabb415792b8a155a6fd212a1147353d60bb837aa772ae6c39721ee8ab22f101d9ad2971051e1fe0ea933bd195c0d79e05fc6a74313259706a01af32a66f3a51d254f14fb8ea1c34903d811534cd29d4ccfa94d94cc5b8a63dd061dde305395a1e6d102df435041212c4f008e8f0ee84739962c07292a48d01b30f776c4595ec279a0af485f2fddbf92e8e90b6596e197b6aaad30ca859f661f9a1edb609ab3b73bb0bef1625cda985c7798dab254c73efa6f4059a22654bb8071f4fbdad92587359ce0a7fe4e9fb3884ab11e25358ae596d4d402fb3bcf05974a56fbcd5ce847133cc72a0cb09fe88e8e6ae807c81d09367a5194ffdd019158f394fd0198da8138419cd4d176c20019a4d63101fe44d55c91ee3ae336c70762ff455fa0b7c3a498a1be37d0d9464772f7dbf2066e37ade504140010296b77b9e2fe105cddcb969b92913068148e8991e1e38859b60cdaae8b0a9088d049daace05bb1453e3d26726373d7f899b4c6fa393d8e3994c84303904c887a14aeea8fb15e2f1f55fc41399011af385514bc35b18249c0e67c47f5e9505458e77eb58f6e1b90ec6aab958bbe12f0dd1eca9ef3ae22a8c64505922fbfd977ebe569ea3b16214c56079906e6bf64d71abc966580ff3f1ea79cc834191c40d3bffc4cee9d695eeba5caa73123e335a260b441654c8a97e21c13b65df21491be4513b2785044e427bdea7c2c6bb1303fe458ea1fa2c607ae3cac93f1ac5d5db7c4830bf1ad5eb3929645e3e1221ceb787ca9882c282376443fe6c00d22626c12fe4c624efb242f3937d64989f7e72da1c1d1327d71e3b70c305387a1ccdb2cbd9d514ead08ac5e8d4617aa5fbf504787cc13311ccc2522138036eb5caa94a829bd4690188e2d8b84c2bf8e45a49939d2e0a9e39115cf091ddb2f72e6c2c7253b9e3ec6510b421d3273d198dad35531d468dfb516fbfc929e6d7f1ecec81c5b23e0979ed484310c15c46",
+            "39bc70264a816ac5ccee3cf5fdd7e9c28ba3764a8554d4edfcd1517026be0ce05da6c7b7d188f1df18aa2e2191be53abc8eb80a6824055bc76a510bf097706ea6820098081d66a6b21c31ee496e3d1c35b9125f4a794d4e0c043094247db68d84cd69e5e16ddf0dccdb0383be6d5481b1a9c85b3aa5cd1947c677712ba2a3830ec670865ad1d30313205a2bb71fd742b056da7d327a34cdad3ad7ee165f2be202d237a0ccef7ac754369cc9949ac3de5109224b3bf0fdfed73b0f1354990776e4f041f1a3f274f37f1a57f4470f215da0975b161911908fe582c72469b4326ae2353903fb35a0717f6a1d1710eb7994fbc583f2206cc35bd44fdc405498f7f03108203640cd589d4e336bd561e11f0b5e7e718bc722cfd4b03387005426d0a4ab71bda4fca432c551e4ae4c257e906a170379db029f2ab3a01cd0f0c522a541b09a39977ddcc51d1a5d166dc8c3b317e73dca63142694d7419e898794d1a97decad4faf8746981b8808017e9530adfff76504e6c00ee6c805a5bb1373f903bb3fa78276794ffb49ff65f4b92c9967690e56f29a869ec4d170186a4a86549d43923b40f32fd6d87a7a20c8cb0a1ccc8008da71f4bf1d55cffda0bdfecb9385eb8574d465b5a3a42e4dda84ecbfd6819abef6c3dd8364ed782e4c26d0b3260805852ddda93a7878d0f8cfc8a88af59a40c071b98fc4eec864840aad881907a4a6a1d198482b0d55cbc203b6869caefaba121899b82a74370a33bdc2ec3a99c39460ebfab8dc4c16432636d221b9143cf6ab7207e076b27fa940453a3b554fb982779a69135fe12faed2df0b8b8cd8e26d6fa3ea73b879d6be66834bf85df2613a5ba65f25d9dbeb76a747be742c4138227e767fea60396c847da2f237caba852578c63f1708e6bc1964ecb90eefa8046d483002dcebd5c463a40c5fb5962e0cb06f84237325a7659edcf791b43fb8ae5a6bf1139f82911f0c5aab3ab2a5dbf591aec6ff94fbc97b080f98b74a844f41de712da5af35843427afe2b1ae3d3635f0dc99184d46174624abd6d0b99b0b4cb0f69f8fb0512ba901624a78971761b9fe4f15757d1186b5663871d5d6e5fb72f2b81fb15eb3270225ad4ee671febfccd0171e53ab87c10c301a87f9dedf05cc48a43e3869e910eca778cfeb7d90923c39a3ca25d225fafef97342e6fb50b69a6a51bbd38d32efa4a612e8ae5f7c9421157ce6ea3a1215c07eb3bdf1fde47fa2f83cec54cc2e72d2a73b5aa6c02274b8e523b34b0fa53ac27ac6c2912b7f8bb5ec9627891ef37c479ba53543e558c030d20e8432d99400dba9f55ba3a319da1cc28b1061b7c25e49821bb1e779bda23a966f2b8f97c76191bbdc76e74f10292b3f932900d4907276809b9297ca61a1350e32636fb76792390e503399e52c82b0c89c47b3c6c04a81afd00574699ee220e49",
+        ),
+    ];
+
+    // PKCS#8 private key for the 3072-bit key above
+    const PKCS8_3072: &str = "308206fd020100300d06092a864886f70d0101010500048206e7308206e30201000282018100974c858cde962fae4c9a4c2f28ae0d270bba067847b1cad1a1a4d802eba37d03a1a4f12f68f8aa9f9664fb1e709d19ab55199469a320816e3281b00ed277dd00c5acf72ca28b11b3abedbffd32b3af93734bbeb260ed2422e9521e4629b5e95b02b04c47481223f83afebf6ee6473e39206f506738a56e44f299be26de46deef561b8533a9f5016d05e472b21593b854220939c49e9ccd64376c451d6f28705051fab929588b6368d91111172c77e2f363496cc57b48bfa42bf65c4a0138d9c89f5fd251ea8521ea89895e718c2c31612b68ac54863bd6b649258b4f2f518c8484bc0e938d36a2648673175e348e162ccf8138387613394950e6b014f308a4f8c2972bc5a1b2172793b6b7911c27b0b601846528d2eb80d9eeb5110228677800b2e04792667c1b9408fa23190fd6b32b2c8bac8dab1b3038064690e78b52c43e2abdfcf584a218b34805ab74b7db0f688e4655c78822939539e7d9fc495c9f13492776ec5c0519f9ec0f6c68054586365177aae3c320ae9a893a1cd365514fab02030100010282018014fd324b159743816c47bcd3e4a7d353f1b0da8995cb4360b634f67133f157e6c8bb92134aa9073a8bbef1db5f875bc7ef4eaa23c6d3e69611544e71454a083dbd83e8e7a7464b01603e38f3ce3481374a625d4fc50480e3daf70a68ca0bde15edc1e403162b7613f9fddc896cfc15d4ba518197a03dcaeb590b1d07000502e909a00312e4bc04239f67f97007c598bd0b2fb91f895ad00a316a1d631f9d9389d650459ef26aa68825677f0b20f4776689053c480a79faceb2a3edafa7e069787b24d5d20a04d539558388c7f4355a72ddcf1530bd8ef290db5180f14991fe9940f7a68d4ac85f1addccefd23d3326ff902b71f3df031e5eee2a58ab7663f1e8f4484aeab2b2c10f87cff43e637c4c265a3a90b0154b28981b38f547c2fe261bd179949378be6e67e6ae1afc4011ad4af9e862221900a90430048521c22b4484617ad5b40cfc1ab37385c77dd1fb1b836d4066771a7703157818ec4ecd9439a8f148729e229fa23071a41d3f11552419b8fce4e6e67eaf1c49d57e387c71ae510281c100d04fe792307c9918f430dfc5582cc9de42c4d30304b40d1ab9d33834c71443a8256ea2418846283e2cac4e61ed0a272097bf4efadcab9c40b21fec9d9fe2259388285c1d8c544494b25da7e99dfc625862381b7885fbe719116b73a0746b2ed6e264fcb629c1071735338c6630053ac7979fa3555bc9e193d6c2b584f1eae9755879dab70ec9fc59eb83a698101546dc0e1c47283b72913951ba28035e6a970e07edb50701a1e57aad79ecb76047d172bd36ba9395ca9600b63da64c071285330281c100b9ef5e247d1a913946d1546c250e5b3813c504c5474f264c7cae32195038ecc00a03fdfb4660b9e1f55a63874b088f6511b9c2bd7e06c25289a8e6fcd8c1a0e318d0a744f5e93a7ee1ecf18db0754bc1c4b3fc463ffc6a5db05d6a0a14016f8fd9c5948c2372ac504ebd7264bc3aa1008945fd3ae31aecc29f819c0a96b61110444c6f63c9924045a03d44599642b479e52213e4701db22bd633ef4eb2d3914196bf8986d22fa288f449d2d59e32fca9e7fef584cd4d60daac3ceee00bc91ba90281c04ef54a983deaac7929edf093b3c55e4af1d1fb0d94d5ce75c7e3c8d530747ce49a33a22ae6c578f1802b5b9680abad830c21c6850de9ba5347711455f6c95866e06033f23b43a1af1f20a2c9c94965d98c7929e87818b052ef28646e78523c31d524642984101768f39073723457771d3a5a97257ea239efb8a29afd26595d5e4e47f9e01329fa8df6d832e846885a108504b0f00e00720d60ee07c6a71e0ddd92a1c6513e8c281864f2e707e6e5b1fd24bd3ee13c10cae4c02f03fea35ba0c30281c10080a19bb48ca40391e76f4c0361037491f0035e10890bb5c8367d6085689d691456197aed0a3c427c92fbcc5d3dc8974369a6e495a9894d88425a0a2b245d984b63d07dd7d4745c9689001e0052f262cd93a29cc590e0b4494a88e5a24142d90660b732d45c75a9c6092340ef75d4edab6c4c722afa109778f1567e3849197de68e097f0a3eda2202c91f7998acbe4b130534b524557805da13df6c103ae0e8ddc24792022688f3dde17aed1401d4ee3d31158e8f2864febb696ae22b379c12b90281c00f129652a280b8842e69452a14dbfcb1c43a77c2ef1bc85ebf0b31f61432226204bdb530949f4af3a2d154c800415a589c7f72c4193c24eaf7e07ab36bc617b29c32948c626bff83f5effba08c3899b8c254a674588483f8996acf0a0d6914dfd73e0445a42d0cc2d9e153f0aceb65c926d9eea644320ab2e1f8fff4fcd601cc9c2a4772a7efff0f5cf4b26507255816d7b723796e4e9fad6023b9f99a33ed695b737214a8e6840e1632cdc68856800254495ac1f1b9ad8341364ab25fbeb43f";
+
+    // OpenSSL multi-prime keys as PKCS#1 RSAPrivateKey with otherPrimeInfos, of 3072 bits
+    // with 3 primes and 4096 bits with 4, and their PKCS1-v1_5 SHA256 signatures on
+    // "Hello World"
+    const MULTI_PRIME_VECTORS: [(usize, &str, &str); 2] = [
+        (
+            3072,
+            "308207350201010282018100aadcc17d1639fb2f1143134ea5128c489a1957fc6b45b04f3b00c8c36c7e7ffa3110dfe9f6eec53a2aec42984cc28efb3ecb9af7c0375db49322ae5987401cd840e8c2a81860c96e44147ce30b1765642bf473e4bbc20d213e395217156553b500e3659308407effc720a67e0e6f9d23417413242799cb36c31da1f258e1ec1d24083f8b156e4131df2436a3a593cec36b9099ef9d6b7e549f5601e82647b4ed5dc7761eaf03a3d3b3d5c81323bf78cacbad883a0cc9544795829c78dc9452921d2e869c29f34d2a26d1e163bd3ee7dcf9ef7d81b2712cdbeb6741f492c2029b1102b0c3aa44715161944569af43dfb114b68d7b7dcfb6ff826f8921bb2de9fc4c5705627e6880566a015e06184342b34b73672a2927c3ca91c4bbf9112b7c2267f161ca331ef4c5c027dc8c10466bc46f2a22db6c243c6a21128b00ebd1e6bc58fd1632ed52f2f317ffee7e842e2a85b1d6f9017183e9197affa60364f54199541fb246259fad913137b6496984b39df2c62d891062eecf199847e57b12b8d7020301000102820180284efe22a96cb2526f4b18b1f405852cd795e508fab1ad1f73f70266002173c5ad6fa2b38a7ad7f29a0fb4c243519ef3ace3d1c263096cc4ddf54f96756eed9f22e9068b2b7d21e82e8d7acd015fda9ea034026752d83bcabce83ef28d2ca36bcf32d652b29eac9760fdd5e9653475a8cc973ab0cbdadf7322e2c9c2e7af1cc9544021d4efe84aa7380bba488a10df0f3c9ac1cb2650800a8f5713e51309f71b425f08ec2476364a548523c6a2a9750c63b336e06db955c76f88fd3b460d998ae9127557bab36bf09433f4bb4d5fbe0751c85d2642dd332536d7502bbeb78b9454d40e79e7cc592ca5fb67f176b4cb6209703c98dc71edf4e42d96e7b33558e2deba64aaf1737170949a9e5cbbac02e69e11e582b55e52949038998bb468556886de62aeb678b6074f2475cfb74e1134e8859958d798ffd409c3dae182d8c82676924ec81b741b0c102278f54ee0b43ebc39457890b753da1579afd318d08511ebbdab43bcde31978295a5deeeab4e397f7a02c0918f661f92a6183805cd6f2102818100fd8768402726239a211972a7c8dd58b8444535ef53b45ebffab862ad09dc3a3d96d2053ad674926e9e8b6f57da87e538211b200f4d7ef5f3432ab21eca91bc8ea0ef1a1ab95d735c8c5c6ad5732d086826fa4cf337d7549f71233a81c1bcd8ce6a0c999678c843d1fb494c09795a188e1c1f339e98857165e5c4c5b2d4d1979502818100d2428c47a3b9f0ee392871886ac743bcdb1fb6188972774d99ec3d6b4ab2b03165bc6a3095c34e53be40185299c9bf447f5d372911edf12b90460ccf35ef2c0b36046089534e711521d68a1f812ffeed1fccd30e9f126654a3d05e71142a2d9cacc052ea197c8bcc20bb3b8f314f7414295cd0dbf873f2af72f10098e4cbe987028180690f71be13fb1409cce994bfbed72aeac12fc517f621ea99b7b62cfdf3f615146ca97f8d504d625900ce5b4796c175d57ab298e87e7ebc41207652f5bb9005093ddb977fb78ce83f53d3005a8fadfbabdd6b1ff43211c3798712c82df930decf3ad719d6671ae62fdfe811e0632f58b99b21baec5fdbfc8ff355456fc40d4eb90281806d31784ea01da99cddfc3df1dd9a3c7870c7e83697f2e5385cce8e1b1140f935be22fa76dedffde689b019b194026d1d7293835905e1af54b3695dafa2cfe1ecb491d35c9df1e7d254af692df1566eaad0cffb6fe57695c4310abe71354aae436687bec7aa92bebb34e86786f6d1cf73c50359c853204c1187bcd7558fc4c2330281805156c304ee40ccbc224009fc94813546e839724325a11a4188d0d6379b630b15fddf0aa0a9f3a4298c9bd5ac557e999f039555ec1268d2476b8878515320b8a87918af2f40381d33a10acef46e69addec11d746e87a2681e081e70f083c94728b758fa7b301b8937876f8703dc9c38a964dc695f67d3ee8e75aca86bdb04c88f3082018f3082018b02818100d20f35e704228ba60219c69b29bc046f06aea29a69a5ca8dbd4ccea8da0f025f9d397efe23c896faf64f85058d3422aecff3c436a117426048580846dad1de015513baa56b90ad4fc3a8ba4c1c728b68edc6922e3b4e0e6f494d7cd0488e48b2f10f26c02395fa9dc4aa719c92c965d45f3a220df81295c1f62a371f9e56d12d0281804f142ad6ad57d3facb0fa91500c1276a24b9d9bd07c8c25859cb7081609605eeb8e1cc4d206b836b370ef8a4fe63822b0d5e579fb945685796e48d391f67a28c1ddb2f88d6d5c06926df7e42e8f2b648ea3ab70d70861229130f78cd7083201b3d64d6d1be93d7a54d445d40f4819d3436254c6cb5d749499bc288bdfe7df0810281810084276111a7109c9dab130fd52d208a2c051cecb899340ee4f76bc72c632891e420730bc0f55137936203340dbd951c90da9cc6f863e0f0acb62be384f00f9d358272c74d1c16977b9e52e413ed0b56d33268afca866060bb4247d5161c67ccc71c639ff803b006c6d718733eda31a3048af56d70428319039aeac4afba8210c1",
+            "028c73230a688fb166011f7da6341f73c94ec195ab51b82824e151143ce532480afb1128981796069cf84cd5d77af8f4838e183f2b8f9be90873f9616b23fa55a078f6a8e163cc1718781e757b725f4005076893c02d41217e4de6b42f8f516d78d597cabcb07af0d810f0848692a8f83cc58ff8fb31b48244f0c38247e82ad04816909cfa89bd3bf34c4993402ee5a5f27b45c32ac2ce95c3ec038ec802add11efe7e2235795fad58fb13d842bf13d6b3f3425ff2b266869401ae45054ebc94680679984eaadaa1f968920745bca38cc8a91f31e20af7342adb5125e6f0bf7c308ced876aa84b40a88cea5812b889f3be352ac295bd3407f2431289e070e27205fcec361027522c7dee6fcefc28d3b743521c833b6ddb317469359219418617381eb23b77a7c793b7bdfff3a1a83b38aa078511eb786fd81742695c18ab2c06e92d3fded679fb92ff56782248d52c41b39e91e2c0e03169942e8c3dc97746a3b1d3ed5cf261857c192240161c8cb2a25aea2e1f1c0261c12fa4958a3b486886",
+        ),
+        (
+            4096,
+            "308209c60201010282020100946ddeb80fe897c1f73c9af25237202af0162dc7586d0b725415ad27d44d1037d1fecbe5328e3f1b26414996441ad6552191c589d6bd54038de36b88695222657158391237743ef5a195fd591d83f1b73b114322bdc56af544ec818289e02179d456760b2591532cf6b5de922c7c5333edb77b633dc5048988bb75eb1cd62cc2d877e26a1671d985e378024ba5ddcc89f5c3d387f3bc01bd0d68a44569f4c6ad8f2609e90e0b97ac6535ac17e6c4997561d5b05102b011e1c0db23aa89fa7676468e7b6e4942cf7f71d065128201db5d058f7ff55e24d357ba23af1d920f3f274d5696ed66039db6050f8f324fe70793d5e9a66b4bd3d1177e47f49e0aa98aea512a676c5d53551a994c0e005c81729d7c14ff91ac40e1db42d931e8daaab95c68f9e52834fd5c02449e0aedfb31532336c5fe0be2abe80e4c064cadf33e84e8b51d876090af0c336591ed5dc37f29ac0ba0da149e308ccdd9595a3e9e8361e6b4f8457ca5700f91785818606f5b5e4c8eba4465918b835340725b015ecc6d83928b3099598cd71a7c8f554a435f5bb10cce499b575e701919d7c36bda63afd65afd54aa1f0d4951cd3500307378efd3bf0693f6eef7846d0f93ad055646699760c0db61dc9ec9722aff0c38772a6f9f44b0f9b77c06514e25dec661f051a297ce3cd16b8695f299f72a7c2ef5afdc734b104bd8a292c26b143f5426de7da0e302030100010282020100924302295663d8c7661dee81c2ca5784783922bb414392f8022baa098986b6e88aa20dc1b8e3bb03c1daabeccb07b71504936498c366a5972b06633c74ac5f009f845418c35465861ec87272924726b56c1b4290c577f841e0bd3d91a8d397a939b849720a7efdfb5cd13295d00862e4d4e6ed4b89deceedc83311abb12acbc6442b6edd93728f93c208afae7a480e2fefe58d8f4537c3131d5c213481ec345787412ecc5d7db27924890009662e96b0b89d09d906073236fba46c0f681db96eafc519886a5d9f1edfaa12e74bb5bfb2c5fca94c72d53e6cb7f28c05007da2b9ec2894c400e5ae45e86e6f6e0d3180d880b46d7c697fa074ff0787354f9b421edf9529b7fb698def025ed38a203f12e457e98fcb3cd02b4688ffdb51cd55cf593d4ab981d117d144ce5cbe4e721d687c61639d0dc6ad32a9d43ed1d1c8a52125ac79dcb8a75191721f4c65c97c3b1e24ae82298b84321c8a79756bd0b879bbced804338de160f22f06d5a53e345648f08d25c6d3ef150fd16b82fdefe5821ea3ebccf47011e0e2060509be8e34422e0e0553c9da008a56de0a273b0c6bbe39754658f9c5a559b6cc7ff703687633529577e52d9f09e896ad7531f7732bbae8203aac6eb12c5c795f61918a074bf78a2bd559a3f8042623ddc5ebfe0be6bf4d27ceb9112f5039f37ec5befb253199d9084a96f9e7db626397c068afd34bd0600102818100ff94ed73b7f4fe51c27b162a6925244ea1db6036da84a1cda43715fd715d95edb3f2411844025f49fb3645a996d833b52cf78895de2d3b9ad4f5a5068d2d4b86025762ee5497c91cfa4c781ae16db6f641b1cdcb87e3cfe642b20bf5e404282beed1680eefdd40d4ee6a995625c78fa082eda4a0751e17417a8e1b9846cf1f8d02818100caf222f0e956c44d5a8e8bf50104fc4df0a929e276044aa54dca293e60bcc350d6b58b5175dce034fe9593675519bedcb417e4af0a86f1037d0fe289520b415281ec3686ec9834755a6108ddaed581f4d37b35397cc6003a8c29a99374f6d39e09fbb415b27f94b7cb613dd877b0c8a72199fd34ce12a69685d9a3f48b8d7bfd028180774b8e621b1551b7dbed13f58bca785df392b0202a730a0b30813dd4649be825617ecbff858f0de73c48a2cf7bc742ab6867c9eb1a1f46d90859f8434c963dd0393b8cc3eddc435531478c300fd4e437a1865aa68bb13c13204276eeb9156fa2c20020700869df9f0401e1bca239b9c2941a546349075897899ad72a86b29ae90281804ff182021468b17b2728efc8db55f36d6057c2c00a6d365a5782432ce73e7451ebc227d736ee83035d25dea2ddcafe7c421507684c654b9b6c304db47a3524ce338fb10b05380fb20aa01eb5f4a0f64852e63dd91f657fc19064f8ffb51b65d0e36ddb4a90bde0ff658630ae8cb77ed317516984db884d470e02e5aaf72e0ca102818100e04cc85f403a3ad19769524d1dc4946f5b8267b369a954a9b8eefc65d370997a453307024daa128bb61b40b0bf16da2a6cbd545dc1a704abda764d1cf086f381ffe474448cafb76799b5ac23686b0457ef5c5097bf1bc7eb65e4cb89252c054fe9353583a1b38ddd37e2d659c7d9b447c65657b69b9e85c2ae062ba71989c3863082031e3082018b02818100e0ebe688c7017e7b2d3bbc59e80d64ccf601039d57dcd0a482d7ad3feb052bba3684cf17ebf5ddc02827a2250f3c09a0627a46c0d018732bdc9a656b8f368d9770d8e496797828de79384227571d4111c100d91d7830164f66154c017486d2895497eec4e9bd753ed3f39dc9dfe6ceaf487b71f01d241223627a00beac639c5b02818100b313bf29b9607c26703e365952c38f350ccc83e73b7448fe225abbcabf410faea4079ba70d2c6550bfe7c6564149e8d43473ec6f497f9dfb171a2a93b7a3949a35b3ca53f004766da30fe2c3d5bd29f88bb534281d7013e34580c1520b47e8ca847aaf154290a08fd44b0b9f16507ad0de21c2f68d0d120ff030f8f4d6e63c2f02818043c944bace6848820f32f067d69cb54bdcac63e9ce9542b09977aa20b136d494b221f02a8516c4debf3fc617b9235e17ffad5a83689d39dd5e978623c9f84d44063a9d3549ac98d8eb31f7426fda6b6cec070c6d96b8739881b1c8f54e0a5a67808d31a37bf6b3a32edd3de599cb083f619936efdd9416168ca00ae894462c653082018b02818100d5737619648b69473e90203bb8e4118556d9541c2cff7a91dc647cfe1d78b16c8f34e7d028b1a0cd8b0dec0651224a44ceb5d6063121687c236fd294f3a747a9c64c030a85724dc1ecf57e293506a8e64e21a6f3d248983d5bba0a920c955f771adf87bec11a5f95a2d1ecf22ba7d5504b5884c60facbef435c93feeab6984c102818100b81cc4a4787e6bcbc1222e3b756a7bd668a8430d9e9eab49bf318a42e286e57b3f37223101b8dd7c0dd26f60af810550be8047e0c5660caaef221820d4ddd8498fbaa6f0df544dcfd52597b1d18432119d1917d9bdeb522db2be113a12d2912aba2a4efb71b2def9c21c92e5fc53e302444b51134a17f609cd8f0eaf3e7960c102818039328318ec151637b43af2f8611549b4e1cc3749a67720b722c5e973b98cb997d6aebe9339e4400ff4168fc6ebef2f09ae9a139c0e540ba70dd034ef22904c0009757c226f82df6cfb4376d143d67a154317d068804781d2d1f9166c3ebc44e6acdbae969c99734d5612cf49946f6d9fb6ad86bd4e88a9bae6eb6538fea22421",
+            "35b621a8c7ad03c67c834e0a8582392a8b1944bbb5a2b33180b32aa385196b527f84b7fb90a14aa297c5f18f39887a00d572fca8c8d37cffed357ae5f846ac24148fb655b66d99b49acf0b5f40677961b8a8727a2b351d2685258c4937ad477f67005505db2a3bd4adfa612df83dc00fd92bd0585322ea32a4f20ba1f7578c613bfc75483a377bb92a2c4a18b96c66cd46340afe8ecb2bef9282447a0250b8de191c6243b38333eaa041f069f3fdd899fa8c1759d3c0b2ad8a5f5134e5ee3b37338a87141d78e26fb733b7deaca2cc22aee5d97080625497228eb3edeec30edfe7b07e7812476b1190c1c175c219921634400f17ac9c383f97c2546feb912059675d9fd7bf638c6564ab3cdce0dccd10451d3f7aa79ad9b87cb7622292186a97581c4a8d16b06e96ad0a37cf13ee80253f9e45752de7498f42e1950852a14143588b7026688a7e98ac8e68cc9c99adfa03ec7c218c3e0aaaaccfebbe16e09cc2f7ddb7a2554457c58623e7e77967ea833dc143acc2001a1c60b8842275b88dfa42ef063e79a44984593ac9e4c53dd504443d27785f5e6a3de7e1994ac8f01f63015ef96f4d16bbafd4e217c47a0dbe6a0183961451278fdfe1a2a812be3496d4c4620160dbcdcf6230f5aa715986bbc301e6015a012cf9ff54719c5e46b56fa740499d581edaf3dae15906d318b634c6789f944716d19a785e8dc1c20e479024",
+        ),
+    ];
+
+    fn test_keys() -> (RsaPrivateKey, RsaPublicKey) {
+        let n = hex::decode(N).unwrap();
+        let d = hex::decode(D).unwrap();
+        let p = hex::decode(P).unwrap();
+        let q = hex::decode(Q).unwrap();
+        let prv = private_key_from_components(&n, 65537, &d, &p, &q).unwrap();
+        let pbc = public_key_from_components(&n, 65537).unwrap();
+        (prv, pbc)
+    }
+
+    #[test]
+    fn test_pss() {
+        let (prv, pbc) = test_keys();
+        let mut rng = create_rng();
+        let mut w = vec![0; 256];
+        let mut sig = vec![0; 256];
+        assert_eq!(pbc.n.nbits(), 2048);
+
+        for v in PSS_VECTORS.iter() {
+            let salt = hex::decode(v.3).unwrap();
+            assert!(pss_encode(v.0, v.1, v.2, &salt, 2047, &mut w));
+            assert!(decrypt(&prv, &mut rng, &w, &mut sig));
+            assert_eq!(hex::encode(&sig[..]), v.4);
+            assert!(verify_pss(&pbc, v.0, v.1, salt.len(), v.2, &sig));
+            assert!(!verify_pss(&pbc, v.0, v.1, salt.len() + 1, v.2, &sig));
+            let other = if v.1 == SHA256 { SHA512 } else { SHA256 };
+            assert!(!verify_pss(&pbc, v.0, other, salt.len(), v.2, &sig));
+            assert!(!verify_pss(&pbc, v.0, v.1, salt.len(), b"other", &sig));
+        }
+
+        let s = hex::decode(PSS_OPENSSL).unwrap();
+        assert!(verify_pss(&pbc, SHA256, SHA256, 32, b"PS256", &s));
+
+        for slen in [0, 20, 32, 64].iter() {
+            assert!(sign_pss(
+                &prv, SHA256, SHA256, *slen, &mut rng, b"abc", &mut sig
+            ));
+            assert!(verify_pss(&pbc, SHA256, SHA256, *slen, b"abc", &sig));
+            sig[128] ^= 1;
+            assert!(!verify_pss(&pbc, SHA256, SHA256, *slen, b"abc", &sig));
+        }
+
+        assert!(sign_pss(
+            &prv, SHA3_256, SHA3_256, 32, &mut rng, b"abc", &mut sig
+        ));
+        assert!(verify_pss(&pbc, SHA3_256, SHA3_256, 32, b"abc", &sig));
+        assert!(!verify_pss(&pbc, SHA256, SHA256, 32, b"abc", &sig));
+
+        // Salt too long for the modulus, unknown hash, out of range or short signature
+        assert!(!sign_pss(
+            &prv,
+            SHA512,
+            SHA512,
+            256 - 65,
+            &mut rng,
+            b"abc",
+            &mut sig
+        ));
+        assert!(!sign_pss(&prv, 20, 20, 20, &mut rng, b"abc", &mut sig));
+        let n = hex::decode(N).unwrap();
+        assert!(!verify_pss(&pbc, SHA256, SHA256, 32, b"abc", &n));
+        assert!(!verify_pss(&pbc, SHA256, SHA256, 32, b"PS256", &s[1..]));
+    }
+
+    #[test]
+    fn test_pkcs1v15() {
+        let mut rng = create_rng();
+        let (prv, pbc) = test_keys();
+        let mut sig = vec![0; 256];
+        let m = b"Hello World";
+
+        for v in PKCS1_VECTORS.iter() {
+            assert!(sign_pkcs1v15(&prv, v.0, &mut rng, m, &mut sig));
+            assert_eq!(hex::encode(&sig[..]), v.1);
+            assert!(verify_pkcs1v15(&pbc, v.0, m, &sig));
+            assert!(!verify_pkcs1v15(&pbc, v.0, b"Hello Worle", &sig));
+            let other = if v.0 == SHA256 { SHA3_256 } else { SHA256 };
+            assert!(!verify_pkcs1v15(&pbc, other, m, &sig));
+        }
+        assert!(!sign_pkcs1v15(&prv, 20, &mut rng, m, &mut sig));
+
+        let s = hex::decode(PKCS1_NO_NULL).unwrap();
+        assert!(verify_pkcs1v15(&pbc, SHA256, m, &s));
+        for v in PKCS1_BAD.iter() {
+            let s = hex::decode(v).unwrap();
+            assert!(!verify_pkcs1v15(&pbc, SHA256, m, &s));
+        }
+        let n = hex::decode(N).unwrap();
+        assert!(!verify_pkcs1v15(&pbc, SHA256, m, &n));
+    }
+
+    #[test]
+    fn test_pkcs1v15_encrypt() {
+        let (prv, pbc) = test_keys();
+        let mut rng = create_rng();
+        let mut c = vec![0; 256];
+        let mut m = vec![0; 256];
+
+        for v in DECRYPT_VECTORS.iter() {
+            let c = hex::decode(v.0).unwrap();
+            let len = decrypt_pkcs1v15(&prv, &mut rng, &c, &mut m).unwrap();
+            assert_eq!(hex::encode(&m[0..len]), v.1);
+        }
+
+        for len in [0, 1, 100, 256 - 11].iter() {
+            let msg: Vec<u8> = (0..*len).map(|i| i as u8).collect();
+            assert!(encrypt_pkcs1v15(&pbc, &mut rng, &msg, &mut c));
+            assert_eq!(decrypt_pkcs1v15(&prv, &mut rng, &c, &mut m), Some(*len));
+            assert_eq!(&m[0..*len], &msg[..]);
+
+            // A short output buffer gets a synthetic message, not an error
+            if *len > 0 {
+                let got = decrypt_pkcs1v15(&prv, &mut rng, &c, &mut m[0..len - 1]).unwrap();
+                assert!(got < *len);
+            }
+        }
+        assert!(!encrypt_pkcs1v15(&pbc, &mut rng, &[0; 246], &mut c));
+
+        let n = hex::decode(N).unwrap();
+        assert_eq!(decrypt_pkcs1v15(&prv, &mut rng, &n, &mut m), None);
+        assert_eq!(decrypt_pkcs1v15(&prv, &mut rng, &n[1..], &mut m), None);
+    }
+
+    #[test]
+    fn test_key_formats() {
+        let mut rng = create_rng();
+        let (prv, pbc) = test_keys();
+        let mut sig = vec![0; 256];
+
+        assert_eq!(hex::encode(private_key_to_der(&prv)), PKCS1_PRIVATE);
+        assert_eq!(hex::encode(private_key_to_pkcs8_der(&prv)), PKCS8_PRIVATE);
+        assert_eq!(hex::encode(public_key_to_pkcs1_der(&pbc)), PKCS1_PUBLIC);
+        assert_eq!(hex::encode(public_key_to_der(&pbc)), SPKI_PUBLIC);
+        assert_eq!(public_key_to_pem(&pbc), SPKI_PEM);
+
+        let k = private_key_from_der(&hex::decode(PKCS1_PRIVATE).unwrap()).unwrap();
+        assert_eq!(hex::encode(private_key_to_der(&k)), PKCS1_PRIVATE);
+        let k = private_key_from_pkcs8_der(&hex::decode(PKCS8_PRIVATE).unwrap()).unwrap();
+        assert_eq!(hex::encode(private_key_to_der(&k)), PKCS1_PRIVATE);
+        assert!(sign_pkcs1v15(&k, SHA256, &mut rng, b"abc", &mut sig));
+        let k = public_key_from_pkcs1_der(&hex::decode(PKCS1_PUBLIC).unwrap()).unwrap();
+        assert!(verify_pkcs1v15(&k, SHA256, b"abc", &sig));
+        let k = public_key_from_der(&hex::decode(SPKI_PUBLIC).unwrap()).unwrap();
+        assert!(verify_pkcs1v15(&k, SHA256, b"abc", &sig));
+
+        let k = private_key_from_pem(&private_key_to_pem(&prv)).unwrap();
+        assert_eq!(hex::encode(private_key_to_der(&k)), PKCS1_PRIVATE);
+        let k = private_key_from_pkcs8_pem(&private_key_to_pkcs8_pem(&prv)).unwrap();
+        assert_eq!(hex::encode(private_key_to_der(&k)), PKCS1_PRIVATE);
+        let k = public_key_from_pkcs1_pem(&public_key_to_pkcs1_pem(&pbc)).unwrap();
+        assert_eq!(hex::encode(public_key_to_der(&k)), SPKI_PUBLIC);
+        let k = public_key_from_pem(SPKI_PEM).unwrap();
+        assert_eq!(hex::encode(public_key_to_der(&k)), SPKI_PUBLIC);
+        assert!(public_key_from_pem(&private_key_to_pem(&prv)).is_none());
+
+        // Inconsistent CRT values, other versions, trailing data and truncation
+        let der = hex::decode(PKCS1_PRIVATE).unwrap();
+        let mut bad = der.clone();
+        let len = bad.len();
+        bad[len - 1] ^= 1;
+        assert!(private_key_from_der(&bad).is_none());
+        let mut bad = der.clone();
+        bad[6] = 1;
+        assert!(private_key_from_der(&bad).is_none());
+        let mut bad = der.clone();
+        bad.push(0);
+        assert!(private_key_from_der(&bad).is_none());
+        assert!(private_key_from_der(&der[0..len - 1]).is_none());
+        assert!(public_key_from_der(&hex::decode(PKCS1_PUBLIC).unwrap()).is_none());
+        assert!(private_key_from_pkcs8_der(&der).is_none());
+
+        // Components
+        let n = hex::decode(N).unwrap();
+        let d = hex::decode(D).unwrap();
+        let p = hex::decode(P).unwrap();
+        let q = hex::decode(Q).unwrap();
+        let k = private_key_from_components(&n, 65537, &d, &q, &p).unwrap();
+        assert!(sign_pkcs1v15(&k, SHA256, &mut rng, b"abc", &mut sig));
+        assert!(verify_pkcs1v15(&pbc, SHA256, b"abc", &sig));
+        assert!(private_key_from_components(&n, 3, &d, &p, &q).is_none());
+        assert!(private_key_from_components(&n, 65537, &n, &p, &q).is_none());
+        assert!(private_key_from_components(&n, 65537, &d, &p, &p).is_none());
+        let mut bad = d.clone();
+        let len = bad.len();
+        bad[len - 1] ^= 2;
+        assert!(private_key_from_components(&n, 65537, &bad, &p, &q).is_none());
+
+        assert!(public_key_from_components(&n, 1).is_none());
+        assert!(public_key_from_components(&n, 65536).is_none());
+        let mut k = Vec::new();
+        der::encode_integer(&n, &mut k);
+        der::encode_integer(&[0x80, 0, 0, 1], &mut k);
+        let mut big_e = Vec::new();
+        der::encode_sequence(&k, &mut big_e);
+        assert!(public_key_from_pkcs1_der(&big_e).is_none());
+        assert!(public_key_from_components(&n[129..], 65537).is_none());
+        let mut even = n.clone();
+        even[255] ^= 1;
+        assert!(public_key_from_components(&even, 65537).is_none());
+    }
+
+    #[test]
+    fn test_sizes() {
+        let mut rng = create_rng();
+        let m = b"Hello World";
+
+        for v in SIZE_VECTORS.iter() {
+            let pbc = public_key_from_der(&hex::decode(v.1).unwrap()).unwrap();
+            assert_eq!(public_key_bytes(&pbc), v.0 / 8);
+            let s = hex::decode(v.2).unwrap();
+            assert!(verify_pkcs1v15(&pbc, SHA256, m, &s));
+            assert!(!verify_pkcs1v15(&pbc, SHA256, b"Hello Worle", &s));
+            let s = hex::decode(v.3).unwrap();
+            assert!(verify_pss(&pbc, SHA256, SHA256, 32, m, &s));
+            assert!(!verify_pss(&pbc, SHA256, SHA256, 32, b"Hello Worle", &s));
+        }
+
+        let prv = private_key_from_pkcs8_der(&hex::decode(PKCS8_3072).unwrap()).unwrap();
+        let pbc = public_key_from_der(&hex::decode(SIZE_VECTORS[1].1).unwrap()).unwrap();
+        let mut sig = vec![0; 384];
+        assert_eq!(private_key_bytes(&prv), 384);
+        assert!(sign_pkcs1v15(&prv, SHA256, &mut rng, m, &mut sig));
+        assert_eq!(hex::encode(&sig), SIZE_VECTORS[1].2);
+        assert!(sign_pss(&prv, SHA256, SHA256, 32, &mut rng, m, &mut sig));
+        assert!(verify_pss(&pbc, SHA256, SHA256, 32, m, &sig));
+        assert!(!sign_pkcs1v15(&prv, SHA256, &mut rng, m, &mut sig[0..256]));
+
+        // A signature from the 2048-bit key does not verify under the 3072-bit one
+        let (prv2048, _) = test_keys();
+        let mut sig2048 = vec![0; 256];
+        assert!(sign_pkcs1v15(&prv2048, SHA256, &mut rng, m, &mut sig2048));
+        assert!(!verify_pkcs1v15(&pbc, SHA256, m, &sig2048));
+
+        let mut c = vec![0; 384];
+        let mut out = vec![0; 384];
+        assert!(encrypt_pkcs1v15(&pbc, &mut rng, m, &mut c));
+        assert_eq!(
+            decrypt_pkcs1v15(&prv, &mut rng, &c, &mut out),
+            Some(m.len())
+        );
+        assert_eq!(&out[0..m.len()], m);
+
+        assert!(new_key_pair(&mut rng, 1024, 65537).is_none());
+        assert!(new_key_pair(&mut rng, 8192, 65537).is_none());
+        assert!(new_key_pair(&mut rng, 3071, 65537).is_none());
+        assert!(new_key_pair(&mut rng, 3072, 65536).is_none());
+    }
+
+    #[test]
+    fn test_countermeasures() {
+        let mut rng = create_rng();
+        let (mut prv, pbc) = test_keys();
+        let m = b"Hello World";
+        let mut sig = vec![0; 256];
+        let mut out = vec![0; 256];
+
+        assert!(sign_pkcs1v15(&prv, SHA256, &mut rng, m, &mut sig));
+        assert_eq!(hex::encode(&sig), PKCS1_VECTORS[0].1);
+        private_key_countermeasures(&mut prv, false, false);
+        assert!(sign_pkcs1v15(&prv, SHA256, &mut rng, m, &mut sig));
+        assert_eq!(hex::encode(&sig), PKCS1_VECTORS[0].1);
+        private_key_countermeasures(&mut prv, true, false);
+        assert!(sign_pkcs1v15(&prv, SHA256, &mut rng, m, &mut sig));
+        assert_eq!(hex::encode(&sig), PKCS1_VECTORS[0].1);
+
+        // A fault in one half of the CRT gives a bad signature, which would reveal a
+        // factor of the modulus. The check withholds it
+        prv.dp.inc(2);
+        assert!(sign_pkcs1v15(&prv, SHA256, &mut rng, m, &mut sig));
+        assert!(!verify_pkcs1v15(&pbc, SHA256, m, &sig));
+        private_key_countermeasures(&mut prv, true, true);
+        assert!(!sign_pkcs1v15(&prv, SHA256, &mut rng, m, &mut sig));
+        assert!(sig.iter().all(|&x| x == 0));
+        let c = hex::decode(DECRYPT_VECTORS[0].0).unwrap();
+        assert!(!decrypt(&prv, &mut rng, &c, &mut out));
+        assert_eq!(decrypt_pkcs1v15(&prv, &mut rng, &c, &mut out), None);
+    }
+
+    #[test]
+    fn test_multi_prime() {
+        let mut rng = create_rng();
+        let m = b"Hello World";
+
+        for v in MULTI_PRIME_VECTORS.iter() {
+            let der = hex::decode(v.1).unwrap();
+            let prv = private_key_from_der(&der).unwrap();
+            let pbc = private_key_to_public(&prv);
+            let k = v.0 / 8;
+            let mut sig = vec![0; k];
+            assert_eq!(private_key_bytes(&prv), k);
+            assert_eq!(private_key_to_der(&prv), der);
+            assert!(sign_pkcs1v15(&prv, SHA256, &mut rng, m, &mut sig));
+            assert_eq!(hex::encode(&sig), v.2);
+            assert!(verify_pkcs1v15(&pbc, SHA256, m, &sig));
+
+            let mut c = vec![0; k];
+            let mut out = vec![0; k];
+            assert!(encrypt_pkcs1v15(&pbc, &mut rng, m, &mut c));
+            assert_eq!(
+                decrypt_pkcs1v15(&prv, &mut rng, &c, &mut out),
+                Some(m.len())
+            );
+            assert_eq!(&out[0..m.len()], m);
+
+            let p8 = private_key_to_pkcs8_der(&prv);
+            let prv = private_key_from_pkcs8_der(&p8).unwrap();
+            assert_eq!(private_key_to_der(&prv), der);
+
+            // A wrong coefficient of the last prime, or a two prime version
+            let mut bad = der.clone();
+            let len = bad.len();
+            bad[len - 1] ^= 1;
+            assert!(private_key_from_der(&bad).is_none());
+            let mut bad = der.clone();
+            bad[6] = 0;
+            assert!(private_key_from_der(&bad).is_none());
+        }
+
+        let (mut prv, pbc) = new_multi_prime_key_pair(&mut rng, 3072, 3, 65537).unwrap();
+        let mut sig = vec![0; 384];
+        assert_eq!(public_key_bytes(&pbc), 384);
+        assert!(sign_pss(&prv, SHA256, SHA256, 32, &mut rng, m, &mut sig));
+        assert!(verify_pss(&pbc, SHA256, SHA256, 32, m, &sig));
+        let der = private_key_to_der(&prv);
+        assert_eq!(
+            private_key_to_der(&private_key_from_der(&der).unwrap()),
+            der
+        );
+
+        // The fault check covers the extra primes too
+        prv.others[0].d.inc(2);
+        assert!(!sign_pss(&prv, SHA256, SHA256, 32, &mut rng, m, &mut sig));
+
+        assert!(new_multi_prime_key_pair(&mut rng, 2048, 4, 65537).is_none());
+        assert!(new_multi_prime_key_pair(&mut rng, 4096, 5, 65537).is_none());
+        assert!(new_multi_prime_key_pair(&mut rng, 3072, 1, 65537).is_none());
+    }
 }
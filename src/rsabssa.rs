@@ -0,0 +1,327 @@
+/*
+Licensed to the Apache Software Foundation (ASF) under one
+or more contributor license agreements.  See the NOTICE file
+distributed with this work for additional information
+regarding copyright ownership.  The ASF licenses this file
+to you under the Apache License, Version 2.0 (the
+"License"); you may not use this file except in compliance
+with the License.  You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing,
+software distributed under the License is distributed on an
+"AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+KIND, either express or implied.  See the License for the
+specific language governing permissions and limitations
+under the License.
+*/
+
+/* RSA Blind Signatures - RSABSSA (RFC 9474). The client prepares and blinds a message,
+the server signs the blinded message with rsa::decrypt, and the client unblinds the
+result into an RSASSA-PSS signature that verifies with rsa::verify_pss */
+
+use super::big;
+use super::ff::FF;
+use super::rsa;
+use super::rsa::{RsaPrivateKey, RsaPublicKey};
+
+use crate::rand::RAND;
+
+/* Suites. All hash with SHA-384 and use MGF1 with SHA-384. PSS has a 48 byte salt,
+PSSZERO none. Randomized suites prefix the message with 32 random bytes */
+pub const SHA384_PSS_RANDOMIZED: usize = 0;
+pub const SHA384_PSSZERO_RANDOMIZED: usize = 1;
+pub const SHA384_PSS_DETERMINISTIC: usize = 2;
+pub const SHA384_PSSZERO_DETERMINISTIC: usize = 3;
+
+const PREFIX_LEN: usize = 32;
+
+/* salt length of SUITE */
+fn salt_len(suite: usize) -> Option<usize> {
+    match suite {
+        SHA384_PSS_RANDOMIZED | SHA384_PSS_DETERMINISTIC => Some(48),
+        SHA384_PSSZERO_RANDOMIZED | SHA384_PSSZERO_DETERMINISTIC => Some(0),
+        _ => None,
+    }
+}
+
+/* Prepare (RFC 9474 4.1). The message to be blinded, signed and verified */
+pub fn prepare(suite: usize, rng: &mut RAND, msg: &[u8]) -> Vec<u8> {
+    let mut m = Vec::with_capacity(PREFIX_LEN + msg.len());
+    if suite == SHA384_PSS_RANDOMIZED || suite == SHA384_PSSZERO_RANDOMIZED {
+        for _ in 0..PREFIX_LEN {
+            m.push(rng.getbyte());
+        }
+    }
+    m.extend_from_slice(msg);
+    m
+}
+
+/* Blind (RFC 9474 4.2) with the given salt and blinding factor R, 0 < R < n */
+fn blind_with(
+    pbc: &RsaPublicKey,
+    msg: &[u8],
+    salt: &[u8],
+    r: &[u8],
+    blinded: &mut [u8],
+    inv: &mut [u8],
+) -> bool {
+    let (nb, e) = rsa::public_key_components(pbc);
+    let k = nb.len();
+    let len = (k + big::MODBYTES - 1) / big::MODBYTES;
+    let n = FF::from_be_bytes(&nb, len);
+    let embits = 8 * k - nb[0].leading_zeros() as usize - 1;
+
+    let mut em = vec![0; k];
+    if !rsa::pss_encode(rsa::SHA384, rsa::SHA384, msg, salt, embits, &mut em) {
+        return false;
+    }
+    let m = FF::from_be_bytes(&em, len);
+    let mut one = FF::new_int(len);
+    one.one();
+    if FF::comp(&FF::gcd(&m, &n), &one) != 0 {
+        return false;
+    }
+
+    let mut x = FF::from_be_bytes(r, len);
+    let mut ri = FF::from_be_bytes(r, len);
+    ri.invmodp(&n);
+    x.power(e, &n);
+    let z = FF::mul(&m, &x).dmod(&n);
+    z.to_be_bytes(blinded);
+    ri.to_be_bytes(inv);
+    true
+}
+
+/* Blind (RFC 9474 4.2). MSG is the output of prepare. BLINDED, sent to the signer, and
+INV, kept for finalize, are the length of the modulus */
+pub fn blind(
+    suite: usize,
+    pbc: &RsaPublicKey,
+    rng: &mut RAND,
+    msg: &[u8],
+    blinded: &mut [u8],
+    inv: &mut [u8],
+) -> bool {
+    let (nb, _) = rsa::public_key_components(pbc);
+    let k = nb.len();
+    let len = (k + big::MODBYTES - 1) / big::MODBYTES;
+    let slen = match salt_len(suite) {
+        Some(s) => s,
+        None => return false,
+    };
+    if blinded.len() != k || inv.len() != k {
+        return false;
+    }
+
+    let salt: Vec<u8> = (0..slen).map(|_| rng.getbyte()).collect();
+    let n = FF::from_be_bytes(&nb, len);
+    let mut r = FF::new_int(len);
+    while r.iszilch() {
+        r.randomnum(&n, rng);
+    }
+    let mut rb = vec![0; k];
+    r.to_be_bytes(&mut rb);
+    blind_with(pbc, msg, &salt, &rb, blinded, inv)
+}
+
+/* BlindSign (RFC 9474 4.3). BLINDED and BSIG are the length of the modulus. RNG blinds the
+exponentiation, and the result is checked by re-encryption in rsa::decrypt, unless
+private_key_countermeasures turned these off */
+pub fn blind_sign(prv: &RsaPrivateKey, rng: &mut RAND, blinded: &[u8], bsig: &mut [u8]) -> bool {
+    let (nb, _) = rsa::public_key_components(&rsa::private_key_to_public(prv));
+    if blinded.len() != nb.len() || bsig.len() != nb.len() || blinded >= &nb[..] {
+        return false;
+    }
+    rsa::decrypt(prv, rng, blinded, bsig)
+}
+
+/* Finalize (RFC 9474 4.4). SIG, the length of the modulus, is the unblinded signature on
+MSG, which is verified before it is returned */
+pub fn finalize(
+    suite: usize,
+    pbc: &RsaPublicKey,
+    msg: &[u8],
+    bsig: &[u8],
+    inv: &[u8],
+    sig: &mut [u8],
+) -> bool {
+    let (nb, _) = rsa::public_key_components(pbc);
+    let k = nb.len();
+    let len = (k + big::MODBYTES - 1) / big::MODBYTES;
+    if bsig.len() != k || inv.len() != k || sig.len() != k || bsig >= &nb[..] {
+        return false;
+    }
+    let n = FF::from_be_bytes(&nb, len);
+    let z = FF::from_be_bytes(bsig, len);
+    let ri = FF::from_be_bytes(inv, len);
+    let s = FF::mul(&z, &ri).dmod(&n);
+    s.to_be_bytes(sig);
+    if !verify(suite, pbc, msg, sig) {
+        sig.iter_mut().for_each(|x| *x = 0);
+        return false;
+    }
+    true
+}
+
+/* verification of the signature SIG on the prepared message MSG */
+pub fn verify(suite: usize, pbc: &RsaPublicKey, msg: &[u8], sig: &[u8]) -> bool {
+    match salt_len(suite) {
+        Some(slen) => rsa::verify_pss(pbc, rsa::SHA384, rsa::SHA384, slen, msg, sig),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::create_rng;
+
+    // RFC 9474 Appendix A key and the vectors of A.3 and A.4. A.1 and A.2, for the
+    // randomized suites, are not included
+    struct Vector {
+        suite: usize,
+        prepared: &'static str,
+        salt: &'static str,
+        encoded: &'static str,
+        inv: &'static str,
+        blinded: &'static str,
+        bsig: &'static str,
+        sig: &'static str,
+    }
+
+    const N: &str = "aec4d69addc70b990ea66a5e70603b6fee27aafebd08f2d94cbe1250c556e047a928d635c3f45ee9b66d1bc628a03bac9b7c3f416fe20dabea8f3d7b4bbf7f963be335d2328d67e6c13ee4a8f955e05a3283720d3e1f139c38e43e0338ad058a9495c53377fc35be64d208f89b4aa721bf7f7d3fef837be2a80e0f8adf0bcd1eec5bb040443a2b2792fdca522a7472aed74f31a1ebe1eebc1f408660a0543dfe2a850f106a617ec6685573702eaaa21a5640a5dcaf9b74e397fa3af18a2f1b7c03ba91a6336158de420d63188ee143866ee415735d155b7c2d854d795b7bc236cffd71542df34234221a0413e142d8c61355cc44d45bda94204974557ac2704cd8b593f035a5724b1adf442e78c542cd4414fce6f1298182fb6d8e53cef1adfd2e90e1e4deec52999bdc6c29144e8d52a125232c8c6d75c706ea3cc06841c7bda33568c63a6c03817f722b50fcf898237d788a4400869e44d90a3020923dc646388abcc914315215fcd1bae11b1c751fd52443aac8f601087d8d42737c18a3fa11ecd4131ecae017ae0a14acfc4ef85b83c19fed33cfd1cd629da2c4c09e222b398e18d822f77bb378dea3cb360b605e5aa58b20edc29d000a66bd177c682a17e7eb12a63ef7c2e4183e0d898f3d6bf567ba8ae84f84f1d23bf8b8e261c3729e2fa6d07b832e07cddd1d14f55325c6f924267957121902dc19b3b32948bdead5";
+    const D: &str = "0d43242aefe1fb2c13fbc66e20b678c4336d20b1808c558b6e62ad16a287077180b177e1f01b12f9c6cd6c52630257ccef26a45135a990928773f3bd2fc01a313f1dac97a51cec71cb1fd7efc7adffdeb05f1fb04812c924ed7f4a8269925dad88bd7dcfbc4ef01020ebfc60cb3e04c54f981fdbd273e69a8a58b8ceb7c2d83fbcbd6f784d052201b88a9848186f2a45c0d2826870733e6fd9aa46983e0a6e82e35ca20a439c5ee7b502a9062e1066493bdadf8b49eb30d9558ed85abc7afb29b3c9bc644199654a4676681af4babcea4e6f71fe4565c9c1b85d9985b84ec1abf1a820a9bbebee0df1398aae2c85ab580a9f13e7743afd3108eb32100b870648fa6bc17e8abac4d3c99246b1f0ea9f7f93a5dd5458c56d9f3f81ff2216b3c3680a13591673c43194d8e6fc93fc1e37ce2986bd628ac48088bc723d8fbe293861ca7a9f4a73e9fa63b1b6d0074f5dea2a624c5249ff3ad811b6255b299d6bc5451ba7477f19c5a0db690c3e6476398b1483d10314afd38bbaf6e2fbdbcd62c3ca9797a420ca6034ec0a83360a3ee2adf4b9d4ba29731d131b099a38d6a23cc463db754603211260e99d19affc902c915d7854554aabf608e3ac52c19b8aa26ae042249b17b2d29669b5c859103ee53ef9bdc73ba3c6b537d5c34b6d8f034671d7f3a8a6966cc4543df223565343154140fd7391c7e7be03e241f4ecfeb877a051";
+    const P: &str = "e1f4d7a34802e27c7392a3cea32a262a34dc3691bd87f3f310dc75673488930559c120fd0410194fb8a0da55bd0b81227e843fdca6692ae80e5a5d414116d4803fca7d8c30eaaae57e44a1816ebb5c5b0606c536246c7f11985d731684150b63c9a3ad9e41b04c0b5b27cb188a692c84696b742a80d3cd00ab891f2457443dadfeba6d6daf108602be26d7071803c67105a5426838e6889d77e8474b29244cefaf418e381b312048b457d73419213063c60ee7b0d81820165864fef93523c9635c22210956e53a8d96322493ffc58d845368e2416e078e5bcb5d2fd68ae6acfa54f9627c42e84a9d3f2774017e32ebca06308a12ecc290c7cd1156dcccfb2311";
+    const Q: &str = "c601a9caea66dc3835827b539db9df6f6f5ae77244692780cd334a006ab353c806426b60718c05245650821d39445d3ab591ed10a7339f15d83fe13f6a3dfb20b9452c6a9b42eaa62a68c970df3cadb2139f804ad8223d56108dfde30ba7d367e9b0a7a80c4fdba2fd9dde6661fc73fc2947569d2029f2870fc02d8325acf28c9afa19ecf962daa7916e21afad09eb62fe9f1cf91b77dc879b7974b490d3ebd2e95426057f35d0a3c9f45f79ac727ab81a519a8b9285932d9b2e5ccd347e59f3f32ad9ca359115e7da008ab7406707bd0e8e185a5ed8758b5ba266e8828f8d863ae133846304a2936ad7bc7c9803879d2fc4a28e69291d73dbd799f8bc238385";
+    const MSG: &str = "8f3dc6fb8c4a02f4d6352edf0907822c1210a9b32f9bdda4c45a698c80023aa6b59f8cfec5fdbb36331372ebefedae7d";
+    const VECTORS: [Vector; 2] = [
+        Vector {
+            suite: SHA384_PSS_DETERMINISTIC,
+            prepared: MSG,
+            salt: "051722b35f458781397c3a671a7d3bd3096503940e4c4f1aaa269d60300ce449555cd7340100df9d46944c5356825abf",
+            encoded: "6e0c464d9c2f9fbc147b43570fc4f238e0d0b38870b3addcf7a4217df912ccef17a7f629aa850f63a063925f312d61d6437be954b45025e8282f9c0b1131bc8ff19a8a928d859b37113db1064f92a27f64761c181c1e1f9b251ae5a2f8a4047573b67a270584e089beadcb13e7c82337797119712e9b849ff56e04385d144d3ca9d8d92bf78adb20b5bbeb3685f17038ec6afade3ef354429c51c687b45a7018ee3a6966b3af15c9ba8f40e6461ba0a17ef5a799672ad882bab02b518f9da7c1a962945c2e9b0f02f29b31b9cdf3e633f9d9d2a22e96e1de28e25241ca7dd04147112f578973403e0f4fd80865965475d22294f065e17a1c4a201de93bd14223e6b1b999fd548f2f759f52db71964528b6f15b9c2d7811f2a0a35d534b8216301c47f4f04f412cae142b48c4cdff78bc54df690fd43142d750c671dd8e2e938e6a440b2f825b6dbb3e19f1d7a3c0150428a47948037c322365b7fe6fe57ac88d8f80889e9ff38177bad8c8d8d98db42908b389cb59692a58ce275aa15acb032ca951b3e0a3404b7f33f655b7c7d83a2f8d1b6bbff49d5fcedf2e030e80881aa436db27a5c0dea13f32e7d460dbf01240c2320c2bb5b3225b17145c72d61d47c8f84d1e19417ebd8ce3638a82d395cc6f7050b6209d9283dc7b93fecc04f3f9e7f566829ac41568ef799480c733c09759aa9734e2013d7640dc6151018ea902bc",
+            inv: "80682c48982407b489d53d1261b19ec8627d02b8cda5336750b8cee332ae260de57b02d72609c1e0e9f28e2040fc65b6f02d56dbd6aa9af8fde656f70495dfb723ba01173d4707a12fddac628ca29f3e32340bd8f7ddb557cf819f6b01e445ad96f874ba235584ee71f6581f62d4f43bf03f910f6510deb85e8ef06c7f09d9794a008be7ff2529f0ebb69decef646387dc767b74939265fec0223aa6d84d2a8a1cc912d5ca25b4e144ab8f6ba054b54910176d5737a2cff011da431bd5f2a0d2d66b9e70b39f4b050e45c0d9c16f02deda9ddf2d00f3e4b01037d7029cd49c2d46a8e1fc2c0c17520af1f4b5e25ba396afc4cd60c494a4c426448b35b49635b337cfb08e7c22a39b256dd032c00adddafb51a627f99a0e1704170ac1f1912e49d9db10ec04c19c58f420212973e0cb329524223a6aa56c7937c5dffdb5d966b6cd4cbc26f3201dd25c80960a1a111b32947bb78973d269fac7f5186530930ed19f68507540eed9e1bab8b00f00d8ca09b3f099aae46180e04e3584bd7ca054df18a1504b89d1d1675d0966c4ae1407be325cdf623cf13ff13e4a28b594d59e3eadbadf6136eee7a59d6a444c9eb4e2198e8a974f27a39eb63af2c9af3870488b8adaad444674f512133ad80b9220e09158521614f1faadfe8505ef57b7df6813048603f0dd04f4280177a11380fbfc861dbcbd7418d62155248dad5fdec0991f",
+            blinded: "10c166c6a711e81c46f45b18e5873cc4f494f003180dd7f115585d871a28930259654fe28a54dab319cc5011204c8373b50a57b0fdc7a678bd74c523259dfe4fd5ea9f52f170e19dfa332930ad1609fc8a00902d725cfe50685c95e5b2968c9a2828a21207fcf393d15f849769e2af34ac4259d91dfd98c3a707c509e1af55647efaa31290ddf48e0133b798562af5eabd327270ac2fb6c594734ce339a14ea4fe1b9a2f81c0bc230ca523bda17ff42a377266bc2778a274c0ae5ec5a8cbbe364fcf0d2403f7ee178d77ff28b67a20c7ceec009182dbcaa9bc99b51ebbf13b7d542be337172c6474f2cd3561219fe0dfa3fb207cff89632091ab841cf38d8aa88af6891539f263adb8eac6402c41b6ebd72984e43666e537f5f5fe27b2b5aa114957e9a580730308a5f5a9c63a1eb599f093ab401d0c6003a451931b6d124180305705845060ebba6b0036154fcef3e5e9f9e4b87e8f084542fd1dd67e7782a5585150181c01eb6d90cb95883837384a5b91dbb606f266059ecc51b5acbaa280e45cfd2eec8cc1cdb1b7211c8e14805ba683f9b78824b2eb005bc8a7d7179a36c152cb87c8219e5569bba911bb32a1b923ca83de0e03fb10fba75d85c55907dda5a2606bf918b056c3808ba496a4d95532212040a5f44f37e1097f26dc27b98a51837daa78f23e532156296b64352669c94a8a855acf30533d8e0594ace7c442",
+            bsig: "364f6a40dbfbc3bbb257943337eeff791a0f290898a6791283bba581d9eac90a6376a837241f5f73a78a5c6746e1306ba3adab6067c32ff69115734ce014d354e2f259d4cbfb890244fd451a497fe6ecf9aa90d19a2d441162f7eaa7ce3fc4e89fd4e76b7ae585be2a2c0fd6fb246b8ac8d58bcb585634e30c9168a434786fe5e0b74bfe8187b47ac091aa571ffea0a864cb906d0e28c77a00e8cd8f6aba4317a8cc7bf32ce566bd1ef80c64de041728abe087bee6cadd0b7062bde5ceef308a23bd1ccc154fd0c3a26110df6193464fc0d24ee189aea8979d722170ba945fdcce9b1b4b63349980f3a92dc2e5418c54d38a862916926b3f9ca270a8cf40dfb9772bfbdd9a3e0e0892369c18249211ba857f35963d0e05d8da98f1aa0c6bba58f47487b8f663e395091275f82941830b050b260e4767ce2fa903e75ff8970c98bfb3a08d6db91ab1746c86420ee2e909bf681cac173697135983c3594b2def673736220452fde4ddec867d40ff42dd3da36c84e3e52508b891a00f50b4f62d112edb3b6b6cc3dbd546ba10f36b03f06c0d82aeec3b25e127af545fac28e1613a0517a6095ad18a98ab79f68801e05c175e15bae21f821e80c80ab4fdec6fb34ca315e194502b8f3dcf7892b511aee45060e3994cd15e003861bc7220a2babd7b40eda03382548a34a7110f9b1779bf3ef6011361611e6bc5c0dc851e1509de1a",
+            sig: "6fef8bf9bc182cd8cf7ce45c7dcf0e6f3e518ae48f06f3c670c649ac737a8b8119a34d51641785be151a697ed7825fdfece82865123445eab03eb4bb91cecf4d6951738495f8481151b62de869658573df4e50a95c17c31b52e154ae26a04067d5ecdc1592c287550bb982a5bb9c30fd53a768cee6baabb3d483e9f1e2da954c7f4cf492fe3944d2fe456c1ecaf0840369e33fb4010e6b44bb1d721840513524d8e9a3519f40d1b81ae34fb7a31ee6b7ed641cb16c2ac999004c2191de0201457523f5a4700dd649267d9286f5c1d193f1454c9f868a57816bf5ff76c838a2eeb616a3fc9976f65d4371deecfbab29362caebdff69c635fe5a2113da4d4d8c24f0b16a0584fa05e80e607c5d9a2f765f1f069f8d4da21f27c2a3b5c984b4ab24899bef46c6d9323df4862fe51ce300fca40fb539c3bb7fe2dcc9409e425f2d3b95e70e9c49c5feb6ecc9d43442c33d50003ee936845892fb8be475647da9a080f5bc7f8a716590b3745c2209fe05b17992830ce15f32c7b22cde755c8a2fe50bd814a0434130b807dc1b7218d4e85342d70695a5d7f29306f25623ad1e8aa08ef71b54b8ee447b5f64e73d09bdd6c3b7ca224058d7c67cc7551e9241688ada12d859cb7646fbd3ed8b34312f3b49d69802f0eaa11bc4211c2f7a29cd5c01ed01a39001c5856fab36228f5ee2f2e1110811872fe7c865c42ed59029c706195d52",
+        },
+        Vector {
+            suite: SHA384_PSSZERO_DETERMINISTIC,
+            prepared: MSG,
+            salt: "",
+            encoded: "159499b90471b496c2639ec482e99feaba525c0420c565d17dc60c1bb1f47703f04436cceaa8f69811e1bf8546fa971226c9e71421b32b571ed5ea0e032269d4219b4404316eb17a58f277634aeed394b7f3888153b5bb163e40807e605dafdd1789dd473b0846bdcb6524417bc3a35366fab4261708c0e4b4beba07a1a64bbccb4b1ac215d1350a50a501e8e96612028b535ad731abf1f117ee07d07a4de9cef3d70f5845ba84c29d5d92c6e66a1f9489a5f527b846825360fd6e90f40ed041c682e489f3acde984a3ea580181418c1d15017af2657bc4b70485cdc0f1ebc3693e0d70a5d01f37ff640993fa071274fb9ee44e0c24dcb58ffa21a9a6540d87f24379beaafcc3b4bd42c45ec6820e03738ce98bea11c71685f31db63429fab8658bdb816f1ecccb1888f2402de0bd2f0f9646decdcad4c11b41428eec1ed25f2a86d43bb04f95726bfbd98ea34ca091b7adbabd0e28f17fa0345b89542d23c3530554987508a23641bd4f9e52962b0bee3ac9ffe005322d26a39941c5847774300411c69635f96903e8d593530908bd92a4fa6a2d52f88073a647a4b3894b7e4ebb80699e60227397bfa93f41b1c97e107b632f68e70409372ead2f072c11cf99be4486fcbf763dde28ee156db26cd358a69fcb79644f1f2fcc166f41a4c80f5851ee08be051f14b601418d6e56e61733b9b210c6bef17edac121a754d19b9bc",
+            inv: "80682c48982407b489d53d1261b19ec8627d02b8cda5336750b8cee332ae260de57b02d72609c1e0e9f28e2040fc65b6f02d56dbd6aa9af8fde656f70495dfb723ba01173d4707a12fddac628ca29f3e32340bd8f7ddb557cf819f6b01e445ad96f874ba235584ee71f6581f62d4f43bf03f910f6510deb85e8ef06c7f09d9794a008be7ff2529f0ebb69decef646387dc767b74939265fec0223aa6d84d2a8a1cc912d5ca25b4e144ab8f6ba054b54910176d5737a2cff011da431bd5f2a0d2d66b9e70b39f4b050e45c0d9c16f02deda9ddf2d00f3e4b01037d7029cd49c2d46a8e1fc2c0c17520af1f4b5e25ba396afc4cd60c494a4c426448b35b49635b337cfb08e7c22a39b256dd032c00adddafb51a627f99a0e1704170ac1f1912e49d9db10ec04c19c58f420212973e0cb329524223a6aa56c7937c5dffdb5d966b6cd4cbc26f3201dd25c80960a1a111b32947bb78973d269fac7f5186530930ed19f68507540eed9e1bab8b00f00d8ca09b3f099aae46180e04e3584bd7ca054df18a1504b89d1d1675d0966c4ae1407be325cdf623cf13ff13e4a28b594d59e3eadbadf6136eee7a59d6a444c9eb4e2198e8a974f27a39eb63af2c9af3870488b8adaad444674f512133ad80b9220e09158521614f1faadfe8505ef57b7df6813048603f0dd04f4280177a11380fbfc861dbcbd7418d62155248dad5fdec0991f",
+            blinded: "982790826556aabe6004467671a864397eea3b95740e9a11c8b80b99ee0cf4dbc50af860bda81b601a2eceaa6943ef104f13325ad0be2e37f42030b3120e87cfee8cfe59cde1acfb25485a43275ebe777292e2518181ae531e596f988ff16f458daa5a42408939cbe60e7271391a21657276427d195bee6a20054101d4ceb892ecdea402ea1a866acf0e451a3336f07e7589330d96c3883fd5bc1a829a715b618b74a86b2a898764246ad081d4c9f1edb8ab5077e315fde2417ec2dd33cad93e120340b49be89c18a63e62c6bb289037283d3bf18608be11ee4c823c710b0c6b89235fed3f03a7b96ddd25a8f54f20dac37ce8905093ad8e066810f354fb1773236e3d3788ba755de2c9bce8d340078bb1831ddc7314a5018673427ced65cb356281aae08b5e6636f3eb2417e09d6ae476a9abcc410bc8c90813d0740e39ae75efae4c02eed49dbb7aa51258bb71197445d17a6029bf566ba6b36282173af2c42e9b9631366f22eb6a19ef1d92bd3ce0631d3a7fb3288195b0ba380a3828d5411cefd5eba83e52198c001ac9946a333a33d89d4d235fc833239d59837f04eaf065e9563659b00c7624a6263b727d8f2c07959ba2bb592e7ff251b8f09c85995fd2e4474e743586576b518230986b6076b762ae77088a37e4bffd2ef41ae68d6d4e79205290b4f76c42ef039638c41cdc6fe8af9b429c0dee45b2942e3861da2a",
+            bsig: "362ef369f9b8c1487e285514702a7cd6fe03e4a2fb854881f3d3f986b7742a0c9bfab6562a6cd5ed71c574af67d7e77e71b33420c08ebb0ff37886b858297f9562fc366066c6d8e77bad1918b04756ba03f5c385d44f06759daf1b7a38b2a64248dee95d0e3886c8afa1f74afd8ac3c56520d0f3fd206df8e0d257312756803b09a79d0cc38112592c3aec32de5a9bc3284c5a0a2d0808b102deafa5cc60f04e3d71c0284cba04f17f88aa8e07d5544fe0265807d515877f79d30ed26d522b9d9c56597647b0dbca5a69d6418f8d1b51481723f272c2a3d48f6f4fd6beeac3576c3edb00e8779964548aeab8e004c7c4f8ef9cb6e680e2d2d49792004bb3e6974fa48f241a361ca449c02bd4c0ad4e66252c55e656f16049908efe59acbafa1171895dfac64d909808e5420469d622c7253ec1de7522b41634d383bf8786bf881cbf1561627f1e62b2d93300ec30ec0f5f0ab32036fce068bc76b0b0c6452079537f8d7f8dcee4b42bbf2d9ad7499d3835cd93cfc7e8ebea3554ab5241e181e5d73241b7bebf0a281b63594a35f4993e2b416d60db966b58b648cfcba2c4bee4c2830aae4a70ff55012480298f549c13b1b2684277bca12f592471b8a99285174f1c0ebb38fc80e74a10b3f02ec3e6682ba873f7ff0e1e79718b470927c74ed754d4f7c3d9a55e22246e829cdb5a1c6fb2a0a6c896df303063c918bcf5eb0017",
+            sig: "4454b6983ff01cb28545329f394936efa42ed231e15efbc025fdaca00277acf0c8e00e3d8b0ecebd35b057b8ebfc14e1a7097368a4abd20b555894ccef3d1b9528c6bcbda6b95376bef230d0f1feff0c1064c62c60a7ae7431d1fdfa43a81eed9235e363e1ffa0b2797aba6aad6082fcd285e14fc8b71de6b9c87cb4059c7dc1e96ae1e63795a1e9af86b9073d1d848aef3eca8a03421bcd116572456b53bcfd4dabb0a9691f1fabda3ed0ce357aee2cfee5b1a0eb226f69716d4e011d96eede5e38a9acb531a64336a0d5b0bae3ab085b658692579a376740ff6ce69e89b06f360520b864e33d82d029c808248a19e18e31f0ecd16fac5cd4870f8d3ebc1c32c718124152dc905672ab0b7af48bf7d1ac1ff7b9c742549c91275ab105458ae37621757add83482bbcf779e777bbd61126e93686635d4766aedf5103cf7978f3856ccac9e28d21a850dbb03c811128616d315d717be1c2b6254f8509acae862042c034530329ce15ca2e2f6b1f5fd59272746e3918c748c0eb810bf76884fa10fcf749326bbfaa5ba285a0186a22e4f628dbf178d3bb5dc7e165ca73f6a55ecc14c4f5a26c4693ce5da032264cbec319b12ddb9787d0efa4fcf1e5ccee35ad85ecd453182df9ed735893f830b570faae8be0f6fe2e571a4e0d927cba4debd368d3b4fca33ec6251897a137cf75474a32ac8256df5e5ffa518b88b43fb6f63a24",
+        },
+    ];
+
+    fn keys() -> (RsaPrivateKey, RsaPublicKey) {
+        let n = hex::decode(N).unwrap();
+        let d = hex::decode(D).unwrap();
+        let p = hex::decode(P).unwrap();
+        let q = hex::decode(Q).unwrap();
+        let prv = rsa::private_key_from_components(&n, 65537, &d, &p, &q).unwrap();
+        let pbc = rsa::public_key_from_components(&n, 65537).unwrap();
+        (prv, pbc)
+    }
+
+    #[test]
+    fn test_rsabssa_vectors() {
+        let mut rng = create_rng();
+        let (prv, pbc) = keys();
+        let msg = hex::decode(MSG).unwrap();
+        let n = FF::from_be_bytes(
+            &hex::decode(N).unwrap(),
+            (512 + big::MODBYTES - 1) / big::MODBYTES,
+        );
+        let mut encoded = vec![0; 512];
+        let mut blinded = vec![0; 512];
+        let mut inv = vec![0; 512];
+        let mut bsig = vec![0; 512];
+        let mut sig = vec![0; 512];
+
+        for v in VECTORS.iter() {
+            let m = prepare(v.suite, &mut rng, &msg);
+            assert_eq!(hex::encode(&m), v.prepared);
+            let salt = hex::decode(v.salt).unwrap();
+            assert!(rsa::pss_encode(
+                rsa::SHA384,
+                rsa::SHA384,
+                &m,
+                &salt,
+                4095,
+                &mut encoded
+            ));
+            assert_eq!(hex::encode(&encoded), v.encoded);
+
+            // the blinding factor is the inverse of INV
+            let mut r = FF::from_be_bytes(&hex::decode(v.inv).unwrap(), n.getlen());
+            r.invmodp(&n);
+            let mut rb = vec![0; 512];
+            r.to_be_bytes(&mut rb);
+            assert!(blind_with(&pbc, &m, &salt, &rb, &mut blinded, &mut inv));
+            assert_eq!(hex::encode(&blinded), v.blinded);
+            assert_eq!(hex::encode(&inv), v.inv);
+            assert!(blind_sign(&prv, &mut rng, &blinded, &mut bsig));
+            assert_eq!(hex::encode(&bsig), v.bsig);
+            assert!(finalize(v.suite, &pbc, &m, &bsig, &inv, &mut sig));
+            assert_eq!(hex::encode(&sig), v.sig);
+            assert!(verify(v.suite, &pbc, &m, &sig));
+        }
+    }
+
+    #[test]
+    fn test_rsabssa() {
+        let (prv, pbc) = keys();
+        let mut rng = create_rng();
+        let mut blinded = vec![0; 512];
+        let mut inv = vec![0; 512];
+        let mut bsig = vec![0; 512];
+        let mut sig = vec![0; 512];
+
+        for suite in 0..4 {
+            let m = prepare(suite, &mut rng, b"token");
+            let randomized = suite == SHA384_PSS_RANDOMIZED || suite == SHA384_PSSZERO_RANDOMIZED;
+            assert_eq!(m.len(), if randomized { 37 } else { 5 });
+            assert_eq!(&m[m.len() - 5..], b"token");
+
+            assert!(blind(suite, &pbc, &mut rng, &m, &mut blinded, &mut inv));
+            assert!(blind_sign(&prv, &mut rng, &blinded, &mut bsig));
+            assert!(finalize(suite, &pbc, &m, &bsig, &inv, &mut sig));
+            assert!(verify(suite, &pbc, &m, &sig));
+            assert!(!verify(suite, &pbc, b"token!", &sig));
+
+            // PSS and PSSZERO signatures are not interchangeable
+            let other = match suite {
+                SHA384_PSS_RANDOMIZED => SHA384_PSSZERO_RANDOMIZED,
+                SHA384_PSSZERO_RANDOMIZED => SHA384_PSS_RANDOMIZED,
+                SHA384_PSS_DETERMINISTIC => SHA384_PSSZERO_DETERMINISTIC,
+                _ => SHA384_PSS_DETERMINISTIC,
+            };
+            assert!(!verify(other, &pbc, &m, &sig));
+
+            // A blind signature for another message does not finalize
+            assert!(!finalize(suite, &pbc, b"other", &bsig, &inv, &mut sig));
+            assert!(sig.iter().all(|&x| x == 0));
+        }
+
+        let m = prepare(SHA384_PSS_RANDOMIZED, &mut rng, b"token");
+        assert!(!blind(4, &pbc, &mut rng, &m, &mut blinded, &mut inv));
+        assert!(!blind(
+            0,
+            &pbc,
+            &mut rng,
+            &m,
+            &mut blinded[0..511],
+            &mut inv
+        ));
+        let (n, _) = rsa::public_key_components(&pbc);
+        assert!(!blind_sign(&prv, &mut rng, &n, &mut bsig));
+        assert!(!blind_sign(&prv, &mut rng, &n[1..], &mut bsig));
+        assert!(!finalize(0, &pbc, &m, &n, &inv, &mut sig));
+    }
+}
@@ -0,0 +1,386 @@
+/*
+Licensed to the Apache Software Foundation (ASF) under one
+or more contributor license agreements.  See the NOTICE file
+distributed with this work for additional information
+regarding copyright ownership.  The ASF licenses this file
+to you under the Apache License, Version 2.0 (the
+"License"); you may not use this file except in compliance
+with the License.  You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing,
+software distributed under the License is distributed on an
+"AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+KIND, either express or implied.  See the License for the
+specific language governing permissions and limitations
+under the License.
+*/
+
+/* BIP-340 Schnorr signatures over secp256k1 */
+
+use super::big;
+use super::big::Big;
+use super::ecp::ECP;
+use super::rom;
+
+use hash256::HASH256;
+use rand::RAND;
+
+pub const ERROR: isize = -3;
+pub const INVALID: isize = -4;
+pub const EFS: usize = big::MODBYTES;
+pub const EGS: usize = big::MODBYTES;
+/// Size of a BIP-340 signature, R.x || s
+pub const SIGNATURE_SIZE: usize = 2 * EFS;
+
+/// Tagged hash of BIP-340: SHA256(SHA256(tag) || SHA256(tag) || m1 || m2 || ...)
+pub fn tagged_hash(tag: &[u8], m: &[&[u8]]) -> [u8; 32] {
+    let mut h = HASH256::new();
+    h.process_array(tag);
+    let th = h.hash();
+    h.process_array(&th);
+    h.process_array(&th);
+    for x in m {
+        h.process_array(x);
+    }
+    h.hash()
+}
+
+/// Recover the point with even y for an x-only public key. Returns infinity if there is none
+#[allow(non_snake_case)]
+pub fn lift_x(pk: &[u8]) -> ECP {
+    let p = Big::new_ints(&rom::MODULUS);
+    let x = Big::frombytes(&pk[0..EFS]);
+    if Big::comp(&x, &p) >= 0 {
+        return ECP::new();
+    }
+    ECP::new_bigint(&x, 0)
+}
+
+/// Reduce a hash output mod the group order
+fn hash_to_scalar(h: &[u8]) -> Big {
+    let r = Big::new_ints(&rom::CURVE_ORDER);
+    let mut e = Big::frombytes(h);
+    e.rmod(&r);
+    e
+}
+
+/// Generate the x-only public key PK of secret key SK
+#[allow(non_snake_case)]
+pub fn public_key(sk: &[u8], pk: &mut [u8]) -> isize {
+    let r = Big::new_ints(&rom::CURVE_ORDER);
+    let d = Big::frombytes(&sk[0..EGS]);
+    if d.iszilch() || Big::comp(&d, &r) >= 0 {
+        return ERROR;
+    }
+    let P = ECP::generator().mul(&d);
+    P.getx().tobytes(&mut pk[0..EFS]);
+    0
+}
+
+/// BIP-340 signature SIG of message M under secret key SK, using 32 bytes of auxiliary randomness AUX
+#[allow(non_snake_case)]
+pub fn sign(sk: &[u8], m: &[u8], aux: &[u8], sig: &mut [u8]) -> isize {
+    let r = Big::new_ints(&rom::CURVE_ORDER);
+    let mut d = Big::frombytes(&sk[0..EGS]);
+    if d.iszilch() || Big::comp(&d, &r) >= 0 || aux.len() != 32 {
+        return ERROR;
+    }
+    let G = ECP::generator();
+    let P = G.mul(&d);
+    if P.gets() != 0 {
+        d = Big::modneg(&d, &r);
+    }
+    let mut px: [u8; EFS] = [0; EFS];
+    P.getx().tobytes(&mut px);
+
+    let mut t: [u8; EGS] = [0; EGS];
+    d.tobytes(&mut t);
+    let ha = tagged_hash(b"BIP0340/aux", &[aux]);
+    for i in 0..EGS {
+        t[i] ^= ha[i];
+    }
+
+    let mut k = hash_to_scalar(&tagged_hash(b"BIP0340/nonce", &[&t, &px, m]));
+    if k.iszilch() {
+        return ERROR;
+    }
+    let R = G.mul(&k);
+    if R.gets() != 0 {
+        k = Big::modneg(&k, &r);
+    }
+    let mut rx: [u8; EFS] = [0; EFS];
+    R.getx().tobytes(&mut rx);
+
+    let e = hash_to_scalar(&tagged_hash(b"BIP0340/challenge", &[&rx, &px, m]));
+
+    let mut s = Big::modmul(&e, &d, &r);
+    s.add(&k);
+    s.norm();
+    s.rmod(&r);
+
+    sig[0..EFS].copy_from_slice(&rx);
+    s.tobytes(&mut sig[EFS..2 * EFS]);
+
+    for b in t.iter_mut() {
+        *b = 0;
+    }
+    d.zero();
+    k.zero();
+    0
+}
+
+/// BIP-340 signature as sign, drawing the auxiliary randomness from RNG
+pub fn sign_rng(rng: &mut RAND, sk: &[u8], m: &[u8], sig: &mut [u8]) -> isize {
+    let mut aux: [u8; 32] = [0; 32];
+    for b in aux.iter_mut() {
+        *b = rng.getbyte();
+    }
+    sign(sk, m, &aux, sig)
+}
+
+/// Verify BIP-340 signature SIG on message M under x-only public key PK
+#[allow(non_snake_case)]
+pub fn verify(pk: &[u8], m: &[u8], sig: &[u8]) -> isize {
+    if pk.len() != EFS || sig.len() != SIGNATURE_SIZE {
+        return INVALID;
+    }
+    let P = lift_x(pk);
+    if P.is_infinity() {
+        return INVALID;
+    }
+    let p = Big::new_ints(&rom::MODULUS);
+    let r = Big::new_ints(&rom::CURVE_ORDER);
+    let rx = Big::frombytes(&sig[0..EFS]);
+    if Big::comp(&rx, &p) >= 0 {
+        return INVALID;
+    }
+    let s = Big::frombytes(&sig[EFS..2 * EFS]);
+    if Big::comp(&s, &r) >= 0 {
+        return INVALID;
+    }
+
+    let e = hash_to_scalar(&tagged_hash(b"BIP0340/challenge", &[&sig[0..EFS], pk, m]));
+    let ne = Big::modneg(&e, &r);
+
+    // R = s.G - e.P
+    let R = P.mul2(&ne, &ECP::generator(), &s);
+    if R.is_infinity() || R.gets() != 0 || Big::comp(&R.getx(), &rx) != 0 {
+        return INVALID;
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::create_rng;
+
+    // From https://github.com/bitcoin/bips/blob/master/bip-0340/test-vectors.csv
+    // Format: (secret key, public key, aux rand, message, signature, result)
+    const VECTORS: [(&str, &str, &str, &str, &str, bool); 19] = [
+        (
+            "0000000000000000000000000000000000000000000000000000000000000003",
+            "F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "E907831F80848D1069A5371B402410364BDF1C5F8307B0084C55F1CE2DCA821525F66A4A85EA8B71E482A74F382D2CE5EBEEE8FDB2172F477DF4900D310536C0",
+            true,
+        ),
+        (
+            "B7E151628AED2A6ABF7158809CF4F3C762E7160F38B4DA56A784D9045190CFEF",
+            "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "6896BD60EEAE296DB48A229FF71DFE071BDE413E6D43F917DC8DCF8C78DE33418906D11AC976ABCCB20B091292BFF4EA897EFCB639EA871CFA95F6DE339E4B0A",
+            true,
+        ),
+        (
+            "C90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74020BBEA63B14E5C9",
+            "DD308AFEC5777E13121FA72B9CC1B7CC0139715309B086C960E18FD969774EB8",
+            "C87AA53824B4D7AE2EB035A2B5BBBCCC080E76CDC6D1692C4B0B62D798E6D906",
+            "7E2D58D8B3BCDF1ABADEC7829054F90DDA9805AAB56C77333024B9D0A508B75C",
+            "5831AAEED7B44BB74E5EAB94BA9D4294C49BCF2A60728D8B4C200F50DD313C1BAB745879A5AD954A72C45A91C3A51D3C7ADEA98D82F8481E0E1E03674A6F3FB7",
+            true,
+        ),
+        (
+            "0B432B2677937381AEF05BB02A66ECD012773062CF3FA2549E44F58ED2401710",
+            "25D1DFF95105F5253C4022F628A996AD3A0D95FBF21D468A1B33F8C160D8F517",
+            "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+            "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+            "7EB0509757E246F19449885651611CB965ECC1A187DD51B64FDA1EDC9637D5EC97582B9CB13DB3933705B32BA982AF5AF25FD78881EBB32771FC5922EFC66EA3",
+            true,
+        ),
+        (
+            "",
+            "D69C3509BB99E412E68B0FE8544E72837DFA30746D8BE2AA65975F29D22DC7B9",
+            "",
+            "4DF3C3F68FCC83B27E9D42C90431A72499F17875C81A599B566C9889B9696703",
+            "00000000000000000000003B78CE563F89A0ED9414F5AA28AD0D96D6795F9C6376AFB1548AF603B3EB45C9F8207DEE1060CB71C04E80F593060B07D28308D7F4",
+            true,
+        ),
+        // public key not on the curve
+        (
+            "",
+            "EEFDEA4CDB677750A420FEE807EACF21EB9898AE79B9768766E4FAA04A2D4A34",
+            "",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E17776969E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+            false,
+        ),
+        // has_even_y(R) is false
+        (
+            "",
+            "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "FFF97BD5755EEEA420453A14355235D382F6472F8568A18B2F057A14602975563CC27944640AC607CD107AE10923D9EF7A73C643E166BE5EBEAFA34B1AC553E2",
+            false,
+        ),
+        // negated message
+        (
+            "",
+            "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "1FA62E331EDBC21C394792D2AB1100A7B432B013DF3F6FF4F99FCB33E0E1515F28890B3EDB6E7189B630448B515CE4F8622A954CFE545735AAEA5134FCCDB2BD",
+            false,
+        ),
+        // negated s value
+        (
+            "",
+            "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E177769961764B3AA9B2FFCB6EF947B6887A226E8D7C93E00C5ED0C1834FF0D0C2E6DA6",
+            false,
+        ),
+        // sG - eP is infinite
+        (
+            "",
+            "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "0000000000000000000000000000000000000000000000000000000000000000123DDA8328AF9C23A94C1FEECFD123BA4FB73476F0D594DCB65C6425BD186051",
+            false,
+        ),
+        (
+            "",
+            "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "00000000000000000000000000000000000000000000000000000000000000017615FBAF5AE28864013C099742DEADB4DBA87F11AC6754F93780D5A1837CF197",
+            false,
+        ),
+        // sig[0:32] is not an X coordinate on the curve
+        (
+            "",
+            "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "4A298DACAE57395A15D0795DDBFD1DCB564DA82B0F269BC70A74F8220429BA1D69E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+            false,
+        ),
+        // sig[0:32] is equal to field size
+        (
+            "",
+            "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F69E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+            false,
+        ),
+        // sig[32:64] is equal to curve order
+        (
+            "",
+            "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E177769FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141",
+            false,
+        ),
+        // public key is not a valid X coordinate because it exceeds the field size
+        (
+            "",
+            "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC30",
+            "",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E17776969E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+            false,
+        ),
+        // variable length messages
+        (
+            "0340034003400340034003400340034003400340034003400340034003400340",
+            "778CAA53B4393AC467774D09497A87224BF9FAB6F6E68B23086497324D6FD117",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "",
+            "71535DB165ECD9FBBC046E5FFAEA61186BB6AD436732FCCC25291A55895464CF6069CE26BF03466228F19A3A62DB8A649F2D560FAC652827D1AF0574E427AB63",
+            true,
+        ),
+        (
+            "0340034003400340034003400340034003400340034003400340034003400340",
+            "778CAA53B4393AC467774D09497A87224BF9FAB6F6E68B23086497324D6FD117",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "11",
+            "08A20A0AFEF64124649232E0693C583AB1B9934AE63B4C3511F3AE1134C6A303EA3173BFEA6683BD101FA5AA5DBC1996FE7CACFC5A577D33EC14564CEC2BACBF",
+            true,
+        ),
+        (
+            "0340034003400340034003400340034003400340034003400340034003400340",
+            "778CAA53B4393AC467774D09497A87224BF9FAB6F6E68B23086497324D6FD117",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0102030405060708090A0B0C0D0E0F1011",
+            "5130F39A4059B43BC7CAC09A19ECE52B5D8699D1A71E3C52DA9AFDB6B50AC370C4A482B77BF960F8681540E25B6771ECE1E5A37FD80E5A51897C5566A97EA5A5",
+            true,
+        ),
+        (
+            "0340034003400340034003400340034003400340034003400340034003400340",
+            "778CAA53B4393AC467774D09497A87224BF9FAB6F6E68B23086497324D6FD117",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "99999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999",
+            "403B12B0D8555A344175EA7EC746566303321E5DBFA8BE6F091635163ECA79A8585ED3E3170807E7C03B720FC54C7B23897FCBA0E9D0B4A06894CFD249F22367",
+            true,
+        ),
+    ];
+
+    #[test]
+    fn test_bip340_vectors() {
+        for (i, v) in VECTORS.iter().enumerate() {
+            let pk = hex::decode(v.1).unwrap();
+            let m = hex::decode(v.3).unwrap();
+            let sig = hex::decode(v.4).unwrap();
+
+            if !v.0.is_empty() {
+                let sk = hex::decode(v.0).unwrap();
+                let aux = hex::decode(v.2).unwrap();
+                let mut opk: [u8; EFS] = [0; EFS];
+                let mut osig: [u8; SIGNATURE_SIZE] = [0; SIGNATURE_SIZE];
+                assert_eq!(public_key(&sk, &mut opk), 0);
+                assert_eq!(pk, opk.to_vec(), "public key, vector {}", i);
+                assert_eq!(sign(&sk, &m, &aux, &mut osig), 0);
+                assert_eq!(sig, osig.to_vec(), "signature, vector {}", i);
+            }
+
+            let expected = if v.5 { 0 } else { INVALID };
+            assert_eq!(verify(&pk, &m, &sig), expected, "verify, vector {}", i);
+        }
+    }
+
+    #[test]
+    fn test_sign_rng() {
+        let mut rng = create_rng();
+
+        let mut sk: [u8; EGS] = [0; EGS];
+        let mut pk: [u8; EFS] = [0; EFS];
+        let mut sig: [u8; SIGNATURE_SIZE] = [0; SIGNATURE_SIZE];
+        let r = Big::new_ints(&rom::CURVE_ORDER);
+        for _ in 0..4 {
+            Big::randomnum(&r, &mut rng).tobytes(&mut sk);
+            assert_eq!(public_key(&sk, &mut pk), 0);
+            assert_eq!(sign_rng(&mut rng, &sk, b"taproot", &mut sig), 0);
+            assert_eq!(verify(&pk, b"taproot", &sig), 0);
+            assert_eq!(verify(&pk, b"taproos", &sig), INVALID);
+        }
+    }
+}
@@ -0,0 +1,246 @@
+/*
+Licensed to the Apache Software Foundation (ASF) under one
+or more contributor license agreements.  See the NOTICE file
+distributed with this work for additional information
+regarding copyright ownership.  The ASF licenses this file
+to you under the Apache License, Version 2.0 (the
+"License"); you may not use this file except in compliance
+with the License.  You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing,
+software distributed under the License is distributed on an
+"AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+KIND, either express or implied.  See the License for the
+specific language governing permissions and limitations
+under the License.
+*/
+
+/* SEC1 v2 section 2.3 / X9.62 elliptic curve point encoding. For Edwards curves the
+same octet strings are used, with y in place of the Weierstrass y coordinate */
+
+use super::big;
+use super::big::Big;
+use super::ecp::ECP;
+use super::ecp::{CurveType, CURVETYPE};
+use super::rom;
+
+pub use types::{PointError, PointFormat};
+
+pub const EFS: usize = big::MODBYTES;
+
+pub const INFINITY: u8 = 0x00;
+pub const COMPRESSED_EVEN: u8 = 0x02;
+pub const COMPRESSED_ODD: u8 = 0x03;
+pub const UNCOMPRESSED: u8 = 0x04;
+pub const HYBRID_EVEN: u8 = 0x06;
+pub const HYBRID_ODD: u8 = 0x07;
+
+/// Length of a finite point encoded in format F
+pub fn encoded_length(f: PointFormat) -> usize {
+    match f {
+        PointFormat::Compressed => EFS + 1,
+        _ => 2 * EFS + 1,
+    }
+}
+
+/// Encode point P in format F. The point at infinity is the single byte 0x00
+#[allow(non_snake_case)]
+pub fn encode(P: &ECP, f: PointFormat) -> Vec<u8> {
+    if P.is_infinity() {
+        return vec![INFINITY];
+    }
+    let mut x = P.getx();
+    let mut y = P.gety();
+    let odd = y.parity() as u8;
+    let mut b = vec![0; encoded_length(f)];
+    x.tobytes(&mut b[1..EFS + 1]);
+    match f {
+        PointFormat::Compressed => b[0] = COMPRESSED_EVEN | odd,
+        PointFormat::Uncompressed => b[0] = UNCOMPRESSED,
+        PointFormat::Hybrid => b[0] = HYBRID_EVEN | odd,
+    }
+    if f != PointFormat::Compressed {
+        y.tobytes(&mut b[EFS + 1..]);
+    }
+    b
+}
+
+/// Read a coordinate, rejecting values not less than the modulus
+fn coordinate(b: &[u8]) -> Result<Big, PointError> {
+    let c = Big::frombytes(b);
+    if Big::comp(&c, &Big::new_ints(&rom::MODULUS)) >= 0 {
+        return Err(PointError::NonCanonical);
+    }
+    Ok(c)
+}
+
+/// Decode and fully validate a point. Unlike ECP::frombytes, every malformed or
+/// off-curve input is reported, and the point at infinity is rejected.
+/// Membership of the prime order subgroup is not checked on curves with a cofactor
+#[allow(non_snake_case)]
+pub fn decode(b: &[u8]) -> Result<ECP, PointError> {
+    if CURVETYPE == CurveType::Montgomery {
+        return Err(PointError::Unsupported);
+    }
+    if b.is_empty() {
+        return Err(PointError::InvalidLength);
+    }
+    let f = match b[0] {
+        INFINITY => {
+            if b.len() != 1 {
+                return Err(PointError::InvalidLength);
+            }
+            return Err(PointError::Infinity);
+        }
+        COMPRESSED_EVEN | COMPRESSED_ODD => PointFormat::Compressed,
+        UNCOMPRESSED => PointFormat::Uncompressed,
+        HYBRID_EVEN | HYBRID_ODD => PointFormat::Hybrid,
+        _ => return Err(PointError::InvalidEncoding),
+    };
+    if b.len() != encoded_length(f) {
+        return Err(PointError::InvalidLength);
+    }
+    let x = coordinate(&b[1..EFS + 1])?;
+    // Only the Edwards identity (0,1) has an affine encoding. Compressed, its y is odd
+    let mut y_is_one = b[0] & 1 == 1;
+    let P = if f == PointFormat::Compressed {
+        ECP::new_bigint(&x, (b[0] & 1) as isize)
+    } else {
+        let y = coordinate(&b[EFS + 1..])?;
+        if f == PointFormat::Hybrid && y.parity() != (b[0] & 1) as isize {
+            return Err(PointError::InvalidEncoding);
+        }
+        y_is_one = Big::comp(&y, &Big::new_int(1)) == 0;
+        ECP::new_bigs(&x, &y)
+    };
+    if P.is_infinity() {
+        if CURVETYPE == CurveType::Edwards && x.iszilch() && y_is_one {
+            return Err(PointError::Infinity);
+        }
+        return Err(PointError::NotOnCurve);
+    }
+    Ok(P)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::create_rng;
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_roundtrip() {
+        if CURVETYPE == CurveType::Montgomery {
+            return;
+        }
+        let mut rng = create_rng();
+        let r = Big::new_ints(&rom::CURVE_ORDER);
+        let G = ECP::generator();
+        for _ in 0..10 {
+            let P = G.mul(&Big::randomnum(&r, &mut rng));
+            for f in [
+                PointFormat::Compressed,
+                PointFormat::Uncompressed,
+                PointFormat::Hybrid,
+            ]
+            .iter()
+            {
+                let b = encode(&P, *f);
+                assert_eq!(b.len(), encoded_length(*f));
+                let Q = decode(&b).unwrap();
+                assert_eq!(Q, P);
+                // Decoded coordinates must be fully reduced
+                assert_eq!(
+                    encode(&Q, PointFormat::Uncompressed),
+                    encode(&P, PointFormat::Uncompressed)
+                );
+            }
+
+            // Agrees with ECP::tobytes
+            let mut t = vec![0; 2 * EFS + 1];
+            P.tobytes(&mut t, false);
+            assert_eq!(encode(&P, PointFormat::Uncompressed), t);
+            P.tobytes(&mut t, true);
+            assert_eq!(encode(&P, PointFormat::Compressed), &t[0..EFS + 1]);
+        }
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_rejections() {
+        if CURVETYPE == CurveType::Montgomery {
+            assert_eq!(decode(&[0]), Err(PointError::Unsupported));
+            return;
+        }
+        let G = ECP::generator();
+        let mut P = G;
+        P.dbl();
+        assert_eq!(encode(&ECP::new(), PointFormat::Compressed), vec![0]);
+        assert_eq!(decode(&[0]), Err(PointError::Infinity));
+        assert_eq!(decode(&[0, 0]), Err(PointError::InvalidLength));
+        assert_eq!(decode(&[]), Err(PointError::InvalidLength));
+        if CURVETYPE == CurveType::Edwards {
+            let mut t = vec![0; 2 * EFS + 1];
+            t[0] = UNCOMPRESSED;
+            t[2 * EFS] = 1;
+            assert_eq!(decode(&t), Err(PointError::Infinity));
+            let mut t = vec![0; EFS + 1];
+            t[0] = COMPRESSED_ODD;
+            assert_eq!(decode(&t), Err(PointError::Infinity));
+        }
+
+        let u = encode(&P, PointFormat::Uncompressed);
+        let c = encode(&P, PointFormat::Compressed);
+        let h = encode(&P, PointFormat::Hybrid);
+        assert_eq!(decode(&u[0..2 * EFS]), Err(PointError::InvalidLength));
+        assert_eq!(decode(&c[0..EFS]), Err(PointError::InvalidLength));
+        let mut t = u.clone();
+        t.push(0);
+        assert_eq!(decode(&t), Err(PointError::InvalidLength));
+
+        // Unknown tags
+        for tag in [0x01, 0x05, 0x08, 0xff].iter() {
+            let mut t = u.clone();
+            t[0] = *tag;
+            assert_eq!(decode(&t), Err(PointError::InvalidEncoding));
+        }
+
+        // Hybrid with the wrong parity
+        let mut t = h.clone();
+        t[0] ^= 1;
+        assert_eq!(decode(&t), Err(PointError::InvalidEncoding));
+
+        // Off the curve
+        let mut t = u.clone();
+        t[2 * EFS] ^= 1;
+        assert_eq!(decode(&t), Err(PointError::NotOnCurve));
+
+        // Coordinates equal to the modulus, and x + p if it fits
+        let mut p: [u8; EFS] = [0; EFS];
+        Big::new_ints(&rom::MODULUS).tobytes(&mut p);
+        let mut t = u.clone();
+        t[1..EFS + 1].copy_from_slice(&p);
+        assert_eq!(decode(&t), Err(PointError::NonCanonical));
+        let mut t = u.clone();
+        t[EFS + 1..].copy_from_slice(&p);
+        assert_eq!(decode(&t), Err(PointError::NonCanonical));
+        let mut t = c.clone();
+        t[1..].copy_from_slice(&p);
+        assert_eq!(decode(&t), Err(PointError::NonCanonical));
+
+        // Compressed x with no point on the curve
+        let mut x = P.getx();
+        loop {
+            x.inc(1);
+            x.norm();
+            let mut t = c.clone();
+            x.tobytes(&mut t[1..]);
+            if ECP::new_bigint(&x, 0).is_infinity() {
+                assert_eq!(decode(&t), Err(PointError::NotOnCurve));
+                break;
+            }
+        }
+    }
+}
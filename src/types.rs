@@ -42,3 +42,30 @@ pub enum SignOfX {
     PositiveX,
     NegativeX,
 }
+
+/// SEC1 / X9.62 point encodings
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PointFormat {
+    Compressed,
+    Uncompressed,
+    Hybrid,
+}
+
+/// Reasons a SEC1 point encoding is rejected
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PointError {
+    /// Length does not match the leading byte
+    InvalidLength,
+    /// Unknown leading byte, or hybrid parity bit inconsistent with y
+    InvalidEncoding,
+    /// A coordinate is not reduced modulo p
+    NonCanonical,
+    /// The coordinates do not satisfy the curve equation
+    NotOnCurve,
+    /// The point at infinity
+    Infinity,
+    /// A point of small order, lost on multiplying by the cofactor
+    SmallOrder,
+    /// Not available for this curve type
+    Unsupported,
+}
@@ -0,0 +1,269 @@
+/*
+Licensed to the Apache Software Foundation (ASF) under one
+or more contributor license agreements.  See the NOTICE file
+distributed with this work for additional information
+regarding copyright ownership.  The ASF licenses this file
+to you under the Apache License, Version 2.0 (the
+"License"); you may not use this file except in compliance
+with the License.  You may obtain a copy of the License at
+
+  http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing,
+software distributed under the License is distributed on an
+"AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+KIND, either express or implied.  See the License for the
+specific language governing permissions and limitations
+under the License.
+*/
+
+/* RFC 7748 X25519 and X448 key agreement. Compiled as c25519::x25519 and goldilocks::x448,
+X448 working on curve448, the Montgomery form of goldilocks */
+
+use super::big;
+use super::big::Big;
+use super::fp::FP;
+use super::rom;
+
+use rand::RAND;
+
+pub const INVALID_PUBLIC_KEY: isize = -2;
+/// Size of a scalar and of a u-coordinate
+pub const EFS: usize = big::MODBYTES;
+pub const EGS: usize = big::MODBYTES;
+
+const X448: bool = rom::MODBITS == 448;
+/// (A-2)/4 for curve25519 and curve448
+const A24: isize = if X448 { 39081 } else { 121665 };
+/// Base point u-coordinate
+const BASE: u8 = if X448 { 5 } else { 9 };
+
+/// Decode a little-endian u-coordinate, masking the unused top bit of X25519 and
+/// accepting non-canonical values
+fn decode_u(u: &[u8]) -> FP {
+    let mut t: [u8; EFS] = [0; EFS];
+    for (i, c) in t.iter_mut().rev().enumerate() {
+        *c = u[i];
+    }
+    if !X448 {
+        t[0] &= 0x7f;
+    }
+    let mut x = Big::frombytes(&t);
+    x.rmod(&Big::new_ints(&rom::MODULUS));
+    FP::new_big(&x)
+}
+
+/// Clamp a little-endian scalar, returning it big-endian for bit access
+fn decode_scalar(k: &[u8]) -> Big {
+    let mut t: [u8; EGS] = [0; EGS];
+    for (i, c) in t.iter_mut().rev().enumerate() {
+        *c = k[i];
+    }
+    if X448 {
+        t[EGS - 1] &= 252;
+        t[0] |= 128;
+    } else {
+        t[EGS - 1] &= 248;
+        t[0] &= 127;
+        t[0] |= 64;
+    }
+    Big::frombytes(&t)
+}
+
+/// The X25519 or X448 function of RFC 7748. R = K.U, with all values little-endian.
+/// Returns INVALID_PUBLIC_KEY if the result is all zero, as happens for small order U
+#[allow(non_snake_case)]
+pub fn scalar_mult(k: &[u8], u: &[u8], r: &mut [u8]) -> isize {
+    let mut e = decode_scalar(k);
+    let x1 = decode_u(u);
+    let mut x2 = FP::new_int(1);
+    let mut z2 = FP::new();
+    let mut x3 = FP::new_copy(&x1);
+    let mut z3 = FP::new_int(1);
+    let mut swap = 0;
+
+    for t in (0..rom::MODBITS).rev() {
+        let kt = e.bit(t);
+        swap ^= kt;
+        x2.cswap(&mut x3, swap);
+        z2.cswap(&mut z3, swap);
+        swap = kt;
+
+        let mut A = FP::new_copy(&x2);
+        A.add(&z2);
+        A.norm();
+        let mut AA = FP::new_copy(&A);
+        AA.sqr();
+        let mut B = FP::new_copy(&x2);
+        B.sub(&z2);
+        B.norm();
+        let mut BB = FP::new_copy(&B);
+        BB.sqr();
+        let mut E = FP::new_copy(&AA);
+        E.sub(&BB);
+        E.norm();
+        let mut C = FP::new_copy(&x3);
+        C.add(&z3);
+        C.norm();
+        let mut D = FP::new_copy(&x3);
+        D.sub(&z3);
+        D.norm();
+        D.mul(&A);
+        C.mul(&B);
+
+        x3.copy(&D);
+        x3.add(&C);
+        x3.norm();
+        x3.sqr();
+        z3.copy(&D);
+        z3.sub(&C);
+        z3.norm();
+        z3.sqr();
+        z3.mul(&x1);
+
+        x2.copy(&AA);
+        x2.mul(&BB);
+        z2.copy(&E);
+        z2.imul(A24);
+        z2.add(&AA);
+        z2.norm();
+        z2.mul(&E);
+    }
+    x2.cswap(&mut x3, swap);
+    z2.cswap(&mut z3, swap);
+    e.zero();
+
+    z2.inverse();
+    x2.mul(&z2);
+    x2.reduce();
+
+    let mut t: [u8; EFS] = [0; EFS];
+    x2.redc().tobytes(&mut t);
+    for (i, c) in t.iter().rev().enumerate() {
+        r[i] = *c;
+    }
+    if x2.iszilch() {
+        return INVALID_PUBLIC_KEY;
+    }
+    0
+}
+
+/// Generate a key pair. If RNG is None then the private key S is provided externally,
+/// otherwise it is generated randomly. W = S.B
+pub fn key_pair_generate(rng: Option<&mut RAND>, s: &mut [u8], w: &mut [u8]) -> isize {
+    if let Some(x) = rng {
+        for b in s.iter_mut().take(EGS) {
+            *b = x.getbyte();
+        }
+    }
+    let mut base: [u8; EFS] = [0; EFS];
+    base[0] = BASE;
+    scalar_mult(s, &base, w)
+}
+
+/// Shared secret Z from private key S and peer public key W
+pub fn shared_secret(s: &[u8], w: &[u8], z: &mut [u8]) -> isize {
+    scalar_mult(s, w, z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rand::create_rng;
+
+    // From https://tools.ietf.org/html/rfc7748#section-5.2
+    // Format: (scalar, u, result)
+    const X25519_VECTORS: [(&str, &str, &str); 2] = [
+        (
+            "a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4",
+            "e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c",
+            "c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552",
+        ),
+        (
+            "4b66e9d4d1b4673c5ad22691957d6af5c11b6421e0ea01d42ca4169e7918ba0d",
+            "e5210f12786811d3f4b7959d0538ae2c31dbe7106fc03c3efc4cd549c715a493",
+            "95cbde9476e8907d7aade45cb4b873f88b595a68799fa152e6f8f7647aac7957",
+        ),
+    ];
+
+    const X448_VECTORS: [(&str, &str, &str); 2] = [
+        (
+            "3d262fddf9ec8e88495266fea19a34d28882acef045104d0d1aae121700a779c984c24f8cdd78fbff44943eba368f54b29259a4f1c600ad3",
+            "06fce640fa3487bfda5f6cf2d5263f8aad88334cbd07437f020f08f9814dc031ddbdc38c19c6da2583fa5429db94ada18aa7a7fb4ef8a086",
+            "ce3e4ff95a60dc6697da1db1d85e6afbdf79b50a2412d7546d5f239fe14fbaadeb445fc66a01b0779d98223961111e21766282f73dd96b6f",
+        ),
+        (
+            "203d494428b8399352665ddca42f9de8fef600908e0d461cb021f8c538345dd77c3e4806e25f46d3315c44e0a5b4371282dd2c8d5be3095f",
+            "0fbcc2f993cd56d3305b0b7d9e55d4c1a8fb5dbb52f8e9a1e9b6201b165d015894e56c4d3570bee52fe205e28a78b91cdfbde71ce8d157db",
+            "884a02576239ff7a2f2f63b2db6a9ff37047ac13568e1e30fe63c4a7ad1b3ee3a5700df34321d62077e63633c575c1c954514e99da7c179d",
+        ),
+    ];
+
+    // RFC 7748 iterated results after 1 and 1000 iterations
+    const X25519_ITERATED: [&str; 2] = [
+        "422c8e7a6227d7bca1350b3e2bb7279f7897b87bb6854b783c60e80311ae3079",
+        "684cf59ba83309552800ef566f2f4d3c1c3887c49360e3875f2eb94d99532c51",
+    ];
+    const X448_ITERATED: [&str; 2] = [
+        "3f482c8a9f19b01e6c46ee9711d9dc14fd4bf67af30765c2ae2b846a4d23a8cd0db897086239492caf350b51f833868b9bc2b3bca9cf4113",
+        "aa3b4749d55b9daf1e5b00288826c467274ce3ebbdd5c17b975e09d4af6c67cf10d087202db88286e2b79fceea3ec353ef54faa26e219f38",
+    ];
+
+    #[test]
+    fn test_vectors() {
+        let vectors = if X448 { &X448_VECTORS } else { &X25519_VECTORS };
+        for v in vectors.iter() {
+            let k = hex::decode(v.0).unwrap();
+            let u = hex::decode(v.1).unwrap();
+            let mut r: [u8; EFS] = [0; EFS];
+            assert_eq!(scalar_mult(&k, &u, &mut r), 0);
+            assert_eq!(hex::encode(&r[..]), v.2);
+        }
+    }
+
+    #[test]
+    fn test_iterated() {
+        let expected = if X448 {
+            &X448_ITERATED
+        } else {
+            &X25519_ITERATED
+        };
+        let mut k: [u8; EFS] = [0; EFS];
+        let mut u: [u8; EFS] = [0; EFS];
+        let mut r: [u8; EFS] = [0; EFS];
+        k[0] = BASE;
+        u[0] = BASE;
+        for i in 1..=1000 {
+            scalar_mult(&k, &u, &mut r);
+            u = k;
+            k = r;
+            if i == 1 {
+                assert_eq!(hex::encode(&k[..]), expected[0]);
+            }
+        }
+        assert_eq!(hex::encode(&k[..]), expected[1]);
+    }
+
+    #[test]
+    fn test_key_agreement() {
+        let mut rng = create_rng();
+
+        let mut s1: [u8; EGS] = [0; EGS];
+        let mut w1: [u8; EFS] = [0; EFS];
+        let mut s2: [u8; EGS] = [0; EGS];
+        let mut w2: [u8; EFS] = [0; EFS];
+        let mut z1: [u8; EFS] = [0; EFS];
+        let mut z2: [u8; EFS] = [0; EFS];
+        assert_eq!(key_pair_generate(Some(&mut rng), &mut s1, &mut w1), 0);
+        assert_eq!(key_pair_generate(Some(&mut rng), &mut s2, &mut w2), 0);
+        assert_eq!(shared_secret(&s1, &w2, &mut z1), 0);
+        assert_eq!(shared_secret(&s2, &w1, &mut z2), 0);
+        assert_eq!(z1, z2);
+
+        // u = 0 and u = 1 have small order
+        let mut w: [u8; EFS] = [0; EFS];
+        assert_eq!(shared_secret(&s1, &w, &mut z1), INVALID_PUBLIC_KEY);
+        w[0] = 1;
+        assert_eq!(shared_secret(&s1, &w, &mut z1), INVALID_PUBLIC_KEY);
+    }
+}